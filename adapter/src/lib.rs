@@ -0,0 +1,11 @@
+//! Between the Tien Len engine and the players.
+//!
+//! The engine only knows [`Action`](tienlen_engine::Action)s and
+//! [`Event`](tienlen_engine::Event)s. [`mirror`] keeps the replicated
+//! components in step with those events and returns the [`Notice`]s for the
+//! players, the server sends them over naia and a table on a device hands
+//! them straight to its UI.
+
+mod mirror;
+
+pub use mirror::{mirror, Countdowns, Notice};
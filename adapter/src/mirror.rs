@@ -0,0 +1,260 @@
+use naia_bevy_demo_shared::{
+    components::{Player, Table},
+    messages::{
+        AcceptPlayCard, AcceptPlayerReady, AcceptStartGame, BackToLobby, EndMatch, MatchResults,
+        PlayerMessage, Standing, UpdateTurn, WaitForStart,
+    },
+};
+use tienlen_engine::{Event, GameEngine};
+
+/// Seconds the players wait before a deal and between two matches
+#[derive(Clone, Copy, Debug)]
+pub struct Countdowns {
+    pub start: usize,
+    pub next_match: usize,
+}
+
+/// A message for everybody at the table about an engine event, unless it
+/// says otherwise
+pub enum Notice {
+    PlayerReady(AcceptPlayerReady),
+    WaitForStart(WaitForStart),
+    /// Only for the player at `pos`, it holds their hand
+    StartGame {
+        pos: usize,
+        message: AcceptStartGame,
+    },
+    PlayCard(AcceptPlayCard),
+    UpdateTurn(UpdateTurn),
+    PlayerMessage(PlayerMessage),
+    MatchResults(MatchResults),
+    EndMatch(EndMatch),
+    BackToLobby(BackToLobby),
+}
+
+fn player_at<'a>(players: &'a mut [&mut Player], pos: usize) -> Option<&'a mut Player> {
+    players
+        .iter_mut()
+        .find(|player| *player.pos == pos)
+        .map(|player| &mut **player)
+}
+
+fn set_next_active(players: &mut [&mut Player], pos: usize) {
+    for player in players.iter_mut() {
+        *player.active = *player.pos == pos;
+    }
+}
+
+/// Mirrors an event of `engine` into the players and the table everybody
+/// sees, and returns what to tell them. The server and a table on a device
+/// only differ in where the notices go
+pub fn mirror<'a>(
+    event: &Event,
+    engine: &GameEngine,
+    players: impl IntoIterator<Item = &'a mut Player>,
+    mut table: Option<&mut Table>,
+    countdowns: Countdowns,
+) -> Vec<Notice> {
+    let mut players: Vec<&mut Player> = players.into_iter().collect();
+
+    match event {
+        // The player is spawned where the event comes from, nothing to mirror
+        Event::PlayerJoined { .. } | Event::PlayerLeft { .. } => vec![],
+        Event::PlayerReady { pos, name } => {
+            let mut avatar = *pos;
+            if let Some(player) = player_at(&mut players, *pos) {
+                *player.ready = true;
+                avatar = *player.avatar;
+            }
+
+            vec![Notice::PlayerReady(AcceptPlayerReady {
+                name: name.clone(),
+                server_pos: *pos,
+                avatar,
+            })]
+        }
+        Event::SeatChanged { from, to } => {
+            if let Some(player) = player_at(&mut players, *from) {
+                *player.pos = *to;
+            }
+
+            vec![]
+        }
+        Event::TableSetUp => vec![Notice::WaitForStart(WaitForStart(countdowns.start))],
+        Event::MatchStarted {
+            hands,
+            active_player,
+        } => {
+            if let Some(table) = table.as_mut() {
+                table.new_match();
+            }
+            set_next_active(&mut players, *active_player);
+
+            for player in players.iter_mut() {
+                if let Some(hand) = hands.get(*player.pos) {
+                    *player.cards = hand.to_string();
+                }
+            }
+
+            engine
+                .seats()
+                .filter_map(|(pos, _)| {
+                    let cards = hands.get(pos)?.to_string();
+                    Some(Notice::StartGame {
+                        pos,
+                        message: AcceptStartGame {
+                            cards,
+                            active_player: *active_player,
+                        },
+                    })
+                })
+                .collect()
+        }
+        Event::CardsPlayed {
+            pos,
+            cards,
+            hand_left,
+            next_player,
+            run_out,
+        } => {
+            let cards = cards.to_string();
+
+            if let Some(table) = table.as_mut() {
+                *table.cards = cards.clone();
+                table.set_round(engine.round());
+            }
+            if let Some(player) = player_at(&mut players, *pos) {
+                *player.cards = hand_left.to_string();
+            }
+            set_next_active(&mut players, *next_player);
+
+            vec![Notice::PlayCard(AcceptPlayCard {
+                cur_player: *pos,
+                cards,
+                next_player: *next_player,
+                run_out_card: *run_out,
+            })]
+        }
+        Event::TurnChanged(next_player) => vec![Notice::UpdateTurn(UpdateTurn(*next_player))],
+        Event::TurnSkipped { pos, next_player } => {
+            if let Some(table) = table.as_mut() {
+                table.set_round(engine.round());
+            }
+            set_next_active(&mut players, *next_player);
+
+            vec![
+                Notice::UpdateTurn(UpdateTurn(*next_player)),
+                Notice::PlayerMessage(PlayerMessage(*pos, "skip".to_string())),
+            ]
+        }
+        Event::PlayerFinished { pos, score, .. } => {
+            if let Some(player) = player_at(&mut players, *pos) {
+                *player.score = *score;
+            }
+
+            vec![]
+        }
+        Event::MatchEnded { standings } => {
+            if let Some(table) = table.as_mut() {
+                table.new_match();
+            }
+
+            // The first of the standings went out first
+            for (place, standing) in standings.iter().enumerate() {
+                if let Some(player) = player_at(&mut players, standing.pos) {
+                    *player.matches += 1;
+                    if place == 0 {
+                        *player.wins += 1;
+                    }
+                }
+            }
+
+            let standings = standings
+                .iter()
+                .map(|standing| Standing {
+                    pos: standing.pos,
+                    points: standing.points,
+                    score: standing.score,
+                    cards_left: standing.hand_left.to_string(),
+                })
+                .collect();
+
+            vec![
+                Notice::MatchResults(MatchResults { standings }),
+                Notice::EndMatch(EndMatch(countdowns.next_match)),
+            ]
+        }
+        // The table itself goes where the event comes from
+        Event::BackToLobby => vec![Notice::BackToLobby(BackToLobby)],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use naia_bevy_demo_shared::components::hand::Hand;
+    use tienlen_engine::{Action, Standing as EngineStanding};
+
+    use super::*;
+
+    const COUNTDOWNS: Countdowns = Countdowns {
+        start: 3,
+        next_match: 5,
+    };
+
+    fn players() -> Vec<Player> {
+        vec![Player::new(0, "alice", 0), Player::new(1, "bob", 1)]
+    }
+
+    #[test]
+    fn test_match_started_tells_each_player_their_hand() {
+        let mut engine = GameEngine::default();
+        for name in ["alice", "bob"] {
+            engine
+                .apply(Action::Join {
+                    name: name.to_string(),
+                })
+                .unwrap();
+        }
+
+        let mut players = players();
+        let event = Event::MatchStarted {
+            hands: vec![Hand::from_str("3S,4S"), Hand::from_str("5H")],
+            active_player: 1,
+        };
+
+        let notices = mirror(&event, &engine, players.iter_mut(), None, COUNTDOWNS);
+
+        let hands: Vec<(usize, String)> = notices
+            .into_iter()
+            .filter_map(|notice| match notice {
+                Notice::StartGame { pos, message } => Some((pos, message.cards)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(hands, vec![(0, "3S,4S".to_string()), (1, "5H".to_string())]);
+        assert_eq!(*players[1].cards, "5H");
+        assert!(!*players[0].active);
+        assert!(*players[1].active);
+    }
+
+    #[test]
+    fn test_match_ended_counts_matches_and_wins() {
+        let engine = GameEngine::default();
+        let mut players = players();
+        let standing = |pos| EngineStanding {
+            pos,
+            points: 0,
+            score: 0,
+            hand_left: Hand::new(),
+        };
+        let event = Event::MatchEnded {
+            standings: vec![standing(1), standing(0)],
+        };
+
+        let notices = mirror(&event, &engine, players.iter_mut(), None, COUNTDOWNS);
+
+        assert!(matches!(notices[1], Notice::EndMatch(EndMatch(5))));
+        assert_eq!((*players[0].matches, *players[0].wins), (1, 0));
+        assert_eq!((*players[1].matches, *players[1].wins), (1, 1));
+    }
+}
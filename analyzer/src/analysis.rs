@@ -0,0 +1,378 @@
+use std::collections::{HashMap, HashSet};
+
+use naia_bevy_demo_shared::components::{
+    card::Card,
+    cards::{evaluate_combination, Cards, ThirteenCombination},
+    hand::Hand,
+};
+use serde::Serialize;
+use tienlen_engine::check_play;
+
+/// A player never holds more, it also keeps the subsets of a hand countable
+pub const MAX_CARDS: usize = 13;
+
+/// Some cards and the combination they make
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct Play {
+    pub cards: String,
+    pub combination: String,
+}
+
+impl Play {
+    fn new(hand: &Hand) -> Self {
+        Self {
+            cards: hand.to_string(),
+            combination: format!("{:?}", hand.get_combination()),
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct Partitions {
+    /// Every way to split the hand into combinations
+    pub total: u64,
+    /// Plays it takes at least to get rid of the hand
+    pub fewest: usize,
+    /// The splits with the fewest combinations first
+    pub listed: Vec<Vec<Play>>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Report {
+    pub hand: String,
+    /// What the whole hand makes, `None` when it is no combination
+    pub combination: Option<String>,
+    pub table: Option<Play>,
+    /// The plays the rules take, on the table or as the lead
+    pub responses: Vec<Play>,
+    pub partitions: Partitions,
+    /// 0 to 100
+    pub strength: u32,
+}
+
+/// Reads cards like `3S,4S,5H`, complaining about the ones it does not know
+pub fn parse_hand(input: &str) -> Result<Hand, String> {
+    let names: Vec<String> = input
+        .split(',')
+        .map(|name| name.trim().to_uppercase())
+        .collect();
+
+    let mut seen = HashSet::new();
+    for name in names.iter() {
+        if Card::from_str(name).is_err() {
+            return Err(format!("{:?} is not a card, try 3S, TD or 2H", name));
+        }
+        if !seen.insert(name) {
+            return Err(format!("{} is there twice", name));
+        }
+    }
+
+    let mut hand = Hand::from_str(&names.join(","));
+    hand.cards.sort();
+
+    Ok(hand)
+}
+
+/// Everything the analyzer has to say about `hand`, answering `table` or
+/// leading when there is nothing to beat
+pub fn analyze(hand: &Hand, table: Option<&Hand>, listed: usize) -> Result<Report, String> {
+    if hand.len() > MAX_CARDS {
+        return Err(format!("A hand has at most {} cards", MAX_CARDS));
+    }
+    if let Some(table) = table {
+        if !table.check_combination() {
+            return Err(format!("{} is no combination to beat", table));
+        }
+    }
+
+    let combos = combinations(hand);
+
+    let mut responses: Vec<Hand> = combos
+        .iter()
+        .map(|mask| pick(hand, *mask))
+        .filter(|play| check_play(table, play).is_ok())
+        .collect();
+    responses.sort_by(|a, b| a.len().cmp(&b.len()).then(a.cmp(b)));
+
+    let mut splitter = Splitter::new(&combos);
+    let all = full_mask(hand);
+    let fewest = splitter.fewest(all);
+    let partitions = Partitions {
+        total: splitter.count(all),
+        fewest,
+        listed: splitter
+            .list(all, listed)
+            .into_iter()
+            .map(|groups| {
+                groups
+                    .into_iter()
+                    .map(|mask| Play::new(&pick(hand, mask)))
+                    .collect()
+            })
+            .collect(),
+    };
+
+    let combination = match evaluate_combination(hand.cards()) {
+        ThirteenCombination::NonSense => None,
+        combination => Some(format!("{:?}", combination)),
+    };
+
+    Ok(Report {
+        hand: hand.to_string(),
+        combination,
+        table: table.map(Play::new),
+        responses: responses.iter().map(Play::new).collect(),
+        partitions,
+        strength: strength(hand, &combos, fewest),
+    })
+}
+
+fn full_mask(hand: &Hand) -> u16 {
+    ((1u32 << hand.len()) - 1) as u16
+}
+
+/// The cards of `hand` picked by the bits of `mask`
+fn pick(hand: &Hand, mask: u16) -> Hand {
+    let cards = hand
+        .cards
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| mask & (1 << index) != 0)
+        .map(|(_, card)| *card)
+        .collect();
+
+    Hand { cards }
+}
+
+/// Every subset of `hand` that makes a combination, as masks over its cards
+fn combinations(hand: &Hand) -> Vec<u16> {
+    (1..=full_mask(hand))
+        .filter(|mask| {
+            evaluate_combination(pick(hand, *mask).cards()) != ThirteenCombination::NonSense
+        })
+        .collect()
+}
+
+/// Splits a hand into combinations, always placing its lowest card left so
+/// every split comes up once
+struct Splitter<'a> {
+    combos: &'a [u16],
+    counts: HashMap<u16, u64>,
+    fewests: HashMap<u16, usize>,
+}
+
+impl<'a> Splitter<'a> {
+    fn new(combos: &'a [u16]) -> Self {
+        Self {
+            combos,
+            counts: HashMap::new(),
+            fewests: HashMap::new(),
+        }
+    }
+
+    /// The combinations that can take the lowest card of `mask`
+    fn next(&self, mask: u16) -> impl Iterator<Item = u16> + 'a {
+        let lowest = mask & mask.wrapping_neg();
+        self.combos
+            .iter()
+            .copied()
+            .filter(move |combo| combo & lowest != 0 && combo & !mask == 0)
+    }
+
+    fn count(&mut self, mask: u16) -> u64 {
+        if mask == 0 {
+            return 1;
+        }
+        if let Some(count) = self.counts.get(&mask) {
+            return *count;
+        }
+
+        let count = self.next(mask).map(|combo| self.count(mask ^ combo)).sum();
+        self.counts.insert(mask, count);
+        count
+    }
+
+    fn fewest(&mut self, mask: u16) -> usize {
+        if mask == 0 {
+            return 0;
+        }
+        if let Some(fewest) = self.fewests.get(&mask) {
+            return *fewest;
+        }
+
+        // Singles always fit, there is a way
+        let fewest = self
+            .next(mask)
+            .map(|combo| 1 + self.fewest(mask ^ combo))
+            .min()
+            .unwrap_or(usize::MAX);
+        self.fewests.insert(mask, fewest);
+        fewest
+    }
+
+    /// Up to `limit` splits of `mask`, by how many combinations they take
+    fn list(&mut self, mask: u16, limit: usize) -> Vec<Vec<u16>> {
+        let mut listed = vec![];
+        let most = mask.count_ones() as usize;
+
+        for groups in self.fewest(mask)..=most {
+            self.split(mask, groups, &mut vec![], &mut listed, limit);
+        }
+
+        listed
+    }
+
+    /// Splits of `mask` into `left` combinations after `taken`
+    fn split(
+        &mut self,
+        mask: u16,
+        left: usize,
+        taken: &mut Vec<u16>,
+        listed: &mut Vec<Vec<u16>>,
+        limit: usize,
+    ) {
+        if listed.len() >= limit {
+            return;
+        }
+        if mask == 0 {
+            if left == 0 {
+                listed.push(taken.clone());
+            }
+            return;
+        }
+
+        let combos: Vec<u16> = self.next(mask).collect();
+        for combo in combos {
+            if left == 0 || 1 + self.fewest(mask ^ combo) > left {
+                continue;
+            }
+
+            taken.push(combo);
+            self.split(mask ^ combo, left - 1, taken, listed, limit);
+            taken.pop();
+        }
+    }
+}
+
+/// A rough guess for teaching, not a probability: high cards, bombs and 2s
+/// count, so do few plays to go out
+fn strength(hand: &Hand, combos: &[u16], fewest: usize) -> u32 {
+    if hand.is_empty() {
+        return 0;
+    }
+
+    let cards = hand.len() as f32;
+    // Ranks go from 1 for a three to 13 for a two
+    let high = hand
+        .cards
+        .iter()
+        .map(|card| (card.rank.ordinal() - 1) as f32 / 12.)
+        .sum::<f32>()
+        / cards;
+    let tempo = if hand.len() == 1 {
+        1.
+    } else {
+        1. - (fewest - 1) as f32 / (cards - 1.)
+    };
+    let bombs = combos
+        .iter()
+        .filter(|mask| {
+            matches!(
+                pick(hand, **mask).get_combination(),
+                ThirteenCombination::Quartet | ThirteenCombination::DoubleSequence
+            )
+        })
+        .count()
+        .min(2) as f32;
+    let twos = hand
+        .cards
+        .iter()
+        .filter(|card| card.rank.ordinal() == 13)
+        .count() as f32;
+
+    let score = 0.45 * high + 0.35 * tempo + 0.1 * bombs + 0.05 * twos;
+
+    (score.min(1.) * 100.).round() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plays(report: &Report) -> Vec<&str> {
+        report
+            .responses
+            .iter()
+            .map(|play| play.cards.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_hand() {
+        assert_eq!(parse_hand(" 5h, 3s ").unwrap(), Hand::from_str("3S,5H"));
+        assert!(parse_hand("3S,XH").is_err());
+        assert!(parse_hand("3S,10H").is_err());
+        assert!(parse_hand("3S,3S").is_err());
+    }
+
+    #[test]
+    fn test_whole_hand_combination() {
+        let report = analyze(&parse_hand("3S,4S,5H").unwrap(), None, 10).unwrap();
+        assert_eq!(report.combination.as_deref(), Some("Sequence"));
+
+        let report = analyze(&parse_hand("3S,4S,9H").unwrap(), None, 10).unwrap();
+        assert_eq!(report.combination, None);
+    }
+
+    #[test]
+    fn test_responses_follow_the_table() {
+        let hand = parse_hand("3S,5S,5H,KD,KH,2C").unwrap();
+        let table = Hand::from_str("4C,4D");
+
+        let report = analyze(&hand, Some(&table), 10).unwrap();
+
+        assert_eq!(plays(&report), vec!["5S,5H", "KD,KH"]);
+    }
+
+    #[test]
+    fn test_leading_takes_any_combination() {
+        let report = analyze(&parse_hand("3S,3H").unwrap(), None, 10).unwrap();
+
+        assert_eq!(plays(&report), vec!["3S", "3H", "3S,3H"]);
+    }
+
+    #[test]
+    fn test_partitions() {
+        let report = analyze(&parse_hand("3S,3H,4D,5C").unwrap(), None, 10).unwrap();
+        let partitions = &report.partitions;
+
+        // 3S 3H 4D 5C, 3S-3H 4D 5C, 3S-4D-5C 3H, 3H-4D-5C 3S
+        assert_eq!(partitions.total, 4);
+        assert_eq!(partitions.fewest, 2);
+        assert_eq!(partitions.listed.len(), 4);
+        assert_eq!(partitions.listed[0].len(), 2);
+        assert_eq!(partitions.listed[3].len(), 4);
+
+        let report = analyze(&parse_hand("3S,3H,4D,5C").unwrap(), None, 1).unwrap();
+        assert_eq!(report.partitions.listed.len(), 1);
+    }
+
+    #[test]
+    fn test_strength_prefers_high_cards_and_bombs() {
+        let weak = analyze(&parse_hand("3S,5H,7D,9C,JS").unwrap(), None, 0).unwrap();
+        let strong = analyze(&parse_hand("2S,2H,AD,AC,AS").unwrap(), None, 0).unwrap();
+        let bomb = analyze(&parse_hand("9S,9H,9D,9C,4S").unwrap(), None, 0).unwrap();
+        let plain = analyze(&parse_hand("9S,9H,8D,8C,4S").unwrap(), None, 0).unwrap();
+
+        assert!(strong.strength > weak.strength);
+        assert!(bomb.strength > plain.strength);
+        assert!(strong.strength <= 100);
+    }
+
+    #[test]
+    fn test_rejects_what_can_not_be_beaten() {
+        let hand = parse_hand("3S").unwrap();
+
+        assert!(analyze(&hand, Some(&Hand::from_str("3H,9C")), 10).is_err());
+    }
+}
@@ -0,0 +1,81 @@
+use clap::Parser;
+
+use analysis::{analyze, parse_hand, Report};
+
+mod analysis;
+
+/// Looks at a hand the way the engine does, for teaching and for settling
+/// what the rules allow
+#[derive(Parser, Debug)]
+#[command(name = "tienlen-analyzer", version, about = "Tien Len hand analyzer")]
+struct Cli {
+    /// The cards of the hand, like `3S,4S,5H`
+    hand: String,
+    /// The cards on the table to beat, lead the round when left out
+    #[arg(short, long)]
+    table: Option<String>,
+    /// How many of the ways to split the hand to list
+    #[arg(short, long, default_value_t = 10)]
+    partitions: usize,
+    /// Print the analysis as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+fn main() {
+    let cli = Cli::parse();
+
+    let report = parse_hand(&cli.hand).and_then(|hand| match cli.table.as_deref() {
+        Some(table) => analyze(&hand, Some(&parse_hand(table)?), cli.partitions),
+        None => analyze(&hand, None, cli.partitions),
+    });
+
+    let report = match report {
+        Ok(report) => report,
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(2);
+        }
+    };
+
+    if cli.json {
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+    } else {
+        print(&report);
+    }
+}
+
+fn print(report: &Report) {
+    println!("Hand: {}", report.hand);
+    println!(
+        "Combination: {}",
+        report.combination.as_deref().unwrap_or("none")
+    );
+    println!("Strength: {}/100", report.strength);
+
+    println!();
+    match &report.table {
+        Some(table) => println!("To beat {} ({}):", table.cards, table.combination),
+        None => println!("Leading:"),
+    }
+    for play in report.responses.iter() {
+        println!("  {} ({})", play.cards, play.combination);
+    }
+    if report.table.is_some() {
+        println!("  pass");
+    }
+
+    let partitions = &report.partitions;
+    println!();
+    println!(
+        "Partitions: {}, going out takes at least {} plays",
+        partitions.total, partitions.fewest
+    );
+    for groups in partitions.listed.iter() {
+        let groups: Vec<&str> = groups.iter().map(|play| play.cards.as_str()).collect();
+        println!("  {}", groups.join(" | "));
+    }
+    if (partitions.listed.len() as u64) < partitions.total {
+        println!("  ...");
+    }
+}
@@ -10,12 +10,17 @@ use naia_bevy_demo_shared::protocol;
 use naia_shared::ConnectionConfig;
 
 use crate::{
+    audio::SoundPlugin,
+    authority::AuthorityPlugin,
     fps::ScreenDiagsTextPlugin,
     // assets::AssetPlugin,
     game::GamePlugin,
+    locale::LocalePlugin,
+    settings::SettingsPlugin,
     states::MainState,
     system_set::{MainLoop, SystemSetsPlugin, Tick},
     systems::{events, init, my_cursor_system, sync},
+    theme::ThemePlugin,
     ui::UiPlugin,
     welcome::WelcomeScreenPlugin,
 };
@@ -27,7 +32,7 @@ pub fn run() {
             title: "thirteen".into(),
             canvas: Some("#thirteen".into()),
             resolution: (960., 600.).into(),
-            fit_canvas_to_parent: false,
+            fit_canvas_to_parent: true,
             prevent_default_event_handling: false,
             ..default()
         }),
@@ -64,6 +69,11 @@ pub fn run() {
         // Add Naia Client Plugin
         .add_plugins(ClientPlugin::new(client_config, protocol()))
         // .add_plugins(WorldInspectorPlugin::new())
+        .add_plugins(AuthorityPlugin)
+        .add_plugins(LocalePlugin)
+        .add_plugins(SettingsPlugin)
+        .add_plugins(ThemePlugin)
+        .add_plugins(SoundPlugin)
         .add_plugins(UiPlugin)
         .add_plugins(ScreenDiagsTextPlugin)
         .add_plugins(WelcomeScreenPlugin)
@@ -89,6 +99,8 @@ pub fn run() {
                 .chain()
                 .in_set(ReceiveEvents),
         )
+        // Game messages, from the server or the local table
+        .add_systems(Update, events::game_message_events.after(ReceiveEvents))
         // Tick Event
         .add_systems(Update, events::tick_events.in_set(Tick))
         .add_systems(
@@ -3,10 +3,12 @@ use std::collections::HashMap;
 use bevy::prelude::*;
 use bevy::reflect::TypePath;
 use bevy_common_assets::ron::RonAssetPlugin;
-use naia_bevy_demo_shared::components::card::Card;
+use naia_bevy_demo_shared::components::player::AVATAR_COUNT;
 
-use crate::resources::Global;
+use crate::game::layout::{Arrangement, Layout};
+use crate::settings::TableTheme;
 use crate::states::MainState;
+use crate::theme::{ThemeHandles, ThemePack};
 
 pub struct AssetPlugin;
 
@@ -23,8 +25,12 @@ impl Plugin for AssetPlugin {
 pub struct UiAssets {
     pub font: Handle<Font>,
     pub noto_font: Handle<Font>,
+    /// Has the Vietnamese letters the other fonts are missing
+    pub text_font: Handle<Font>,
     pub cards: HashMap<String, Handle<Image>>,
-    pub board: Handle<Image>,
+    pub boards: HashMap<TableTheme, Handle<Image>>,
+    /// The table of the theme, in place of the boards
+    pub table: Option<Handle<Image>>,
     pub back_card: Handle<Image>,
     pub background: Handle<Image>,
     pub play_btn: Handle<Image>,
@@ -32,32 +38,32 @@ pub struct UiAssets {
     pub avatars: HashMap<i32, Handle<Image>>,
 }
 
+impl UiAssets {
+    /// The table to show, the theme's or the one of the colour picked
+    pub fn board(&self, theme: TableTheme) -> Handle<Image> {
+        self.table
+            .clone()
+            .unwrap_or_else(|| self.boards[&theme].clone())
+    }
+
+    pub fn set_theme(&mut self, theme: ThemeHandles) {
+        self.cards = theme.cards;
+        self.back_card = theme.back_card;
+        self.table = theme.table;
+        self.background = theme.background;
+        self.font = theme.font;
+        self.text_font = theme.text_font;
+    }
+}
+
 #[derive(serde::Deserialize, Asset, TypePath, Debug)]
 pub struct GameConfig {
     pub window_title: String,
-    pile_position: (f32, f32, f32),
-    p0_position: (f32, f32),
-    p1_position: (f32, f32),
-    p2_position: (f32, f32),
-    p3_position: (f32, f32),
-}
-
-impl GameConfig {
-    pub fn pile_position(&self) -> Vec3 {
-        Vec3::from(self.pile_position)
-    }
-    pub fn p0(&self) -> Vec2 {
-        Vec2::from(self.p0_position)
-    }
-    pub fn p1(&self) -> Vec2 {
-        Vec2::from(self.p1_position)
-    }
-    pub fn p2(&self) -> Vec2 {
-        Vec2::from(self.p2_position)
-    }
-    pub fn p3(&self) -> Vec2 {
-        Vec2::from(self.p3_position)
-    }
+    /// Replaces the built in arrangement when given
+    #[serde(default)]
+    landscape: Option<Arrangement>,
+    #[serde(default)]
+    portrait: Option<Arrangement>,
 }
 
 #[derive(Resource, Debug)]
@@ -72,46 +78,39 @@ fn setup(
 
     commands.insert_resource(game_config);
 
-    let font = asset_server.load("fonts/font.ttf");
+    // The theme picked in the settings takes over once it is loaded
+    let theme = ThemeHandles::load(&ThemePack::default(), &asset_server);
 
     let noto_font = asset_server.load("fonts/noto.ttf");
 
-    let board = asset_server.load("cards/tables/table_blue.png");
-
-    let back_card =
-        asset_server.load("cards/standard/solitaire/individuals/card_back/card_back.png");
+    let boards = TableTheme::ALL
+        .iter()
+        .map(|theme| (*theme, asset_server.load(theme.asset_path())))
+        .collect();
 
     let play_btn = asset_server.load("play.png");
 
     let skip_btn = asset_server.load("skip.png");
 
-    let background: Handle<Image> = asset_server.load("cards/backgrounds/background_1.png");
-
     let mut avatars = HashMap::new();
 
-    for i in 0..5 {
+    for i in 0..AVATAR_COUNT as i32 {
         let path = format!("avatars/c{}.png", i);
         let circle_avatar = asset_server.load(path);
         avatars.insert(i, circle_avatar);
     }
 
-    let mut cards = HashMap::new();
-    let all_cards: &[Card] = Card::all_cards();
-
-    for card in all_cards {
-        let handle = asset_server.load(card.to_path());
-        cards.insert(card.name(), handle);
-    }
-
     commands.insert_resource(UiAssets {
-        cards,
-        font,
+        cards: theme.cards,
+        font: theme.font,
         play_btn,
         skip_btn,
-        background,
-        back_card,
-        board,
+        background: theme.background,
+        back_card: theme.back_card,
+        boards,
+        table: theme.table,
         noto_font,
+        text_font: theme.text_font,
         avatars,
     });
 
@@ -123,16 +122,16 @@ fn setup(
 fn spawn_level(
     config_res: Res<GameConfigHandle>,
     mut game_config: ResMut<Assets<GameConfig>>,
-    mut global: ResMut<Global>,
+    mut layout: ResMut<Layout>,
 ) {
     if let Some(game_config) = game_config.remove(config_res.0.id()) {
         info!("Game Config");
-        global.game.player_1.draw_pos = game_config.p1();
-        global.game.player_2.draw_pos = game_config.p2();
-        global.game.player_3.draw_pos = game_config.p3();
-        global.game.local_player.draw_pos = game_config.p0();
-
-        global.game.local_player.pile_pos = game_config.pile_position();
+        if let Some(landscape) = game_config.landscape.clone() {
+            layout.landscape = landscape;
+        }
+        if let Some(portrait) = game_config.portrait.clone() {
+            layout.portrait = portrait;
+        }
 
         info!("Updated {:?}", game_config);
     }
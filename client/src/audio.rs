@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+
+use bevy::{
+    audio::{PlaybackMode, Volume, VolumeLevel},
+    prelude::*,
+};
+use naia_bevy_demo_shared::{
+    components::{
+        cards::{evaluate_combination, ThirteenCombination},
+        hand::Hand,
+        timer::Counter,
+    },
+    messages::{AcceptPlayCard, AcceptStartGame, EndMatch, NewMatch, PlayerMessage, UpdateTurn},
+};
+
+use crate::{authority::Incoming, resources::Global, settings::Settings};
+
+/// Sound effects driven by the game messages, and the background music
+pub struct SoundPlugin;
+
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PlaySound>()
+            .add_systems(Startup, load_sounds)
+            .add_systems(
+                Update,
+                (
+                    (message_sounds, timer_low_sound),
+                    play_sounds,
+                    (apply_volume, toggle_music).run_if(resource_changed::<Settings>()),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Counter value from which the active player hears a tick every second
+const TIMER_LOW_SECS: f32 = 5.;
+/// The music plays under the effects
+const MUSIC_VOLUME: f32 = 0.4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Sound {
+    Deal,
+    Select,
+    Play,
+    Pass,
+    /// A quartet or three pairs in a row, they cut a 2
+    Bomb,
+    YourTurn,
+    TimerLow,
+    MatchEnd,
+}
+
+impl Sound {
+    const ALL: [Sound; 8] = [
+        Self::Deal,
+        Self::Select,
+        Self::Play,
+        Self::Pass,
+        Self::Bomb,
+        Self::YourTurn,
+        Self::TimerLow,
+        Self::MatchEnd,
+    ];
+
+    fn asset_path(&self) -> &'static str {
+        match self {
+            Self::Deal => "sounds/deal.wav",
+            Self::Select => "sounds/select.wav",
+            Self::Play => "sounds/play.wav",
+            Self::Pass => "sounds/pass.wav",
+            Self::Bomb => "sounds/bomb.wav",
+            Self::YourTurn => "sounds/your_turn.wav",
+            Self::TimerLow => "sounds/timer_low.wav",
+            Self::MatchEnd => "sounds/match_end.wav",
+        }
+    }
+}
+
+/// Plays a sound effect, for what only the client knows about
+#[derive(Event)]
+pub struct PlaySound(pub Sound);
+
+#[derive(Resource)]
+struct Sounds {
+    effects: HashMap<Sound, Handle<AudioSource>>,
+    music: Handle<AudioSource>,
+}
+
+#[derive(Component)]
+struct Music;
+
+fn load_sounds(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let effects = Sound::ALL
+        .iter()
+        .map(|sound| (*sound, asset_server.load(sound.asset_path())))
+        .collect();
+
+    commands.insert_resource(Sounds {
+        effects,
+        music: asset_server.load("sounds/music.wav"),
+    });
+}
+
+fn is_bomb(cards: &str) -> bool {
+    let hand = Hand::from_str(cards);
+
+    match evaluate_combination(&hand.cards) {
+        ThirteenCombination::Quartet => true,
+        ThirteenCombination::DoubleSequence => hand.len() >= 6,
+        _ => false,
+    }
+}
+
+fn message_sounds(
+    global: Res<Global>,
+    mut start_ev: EventReader<Incoming<AcceptStartGame>>,
+    mut play_ev: EventReader<Incoming<AcceptPlayCard>>,
+    mut player_message_ev: EventReader<Incoming<PlayerMessage>>,
+    mut turn_ev: EventReader<Incoming<UpdateTurn>>,
+    mut new_match_ev: EventReader<Incoming<NewMatch>>,
+    mut end_match_ev: EventReader<Incoming<EndMatch>>,
+    mut play_sound: EventWriter<PlaySound>,
+) {
+    let local_pos = global.game.local_player.pos as usize;
+
+    for Incoming(message) in start_ev.read() {
+        play_sound.send(PlaySound(Sound::Deal));
+        if message.active_player == local_pos {
+            play_sound.send(PlaySound(Sound::YourTurn));
+        }
+    }
+
+    for Incoming(message) in play_ev.read() {
+        if is_bomb(&message.cards) {
+            play_sound.send(PlaySound(Sound::Bomb));
+        } else {
+            play_sound.send(PlaySound(Sound::Play));
+        }
+    }
+
+    for Incoming(PlayerMessage(_, message)) in player_message_ev.read() {
+        if message == "skip" {
+            play_sound.send(PlaySound(Sound::Pass));
+        }
+    }
+
+    // Follows plays and skips alike
+    for Incoming(message) in turn_ev.read() {
+        if message.0 == local_pos {
+            play_sound.send(PlaySound(Sound::YourTurn));
+        }
+    }
+
+    for Incoming(message) in new_match_ev.read() {
+        if message.active_player == local_pos {
+            play_sound.send(PlaySound(Sound::YourTurn));
+        }
+    }
+
+    for _ in end_match_ev.read() {
+        play_sound.send(PlaySound(Sound::MatchEnd));
+    }
+}
+
+/// Ticks every second of the last few of the local player's turn
+fn timer_low_sound(
+    global: Res<Global>,
+    counter_q: Query<&Counter, Changed<Counter>>,
+    mut last_tick: Local<Option<i32>>,
+    mut play_sound: EventWriter<PlaySound>,
+) {
+    let Ok(counter) = counter_q.get_single() else {
+        return;
+    };
+
+    let my_turn = global.game.active_player_pos == global.game.local_player.pos;
+    let seconds = counter.counter.ceil();
+
+    if !my_turn || seconds > TIMER_LOW_SECS || seconds <= 0. {
+        *last_tick = None;
+        return;
+    }
+
+    if *last_tick != Some(seconds as i32) {
+        *last_tick = Some(seconds as i32);
+        play_sound.send(PlaySound(Sound::TimerLow));
+    }
+}
+
+fn play_sounds(
+    mut commands: Commands,
+    sounds: Res<Sounds>,
+    settings: Res<Settings>,
+    mut play_sound: EventReader<PlaySound>,
+) {
+    for PlaySound(sound) in play_sound.read() {
+        if settings.volume <= 0. {
+            continue;
+        }
+
+        commands.spawn(AudioBundle {
+            source: sounds.effects[sound].clone(),
+            settings: PlaybackSettings::DESPAWN,
+        });
+    }
+}
+
+fn music_volume(settings: &Settings) -> f32 {
+    settings.volume * MUSIC_VOLUME
+}
+
+/// New sounds pick the global volume up, the music already plays
+fn apply_volume(
+    settings: Res<Settings>,
+    mut global_volume: ResMut<GlobalVolume>,
+    music_q: Query<&AudioSink, With<Music>>,
+) {
+    *global_volume = GlobalVolume::new(settings.volume);
+
+    for sink in music_q.iter() {
+        sink.set_volume(music_volume(&settings));
+    }
+}
+
+fn toggle_music(
+    mut commands: Commands,
+    sounds: Res<Sounds>,
+    settings: Res<Settings>,
+    music_q: Query<Entity, With<Music>>,
+) {
+    let playing = !music_q.is_empty();
+
+    if settings.music && !playing {
+        commands.spawn((
+            Music,
+            AudioBundle {
+                source: sounds.music.clone(),
+                settings: PlaybackSettings {
+                    mode: PlaybackMode::Loop,
+                    // Absolute, the global volume only scales the effects
+                    volume: Volume::Absolute(VolumeLevel::new(music_volume(&settings))),
+                    ..Default::default()
+                },
+            },
+        ));
+    } else if !settings.music && playing {
+        for entity in music_q.iter() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use std::{cmp::Ordering, collections::BTreeMap};
+
+use naia_bevy_demo_shared::components::{
+    card::Card,
+    cards::{Cards, ThirteenCombination},
+    hand::Hand,
+    rank::Rank,
+};
+use tienlen_engine::{Action, GameEngine};
+
+/// Shortest straight the rules take
+const MIN_SEQUENCE: usize = 3;
+
+/// What the bot at `pos` does on its turn. Leading, it gets rid of its
+/// weakest card along with as many cards as go with it; following, it plays
+/// the weakest combination that beats the table, or passes.
+pub fn choose(engine: &GameEngine, pos: usize) -> Action {
+    let Some(seat) = engine.seat(pos) else {
+        return Action::Timeout;
+    };
+
+    let candidates = candidates(&seat.hand);
+
+    let play = match engine.last_played().filter(|_| !engine.leader_turn()) {
+        None => {
+            let weakest = seat.hand.cards.iter().min();
+            candidates
+                .into_iter()
+                .filter(|hand| weakest.is_some_and(|card| hand.cards.contains(card)))
+                .max_by_key(Hand::len)
+        }
+        Some(to_beat) => candidates
+            .into_iter()
+            .filter(|hand| {
+                hand.len() == to_beat.len()
+                    && hand.get_combination() == to_beat.get_combination()
+                    && to_beat.cmp(hand) != Ordering::Greater
+            })
+            .min_by_key(|hand| *hand.highest_value()),
+    };
+
+    match play {
+        Some(cards) => Action::Play { pos, cards },
+        // Same as the clock: the smallest card
+        None if engine.leader_turn() => Action::Timeout,
+        None => Action::Skip { pos },
+    }
+}
+
+/// Singles, cards of a kind and straights out of `hand`. Bombs made of
+/// double straights are left to the players.
+fn candidates(hand: &Hand) -> Vec<Hand> {
+    let mut by_rank: BTreeMap<Rank, Vec<Card>> = BTreeMap::new();
+    for card in hand.cards.iter() {
+        by_rank.entry(card.rank).or_default().push(*card);
+    }
+    for cards in by_rank.values_mut() {
+        cards.sort();
+    }
+
+    let mut candidates = vec![];
+
+    for cards in by_rank.values() {
+        for size in 1..=cards.len() {
+            candidates.extend(subsets(cards, size).into_iter().map(|cards| Hand { cards }));
+        }
+    }
+
+    // 2s never go in a straight
+    let ranks: Vec<&Rank> = by_rank.keys().filter(|rank| **rank != Rank::Two).collect();
+    for start in 0..ranks.len() {
+        for end in start + MIN_SEQUENCE - 1..ranks.len() {
+            if ranks[end].ordinal() - ranks[start].ordinal() != end - start {
+                break;
+            }
+
+            // The lowest card of each rank, the top one decides what it beats
+            let low: Vec<Card> = ranks[start..end]
+                .iter()
+                .map(|rank| by_rank[*rank][0])
+                .collect();
+
+            for top in by_rank[ranks[end]].iter() {
+                let mut cards = low.clone();
+                cards.push(*top);
+                candidates.push(Hand { cards });
+            }
+        }
+    }
+
+    candidates.retain(|hand| hand.get_combination() != ThirteenCombination::NonSense);
+    candidates
+}
+
+/// Every way to pick `size` of `cards`, in order
+fn subsets(cards: &[Card], size: usize) -> Vec<Vec<Card>> {
+    if size == 0 {
+        return vec![vec![]];
+    }
+
+    let mut picks = vec![];
+    for (index, card) in cards.iter().enumerate() {
+        for mut rest in subsets(&cards[index + 1..], size - 1) {
+            rest.insert(0, *card);
+            picks.push(rest);
+        }
+    }
+
+    picks
+}
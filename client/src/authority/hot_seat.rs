@@ -0,0 +1,110 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use naia_bevy_demo_shared::components::Player;
+
+use crate::{components::LocalPlayer, game::ShowHand, locale::Locale, resources::Global};
+
+use super::{local::LocalTable, Mode};
+
+/// Passes the device around the players of a hot seat table, with a screen
+/// in between so nobody sees the hand of the one before
+pub struct HotSeatPlugin;
+
+impl Plugin for HotSeatPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PassDevice>().add_systems(
+            Update,
+            (pass_device, privacy_screen)
+                .chain()
+                .run_if(resource_equals(Mode::HotSeat))
+                .run_if(resource_exists::<LocalTable>()),
+        );
+    }
+}
+
+/// Seat of the player the device goes to, until they take a look
+#[derive(Resource, Default)]
+struct PassDevice(Option<usize>);
+
+fn pass_device(local: Res<LocalTable>, mut pass: ResMut<PassDevice>) {
+    let next = local
+        .engine
+        .active_player()
+        .filter(|pos| *pos != local.pos && local.humans.contains(pos));
+
+    if pass.0 != next {
+        pass.0 = next;
+    }
+}
+
+/// Covers the whole table until the next player taps it, then hands them
+/// the device: their seat is the local one and their hand in the pile
+#[allow(clippy::too_many_arguments)]
+fn privacy_screen(
+    mut commands: Commands,
+    mut egui_ctx: EguiContexts,
+    mut local: ResMut<LocalTable>,
+    mut global: ResMut<Global>,
+    pass: Res<PassDevice>,
+    player_q: Query<(Entity, &Player, Has<LocalPlayer>)>,
+    mut show_hand_ev: EventWriter<ShowHand>,
+    locale: Locale,
+) {
+    let Some(pos) = pass.0 else {
+        return;
+    };
+
+    let name = player_q
+        .iter()
+        .find(|(_, player, _)| *player.pos == pos)
+        .map(|(_, player, _)| player.name())
+        .unwrap_or_default();
+
+    let ctx = egui_ctx.ctx_mut();
+    let screen = ctx.screen_rect();
+
+    // Above the other windows, the tracker and the results know about hands
+    let tapped = egui::Area::new("hot-seat-screen")
+        .order(egui::Order::Foreground)
+        .fixed_pos(screen.min)
+        .show(ctx, |ui| {
+            ui.painter()
+                .rect_filled(screen, 0., ui.visuals().panel_fill);
+
+            ui.allocate_ui_at_rect(screen, |ui| {
+                ui.vertical_centered(|ui| {
+                    ui.add_space(screen.height() / 3.);
+                    ui.heading(locale.t_args("hot-seat-pass", &[("name", &name)]));
+                    ui.add_space(10.);
+                    ui.label(locale.t("hot-seat-reveal"));
+                });
+            });
+
+            ui.interact(screen, ui.id().with("tap"), egui::Sense::click())
+                .clicked()
+        })
+        .inner;
+
+    if !tapped {
+        return;
+    }
+
+    local.pos = pos;
+
+    for (entity, player, is_local) in player_q.iter() {
+        if is_local {
+            commands.entity(entity).remove::<LocalPlayer>();
+        }
+        if *player.pos == pos {
+            commands.entity(entity).insert(LocalPlayer);
+            global.player_entity = Some(entity);
+        }
+    }
+
+    let hand = local
+        .engine
+        .seat(pos)
+        .map(|seat| seat.hand.to_string())
+        .unwrap_or_default();
+    show_hand_ev.send(ShowHand(hand));
+}
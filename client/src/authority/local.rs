@@ -0,0 +1,296 @@
+use std::{collections::BTreeSet, time::Duration};
+
+use bevy::{ecs::event::Event as BevyEvent, prelude::*, time::common_conditions::on_timer};
+use naia_bevy_demo_shared::{
+    components::{hand::Hand, player::AVATAR_COUNT, Host, Player, Table, TableOptions},
+    messages::{
+        AcceptPlayCard, AcceptStartGame, BackToLobby, EndMatch, ErrorCode, GameError, KickPlayer,
+        MatchResults, PlayCard, PlayerMessage, RequestStart, SetCardTracker, SkipTurn, StartGame,
+        TakeSeat, UpdateTurn, WaitForStart,
+    },
+};
+use tienlen_adapter::{mirror, Countdowns, Notice};
+use tienlen_engine::{Action, Event, GameEngine, Phase};
+
+use crate::{components::LocalPlayer, resources::Global, states::MainState};
+
+use super::{bot, Incoming, Mode, Outgoing};
+
+const BOT_NAMES: [&str; 3] = ["Lan", "Minh", "Tuan"];
+/// Same as the server defaults
+const COUNTDOWNS: Countdowns = Countdowns {
+    start: 3,
+    next_match: 5,
+};
+/// How long a bot takes over its move
+const BOT_DELAY: Duration = Duration::from_millis(900);
+
+/// A table run by an engine of its own on this device, with bots or other
+/// players of the device in the other seats
+pub struct LocalTablePlugin;
+
+impl Plugin for LocalTablePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<EngineEvent>()
+            .add_systems(
+                OnExit(MainState::Welcome),
+                setup_table.run_if(|mode: Res<Mode>| mode.is_local()),
+            )
+            .add_systems(
+                Update,
+                (
+                    player_actions,
+                    bot_turns.run_if(in_state(MainState::Game).and_then(on_timer(BOT_DELAY))),
+                    engine_events,
+                )
+                    .chain()
+                    .run_if(resource_exists::<LocalTable>()),
+            );
+    }
+}
+
+#[derive(Resource)]
+pub(super) struct LocalTable {
+    pub(super) engine: GameEngine,
+    /// Seat of the player holding the device
+    pub(super) pos: usize,
+    /// Seats of the players on this device, the bots sit in the others
+    pub(super) humans: BTreeSet<usize>,
+}
+
+impl LocalTable {
+    /// Everybody at the table but the player holding the device
+    fn others(&self) -> Vec<usize> {
+        self.engine
+            .seats()
+            .map(|(pos, _)| pos)
+            .filter(|pos| *pos != self.pos)
+            .collect()
+    }
+
+    /// Follows the players on this device around the seats
+    fn track(&mut self, event: &Event) {
+        match event {
+            Event::SeatChanged { from, to } => {
+                if self.humans.remove(from) {
+                    self.humans.insert(*to);
+                }
+                if self.pos == *from {
+                    self.pos = *to;
+                }
+            }
+            Event::PlayerLeft { pos } => {
+                self.humans.remove(pos);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Something the local engine reported, turned into messages by
+/// `engine_events` like the server does
+#[derive(BevyEvent)]
+struct EngineEvent(Event);
+
+/// Seats the player and the bots or the guests, everybody ready so the host
+/// can start
+fn setup_table(mut commands: Commands, mut global: ResMut<Global>, mode: Res<Mode>) {
+    let mut engine = GameEngine::default();
+
+    let others = match *mode {
+        Mode::HotSeat => global.guest_names.clone(),
+        _ => BOT_NAMES.iter().map(|name| name.to_string()).collect(),
+    };
+
+    let mut players = vec![(global.player_name.clone(), global.player_avatar)];
+    // The others take the avatars after the player's
+    players.extend(others.into_iter().enumerate().map(|(index, name)| {
+        let avatar = (global.player_avatar + index + 1) % AVATAR_COUNT;
+        (name, avatar)
+    }));
+
+    let humans = match *mode {
+        Mode::HotSeat => (0..players.len()).collect(),
+        _ => BTreeSet::from([0]),
+    };
+
+    for (pos, (name, avatar)) in players.into_iter().enumerate() {
+        engine
+            .apply(Action::Join { name })
+            .expect("an empty table has room");
+        engine
+            .apply(Action::Ready { pos })
+            .expect("the seat was just taken");
+
+        let name = engine
+            .seat(pos)
+            .map(|seat| seat.name.clone())
+            .unwrap_or_default();
+        let mut player = Player::new(pos, &name, avatar);
+        *player.ready = true;
+
+        let entity = commands.spawn(player).id();
+        if pos == 0 {
+            commands.entity(entity).insert((LocalPlayer, Host));
+            global.player_entity = Some(entity);
+        }
+    }
+
+    commands.spawn(TableOptions::new(false));
+    commands.insert_resource(LocalTable {
+        engine,
+        pos: 0,
+        humans,
+    });
+}
+
+#[allow(clippy::too_many_arguments)]
+fn player_actions(
+    mut local: ResMut<LocalTable>,
+    mut start_ev: EventReader<Outgoing<StartGame>>,
+    mut request_ev: EventReader<Outgoing<RequestStart>>,
+    mut play_ev: EventReader<Outgoing<PlayCard>>,
+    mut skip_ev: EventReader<Outgoing<SkipTurn>>,
+    mut seat_ev: EventReader<Outgoing<TakeSeat>>,
+    mut kick_ev: EventReader<Outgoing<KickPlayer>>,
+    mut tracker_ev: EventReader<Outgoing<SetCardTracker>>,
+    mut options_q: Query<&mut TableOptions>,
+    mut engine_ev: EventWriter<EngineEvent>,
+    mut error_ev: EventWriter<Incoming<ErrorCode>>,
+) {
+    let pos = local.pos;
+    let mut actions = vec![];
+
+    actions.extend(start_ev.read().map(|_| Action::Start));
+
+    for _ in request_ev.read() {
+        // The others are bots or on this device, they ask first so the deal
+        // comes with the player's request
+        for other in local.others() {
+            let _ = local.engine.apply(Action::RequestDeal { pos: other });
+        }
+        actions.push(Action::RequestDeal { pos });
+    }
+
+    actions.extend(
+        play_ev
+            .read()
+            .map(|Outgoing(PlayCard(cards))| Action::Play {
+                pos,
+                cards: Hand::from(cards.clone()),
+            }),
+    );
+    actions.extend(skip_ev.read().map(|_| Action::Skip { pos }));
+    actions.extend(
+        seat_ev
+            .read()
+            .map(|Outgoing(TakeSeat(to))| Action::TakeSeat { pos, to: *to }),
+    );
+
+    // Like the server, the host only removes players in the lobby
+    for Outgoing(KickPlayer(kicked)) in kick_ev.read() {
+        match local.engine.phase() {
+            Phase::Lobby if *kicked != pos => actions.push(Action::Leave { pos: *kicked }),
+            Phase::Lobby => {}
+            phase => error_ev.send(Incoming(ErrorCode::from(GameError::WrongPhase { phase }))),
+        }
+    }
+
+    for Outgoing(SetCardTracker(on)) in tracker_ev.read() {
+        for mut options in options_q.iter_mut() {
+            *options.card_tracker = *on;
+        }
+    }
+
+    for action in actions {
+        match local.engine.apply(action) {
+            Ok(events) => {
+                for event in events.iter() {
+                    local.track(event);
+                }
+                engine_ev.send_batch(events.into_iter().map(EngineEvent));
+            }
+            Err(error) => error_ev.send(Incoming(ErrorCode::from(error))),
+        }
+    }
+}
+
+fn bot_turns(mut local: ResMut<LocalTable>, mut engine_ev: EventWriter<EngineEvent>) {
+    let Some(pos) = local
+        .engine
+        .active_player()
+        .filter(|pos| !local.humans.contains(pos))
+    else {
+        return;
+    };
+
+    let action = bot::choose(&local.engine, pos);
+    // What the clock would do is always allowed
+    let events = local
+        .engine
+        .apply(action)
+        .or_else(|_| local.engine.apply(Action::Timeout));
+
+    if let Ok(events) = events {
+        engine_ev.send_batch(events.into_iter().map(EngineEvent));
+    }
+}
+
+/// Mirrors the engine into the local components and tells the UI, the way
+/// the server does over the network
+#[allow(clippy::too_many_arguments)]
+fn engine_events(
+    mut commands: Commands,
+    local: Res<LocalTable>,
+    mut engine_ev: EventReader<EngineEvent>,
+    mut player_q: Query<(Entity, &mut Player)>,
+    mut table_q: Query<&mut Table>,
+    mut wait_ev: EventWriter<Incoming<WaitForStart>>,
+    mut start_ev: EventWriter<Incoming<AcceptStartGame>>,
+    mut play_ev: EventWriter<Incoming<AcceptPlayCard>>,
+    mut turn_ev: EventWriter<Incoming<UpdateTurn>>,
+    mut message_ev: EventWriter<Incoming<PlayerMessage>>,
+    mut results_ev: EventWriter<Incoming<MatchResults>>,
+    mut end_ev: EventWriter<Incoming<EndMatch>>,
+    mut lobby_ev: EventWriter<Incoming<BackToLobby>>,
+) {
+    for EngineEvent(event) in engine_ev.read() {
+        match event {
+            Event::PlayerLeft { pos } => {
+                if let Some((entity, _)) = player_q.iter().find(|(_, player)| *player.pos == *pos) {
+                    commands.entity(entity).despawn();
+                }
+            }
+            Event::TableSetUp => {
+                commands.spawn(Table::new(String::new()));
+            }
+            _ => {}
+        }
+
+        let notices = mirror(
+            event,
+            &local.engine,
+            player_q.iter_mut().map(|(_, player)| player.into_inner()),
+            table_q.get_single_mut().ok().map(Mut::into_inner),
+            COUNTDOWNS,
+        );
+
+        for notice in notices {
+            match notice {
+                // Everybody is ready from `setup_table` on
+                Notice::PlayerReady(_) => {}
+                Notice::WaitForStart(message) => wait_ev.send(Incoming(message)),
+                Notice::StartGame { pos, message } if pos == local.pos => {
+                    start_ev.send(Incoming(message));
+                }
+                Notice::StartGame { .. } => {}
+                Notice::PlayCard(message) => play_ev.send(Incoming(message)),
+                Notice::UpdateTurn(message) => turn_ev.send(Incoming(message)),
+                Notice::PlayerMessage(message) => message_ev.send(Incoming(message)),
+                Notice::MatchResults(message) => results_ev.send(Incoming(message)),
+                Notice::EndMatch(message) => end_ev.send(Incoming(message)),
+                Notice::BackToLobby(message) => lobby_ev.send(Incoming(message)),
+            }
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use bevy::prelude::*;
+use naia_bevy_client::{events::MessageEvents, Client, ReceiveEvents};
+use naia_bevy_demo_shared::{
+    channels::{GameSystemChannel, PlayerActionChannel},
+    messages::{
+        AcceptPlayCard, AcceptStartGame, BackToLobby, EndMatch, ErrorCode, KickPlayer,
+        MatchResults, NewMatch, NewPlayer, PlayCard, PlayerMessage, RequestStart, SetCardTracker,
+        SkipTurn, StartGame, SystemMessage, TakeSeat, TransferHost, UpdateScore, UpdateTurn,
+        WaitForStart,
+    },
+};
+use naia_shared::Message;
+
+mod bot;
+mod hot_seat;
+mod local;
+
+use self::{hot_seat::HotSeatPlugin, local::LocalTablePlugin};
+
+/// Who runs the game. The UI only sees [`Incoming`] and [`Outgoing`]
+/// messages, online they go over naia and otherwise to a local engine
+pub struct AuthorityPlugin;
+
+impl Plugin for AuthorityPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Mode>()
+            .add_incoming::<AcceptPlayCard>()
+            .add_incoming::<AcceptStartGame>()
+            .add_incoming::<BackToLobby>()
+            .add_incoming::<EndMatch>()
+            .add_incoming::<ErrorCode>()
+            .add_incoming::<MatchResults>()
+            .add_incoming::<NewMatch>()
+            .add_incoming::<NewPlayer>()
+            .add_incoming::<PlayerMessage>()
+            .add_incoming::<SystemMessage>()
+            .add_incoming::<UpdateScore>()
+            .add_incoming::<UpdateTurn>()
+            .add_incoming::<WaitForStart>()
+            .add_outgoing::<KickPlayer>()
+            .add_outgoing::<PlayCard>()
+            .add_outgoing::<RequestStart>()
+            .add_outgoing::<SetCardTracker>()
+            .add_outgoing::<SkipTurn>()
+            .add_outgoing::<StartGame>()
+            .add_outgoing::<TakeSeat>()
+            .add_outgoing::<TransferHost>()
+            .add_plugins((LocalTablePlugin, HotSeatPlugin));
+    }
+}
+
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Against other players, the server decides
+    #[default]
+    Online,
+    /// Against bots, no network needed
+    Practice,
+    /// Players taking turns on this device
+    HotSeat,
+}
+
+impl Mode {
+    /// The game runs on this device
+    pub fn is_local(&self) -> bool {
+        *self != Mode::Online
+    }
+}
+
+/// A message from the game authority to the local player
+pub struct Incoming<M>(pub M);
+
+impl<M: Send + Sync + 'static> Event for Incoming<M> {}
+
+/// A message from the local player to the game authority
+pub struct Outgoing<M>(pub M);
+
+impl<M: Send + Sync + 'static> Event for Outgoing<M> {}
+
+trait AuthorityAppExt {
+    fn add_incoming<M: Message>(&mut self) -> &mut Self;
+    fn add_outgoing<M: Message>(&mut self) -> &mut Self;
+}
+
+impl AuthorityAppExt for App {
+    fn add_incoming<M: Message>(&mut self) -> &mut Self {
+        self.add_event::<Incoming<M>>().add_systems(
+            Update,
+            receive_message::<M>
+                .in_set(ReceiveEvents)
+                .run_if(resource_equals(Mode::Online)),
+        )
+    }
+
+    fn add_outgoing<M: Message>(&mut self) -> &mut Self {
+        self.add_event::<Outgoing<M>>().add_systems(
+            Update,
+            send_message::<M>.run_if(resource_equals(Mode::Online)),
+        )
+    }
+}
+
+fn receive_message<M: Message>(
+    mut event_reader: EventReader<MessageEvents>,
+    mut incoming: EventWriter<Incoming<M>>,
+) {
+    for events in event_reader.read() {
+        for message in events.read::<GameSystemChannel, M>() {
+            incoming.send(Incoming(message));
+        }
+    }
+}
+
+fn send_message<M: Message>(mut client: Client, mut outgoing: EventReader<Outgoing<M>>) {
+    for Outgoing(message) in outgoing.read() {
+        client.send_message::<PlayerActionChannel, M>(message);
+    }
+}
@@ -0,0 +1,121 @@
+use bevy::log::info;
+use naia_bevy_client::{transport::webrtc, Client};
+use naia_bevy_demo_shared::messages::Auth;
+
+/// Used when nothing else tells the client where the server is
+const DEFAULT_SERVER_ADDRESS: &str = "http://127.0.0.1:14191";
+/// Default auth address of the server UDP transport
+#[cfg(not(target_arch = "wasm32"))]
+const DEFAULT_UDP_SERVER_ADDRESS: &str = "http://127.0.0.1:14195";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transport {
+    WebRtc,
+    /// Native builds only, browsers can not open UDP sockets
+    Udp,
+}
+
+/// Fallback baked in at build time, the runtime sources below win over it
+fn build_time_address() -> String {
+    option_env!("SERVER_INIT_ADDRESS")
+        .unwrap_or(DEFAULT_SERVER_ADDRESS)
+        .to_string()
+}
+
+/// Value of `--<name> <value>` or `--<name>=<value>` on the command line
+#[cfg(not(target_arch = "wasm32"))]
+fn arg(name: &str) -> Option<String> {
+    let flag = format!("--{}", name);
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        if arg == flag {
+            return args.next();
+        }
+        if let Some(value) = arg.strip_prefix(&format!("{}=", flag)) {
+            return Some(value.to_string());
+        }
+    }
+
+    None
+}
+
+/// Native: `--transport udp|webrtc`, then the `TIENLEN_TRANSPORT` env var.
+/// Defaults to WebRTC so existing setups keep working.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn transport() -> Transport {
+    let transport = arg("transport").or_else(|| std::env::var("TIENLEN_TRANSPORT").ok());
+
+    match transport.as_deref().map(str::to_lowercase).as_deref() {
+        Some("udp") => Transport::Udp,
+        _ => Transport::WebRtc,
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn transport() -> Transport {
+    Transport::WebRtc
+}
+
+/// Signaling (or UDP auth) address of the server.
+///
+/// Native: `--server <url>`, then the `SERVER_INIT_ADDRESS` env var.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn server_address(transport: Transport) -> String {
+    if let Some(address) = arg("server") {
+        return address;
+    }
+
+    match transport {
+        Transport::WebRtc => {
+            std::env::var("SERVER_INIT_ADDRESS").unwrap_or_else(|_| build_time_address())
+        }
+        Transport::Udp => std::env::var("SERVER_UDP_INIT_ADDRESS")
+            .unwrap_or_else(|_| DEFAULT_UDP_SERVER_ADDRESS.to_string()),
+    }
+}
+
+/// Signaling address of the server.
+///
+/// Web: the `?server=<url>` query parameter of the page.
+#[cfg(target_arch = "wasm32")]
+pub fn server_address(_transport: Transport) -> String {
+    web_sys::window()
+        .and_then(|window| window.location().search().ok())
+        .and_then(|search| web_sys::UrlSearchParams::new_with_str(&search).ok())
+        .and_then(|params| params.get("server"))
+        .filter(|address| !address.is_empty())
+        .unwrap_or_else(build_time_address)
+}
+
+/// Opens the connection with the transport picked at startup
+pub fn connect(client: &mut Client) {
+    let transport = transport();
+    let server_address = server_address(transport);
+
+    info!("Connecting to {} over {:?}", server_address, transport);
+
+    // Carries the protocol version, the credentials only matter when the
+    // server requires them
+    client.auth(Auth::new(
+        option_env!("AUTH_USER_NAME").unwrap_or_default(),
+        option_env!("AUTH_USER_PASS").unwrap_or_default(),
+    ));
+
+    match transport {
+        Transport::WebRtc => {
+            let socket = webrtc::Socket::new(&server_address, client.socket_config());
+            client.connect(socket);
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        Transport::Udp => {
+            let socket = naia_bevy_client::transport::udp::Socket::new(
+                &server_address,
+                client.socket_config(),
+            );
+            client.connect(socket);
+        }
+        #[cfg(target_arch = "wasm32")]
+        Transport::Udp => unreachable!("UDP is not available in the browser"),
+    }
+}
@@ -1,21 +1,31 @@
 use bevy::prelude::*;
 use bevy_mod_picking::prelude::*;
 use bevy_tweening::{lens::*, *};
-use naia_bevy_client::{events::MessageEvents, Client};
 use naia_bevy_demo_shared::{
-    channels::{GameSystemChannel, PlayerActionChannel},
-    components::{deck::Deck, hand::Hand, rank::Rank, suit::Suit},
+    components::{card::Card as CardValue, deck::Deck, hand::Hand, rank::Rank, suit::Suit},
     messages::{AcceptPlayCard, AcceptStartGame, EndMatch, PlayCard},
 };
 use std::{collections::HashMap, ops::Add};
 
+pub mod order;
+pub mod touch;
+
 use crate::{
+    assets::UiAssets,
+    audio::{PlaySound, Sound},
+    authority::{Incoming, Outgoing},
+    locale::Locale,
     resources::Global,
+    settings::{Settings, SortMode},
+    states::MainState,
     system_set::{Animating, Playing},
+    theme::ThemePack,
 };
 
+use self::order::HandOrder;
 use super::{
     controller::PlayEvent,
+    layout::Layout,
     player_ui::{BackCard, PlayerPos},
     status::DrawStatus,
 };
@@ -29,18 +39,39 @@ impl Plugin for CardPlugin {
                 DefaultPickingPlugins.build(), // .disable::<DebugPickingPlugin>(),
             )
             .add_event::<SchedulePileEvent>()
+            .add_event::<ToggleCard>()
+            .add_event::<touch::TouchFeedback>()
+            .add_event::<HighlightCards>()
+            .add_event::<ShowHand>()
+            .init_resource::<HandOrder>()
             .add_systems(Startup, setup)
+            .add_systems(OnExit(MainState::Welcome), order::spawn_sort_btn)
+            .add_systems(
+                Update,
+                (order::sort_btn_click, order::update_sort_btn)
+                    .run_if(resource_exists::<UiAssets>()),
+            )
+            .add_systems(
+                Update,
+                (touch::touch_gestures, touch::touch_feedback)
+                    .chain()
+                    .run_if(in_state(MainState::Game)),
+            )
             .add_systems(
                 Update,
-                send_cards_to_server.in_set(Playing), // .run_if(valid_cards_condition),
+                send_played_cards.in_set(Playing), // .run_if(valid_cards_condition),
             )
             .add_systems(
                 Update,
                 (
                     handle_accept_play_event,
                     spawn_player_card,
+                    show_hand,
                     update_status,
+                    toggle_card,
                     handle_end_match_event,
+                    resort_pile,
+                    highlight_cards,
                     handle_reschedule_pile.in_set(Animating),
                 ),
             );
@@ -50,6 +81,20 @@ impl Plugin for CardPlugin {
 #[derive(Event, Clone, Default)]
 pub struct SchedulePileEvent(Vec<Entity>);
 
+/// Selects or unselects a card in hand, from a click or the keyboard
+#[derive(Event, Clone, Copy)]
+pub struct ToggleCard(pub Entity);
+
+/// Points at the cards a rejected play was about
+#[derive(Event, Clone, Default)]
+pub struct HighlightCards(pub Vec<Entity>);
+
+/// Puts another hand in the pile, when the device goes to the next player
+#[derive(Event, Clone, Default)]
+pub struct ShowHand(pub String);
+
+const HIGHLIGHT_FLASH: Color = Color::rgb(1., 0.45, 0.4);
+
 #[derive(Component)]
 struct PlayBtn;
 
@@ -70,6 +115,8 @@ pub enum CStatus {
     Idle,
     Active,
     Animating,
+    /// Follows the pointer, laid out again once dropped
+    Dragging,
 }
 
 #[derive(Component, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -124,11 +171,9 @@ impl CardBundle {
 
         if let Ok(rank) = Rank::from_char(char_rank) {
             if let Ok(suit) = Suit::from_char(char_suit) {
-                let asset_path = format!(
-                    "cards/standard/solitaire/individuals/{}/{}.png",
-                    suit.get_asset_path(),
-                    rank.get_asset_path()
-                );
+                // The theme swaps the face in once it is loaded
+                let asset_path =
+                    CardValue::new(rank, suit).asset_path(&ThemePack::default().card_faces);
 
                 info!("Path: {}", asset_path);
 
@@ -159,7 +204,12 @@ impl CardBundle {
                         },
                         CStatus::Idle,
                         On::<Pointer<Click>>::run(click_card),
+                        On::<Pointer<Drag>>::run(order::drag_card),
+                        On::<Pointer<DragEnd>>::run(order::drop_card),
                     ))
+                    .with_children(|parent| {
+                        parent.spawn(touch::hit_area());
+                    })
                     .id();
 
                 return Ok(entity);
@@ -173,48 +223,81 @@ impl CardBundle {
 fn handle_reschedule_pile(
     mut commands: Commands,
     mut reschedule_pile_ev: EventReader<SchedulePileEvent>,
-    mut card_q: Query<(&Transform, &mut CStatus, &Ordinal, &mut Visibility), With<Card>>,
+    mut card_q: Query<(&Transform, &mut CStatus, &Rank, &Suit, &mut Visibility), With<Card>>,
+    mut order: ResMut<HandOrder>,
+    settings: Res<Settings>,
+    layout: Res<Layout>,
 ) {
     for event in reschedule_pile_ev.read() {
-        let mut pile_pos = Vec3::new(0., 0., 10.);
         let mut cards = vec![];
 
         for c in event.0.iter() {
-            let (_trans, status, ordinal, mut vis) = card_q.get_mut(*c).unwrap();
+            let (_trans, _status, rank, suit, mut vis) = card_q.get_mut(*c).unwrap();
             *vis = Visibility::Visible;
-            if let CStatus::Idle = *status {
-                cards.push((*c, ordinal.get()));
-            }
+            cards.push((*c, CardValue::new(*rank, *suit)));
         }
 
-        cards.sort_by_key(|o| o.1);
+        let ordered = order::ordered(&cards, settings.sort_mode, &order);
+        order.0 = ordered;
+
+        for (slot, c) in order.0.iter().enumerate() {
+            let (trans, mut status, _, _, _) = card_q.get_mut(*c).unwrap();
+
+            // Selected cards keep standing out, the moving ones stay put
+            let (lift, completed) = match *status {
+                CStatus::Idle => (0., 4),
+                CStatus::Active => (15., 0),
+                CStatus::Animating | CStatus::Dragging => continue,
+            };
 
-        for c in cards.iter().map(|d| d.0) {
-            let (trans, mut status, _, _) = card_q.get_mut(c).unwrap();
             let tween = Tween::new(
                 EaseFunction::QuarticIn,
-                std::time::Duration::from_millis(300),
+                settings.animation_speed.scale(300),
                 TransformPositionLens {
                     start: trans.translation,
-                    end: pile_pos,
+                    // The card on the right lies on top, it takes the taps
+                    end: Vec3::new(
+                        slot as f32 * layout.card_spacing(),
+                        lift,
+                        10. + slot as f32 * 0.1,
+                    ),
                 },
             )
-            .with_completed_event(4);
-
-            pile_pos.x += 35.;
+            .with_completed_event(completed);
 
             *status = CStatus::Animating;
 
-            commands.entity(c).insert(Animator::new(tween));
+            commands.entity(*c).insert(Animator::new(tween));
         }
     }
 }
 
-fn send_cards_to_server(
-    mut client: Client,
+/// Lays the hand out again when the sort mode changes in the settings, or
+/// where the layout puts it
+fn resort_pile(
+    settings: Res<Settings>,
+    layout: Res<Layout>,
+    mut sort_mode: Local<Option<SortMode>>,
+    mut pile_q: Query<(&mut Transform, &Children), With<Pile>>,
+    mut reschedule_pile_ev: EventWriter<SchedulePileEvent>,
+) {
+    if *sort_mode == Some(settings.sort_mode) && !layout.is_changed() {
+        return;
+    }
+    *sort_mode = Some(settings.sort_mode);
+
+    for (mut trans, children) in pile_q.iter_mut() {
+        trans.translation = layout.pile_position();
+        reschedule_pile_ev.send(SchedulePileEvent(children.to_vec()));
+    }
+}
+
+fn send_played_cards(
+    mut play_card_ev: EventWriter<Outgoing<PlayCard>>,
     mut play_event_reader: EventReader<PlayEvent>,
     card_q: Query<&Raw, With<Card>>,
     mut draw_status_ev: EventWriter<DrawStatus>,
+    locale: Locale,
 ) {
     for event in play_event_reader.read() {
         let cards: Vec<String> = event
@@ -226,18 +309,16 @@ fn send_cards_to_server(
         let cards = cards.join(",");
 
         if cards.is_empty() {
-            return draw_status_ev
-                .send(DrawStatus::Error("You must select at least 1 card".into()));
+            return draw_status_ev.send(DrawStatus::Error(locale.t("error-no-card-selected")));
         }
 
         let hand = Hand::from_str(&cards);
 
         if !hand.check_combination() {
-            return draw_status_ev
-                .send(DrawStatus::Error("Your selected cards is not valid".into()));
+            return draw_status_ev.send(DrawStatus::Error(locale.t("error-invalid-selection")));
         }
 
-        client.send_message::<PlayerActionChannel, PlayCard>(&PlayCard(cards));
+        play_card_ev.send(Outgoing(PlayCard(cards)));
     }
 }
 
@@ -247,7 +328,7 @@ fn handle_accept_play_event(
     mut commands: Commands,
     global: Res<Global>,
     card_map: Res<CardMap>,
-    mut event_reader: EventReader<MessageEvents>,
+    mut accept_play_ev: EventReader<Incoming<AcceptPlayCard>>,
     mut pile_q: Query<(Entity, &Children), With<Pile>>,
     mut card_q: Query<
         (
@@ -260,75 +341,80 @@ fn handle_accept_play_event(
     >,
     back_card_q: Query<(&Transform, &PlayerPos), (With<BackCard>, Without<Card>)>,
     mut reschedule_pile_ev: EventWriter<SchedulePileEvent>,
+    settings: Res<Settings>,
+    layout: Res<Layout>,
 ) {
-    for events in event_reader.read() {
-        for data in events.read::<GameSystemChannel, AcceptPlayCard>() {
-            let mut table_pos = Vec3::new(-150., 50., 10.);
-            let cards = card_map.list_from_str(&data.cards);
-
-            if global.game.local_player.pos as usize == data.cur_player {
-                let mut need_reschedule = false;
-                let Ok((pile_entity, pile_child)) = pile_q.get_single_mut() else {
-                    info!("BUG: Not found PILE");
-                    return;
-                };
-
-                for entity in cards.iter() {
-                    need_reschedule = true;
-                    let (_, glb_trans, mut trans, mut status) = card_q.get_mut(*entity).unwrap();
-                    trans.translation = glb_trans.translation();
-                    commands.entity(pile_entity).remove_children(&[*entity]);
-
-                    let tween = Tween::new(
-                        EaseFunction::QuadraticInOut,
-                        std::time::Duration::from_millis(300),
-                        TransformPositionLens {
-                            start: glb_trans.translation(),
-                            end: table_pos,
-                        },
-                    )
-                    .with_completed_event(2);
+    for Incoming(data) in accept_play_ev.read() {
+        let mut table_pos = layout.table_position();
+        let cards = card_map.list_from_str(&data.cards);
+
+        if global.game.local_player.pos as usize == data.cur_player {
+            let mut need_reschedule = false;
+            let Ok((pile_entity, pile_child)) = pile_q.get_single_mut() else {
+                info!("BUG: Not found PILE");
+                return;
+            };
+
+            for entity in cards.iter() {
+                need_reschedule = true;
+                let (_, glb_trans, mut trans, mut status) = card_q.get_mut(*entity).unwrap();
+                trans.translation = glb_trans.translation();
+                commands.entity(pile_entity).remove_children(&[*entity]);
+
+                let tween = Tween::new(
+                    EaseFunction::QuadraticInOut,
+                    settings.animation_speed.scale(300),
+                    TransformPositionLens {
+                        start: glb_trans.translation(),
+                        end: table_pos,
+                    },
+                )
+                .with_completed_event(2);
 
-                    table_pos.x += 35.;
+                table_pos.x += layout.card_spacing();
 
-                    commands.entity(*entity).insert(Animator::new(tween));
+                commands.entity(*entity).insert(Animator::new(tween));
 
-                    *status = CStatus::Animating;
-                }
+                *status = CStatus::Animating;
+            }
 
-                if need_reschedule {
-                    let cards = pile_child.iter().copied().collect::<Vec<Entity>>();
-                    reschedule_pile_ev.send(SchedulePileEvent(cards));
-                }
-            } else {
-                // Foregin player play card animation
-                for entity in cards.iter() {
-                    let mut card = card_q.get_mut(*entity).unwrap();
+            if need_reschedule {
+                // The played cards leave the pile with the commands
+                let cards = pile_child
+                    .iter()
+                    .filter(|entity| !cards.contains(entity))
+                    .copied()
+                    .collect::<Vec<Entity>>();
+                reschedule_pile_ev.send(SchedulePileEvent(cards));
+            }
+        } else {
+            // Foregin player play card animation
+            for entity in cards.iter() {
+                let mut card = card_q.get_mut(*entity).unwrap();
 
-                    *card.0 = Visibility::Visible;
+                *card.0 = Visibility::Visible;
 
-                    let mut back_card_pos = Vec3::splat(0.);
+                let mut back_card_pos = Vec3::splat(0.);
 
-                    for (trans, p_pos) in back_card_q.iter() {
-                        if p_pos.0 == data.cur_player as i32 {
-                            back_card_pos = trans.translation;
-                        }
+                for (trans, p_pos) in back_card_q.iter() {
+                    if p_pos.0 == data.cur_player as i32 {
+                        back_card_pos = trans.translation;
                     }
+                }
 
-                    let tween = Tween::new(
-                        EaseFunction::QuadraticIn,
-                        std::time::Duration::from_millis(300),
-                        TransformPositionLens {
-                            start: back_card_pos,
-                            end: table_pos,
-                        },
-                    )
-                    .with_completed_event(2);
+                let tween = Tween::new(
+                    EaseFunction::QuadraticIn,
+                    settings.animation_speed.scale(300),
+                    TransformPositionLens {
+                        start: back_card_pos,
+                        end: table_pos,
+                    },
+                )
+                .with_completed_event(2);
 
-                    table_pos.x += 35.;
+                table_pos.x += layout.card_spacing();
 
-                    commands.entity(*entity).insert(Animator::new(tween));
-                }
+                commands.entity(*entity).insert(Animator::new(tween));
             }
         }
     }
@@ -349,43 +435,65 @@ fn update_status(
     }
 }
 
-fn click_card(
-    event: Listener<Pointer<Click>>,
+/// The mouse, a finger is followed by the touch gestures
+fn click_card(event: Listener<Pointer<Click>>, mut toggle_card_ev: EventWriter<ToggleCard>) {
+    if event.pointer_id.is_touch() {
+        return;
+    }
+    toggle_card_ev.send(ToggleCard(event.listener()));
+}
+
+/// Raises an idle card to select it, lowers a selected one
+fn toggle_card(
     mut commands: Commands,
+    mut toggle_card_ev: EventReader<ToggleCard>,
     mut card_q: Query<(&mut Transform, &mut CStatus), With<Card>>,
+    settings: Res<Settings>,
+    mut play_sound: EventWriter<PlaySound>,
 ) {
-    let (tran, mut status) = card_q.get_mut(event.target).unwrap();
+    for ToggleCard(entity) in toggle_card_ev.read() {
+        let Ok((tran, mut status)) = card_q.get_mut(*entity) else {
+            continue;
+        };
+
+        let (lift, completed) = match *status {
+            CStatus::Idle => (15., 0),
+            CStatus::Active => (-15., 1),
+            _ => continue,
+        };
+
+        let tween = Tween::new(
+            EaseFunction::CubicIn,
+            settings.animation_speed.scale(100),
+            TransformPositionLens {
+                start: tran.translation,
+                end: tran.translation.add(Vec3::new(0., lift, 0.)),
+            },
+        )
+        .with_completed_event(completed);
+
+        commands.entity(*entity).insert(Animator::new(tween));
+        *status = CStatus::Animating;
+        play_sound.send(PlaySound(Sound::Select));
+    }
+}
 
-    match *status {
-        CStatus::Idle => {
+fn highlight_cards(mut commands: Commands, mut highlight_ev: EventReader<HighlightCards>) {
+    for HighlightCards(cards) in highlight_ev.read() {
+        for card in cards {
             let tween = Tween::new(
-                EaseFunction::CubicIn,
-                std::time::Duration::from_millis(100),
-                TransformPositionLens {
-                    start: tran.translation,
-                    end: tran.translation.add(Vec3::new(0., 15., 0.)),
+                EaseFunction::QuadraticInOut,
+                std::time::Duration::from_millis(250),
+                SpriteColorLens {
+                    start: Color::WHITE,
+                    end: HIGHLIGHT_FLASH,
                 },
             )
-            .with_completed_event(0);
+            .with_repeat_count(RepeatCount::Finite(4))
+            .with_repeat_strategy(RepeatStrategy::MirroredRepeat);
 
-            commands.entity(event.target).insert(Animator::new(tween));
-            *status = CStatus::Animating;
+            commands.entity(*card).insert(Animator::new(tween));
         }
-        CStatus::Active => {
-            let tween = Tween::new(
-                EaseFunction::CubicIn,
-                std::time::Duration::from_millis(100),
-                TransformPositionLens {
-                    start: tran.translation,
-                    end: tran.translation.add(Vec3::new(0., -15., 0.)),
-                },
-            )
-            .with_completed_event(1);
-
-            commands.entity(event.target).insert(Animator::new(tween));
-            *status = CStatus::Animating;
-        }
-        _ => {}
     }
 }
 
@@ -413,55 +521,114 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
     commands.insert_resource(card_map);
 }
 
+fn spawn_pile(
+    commands: &mut Commands,
+    cards: Vec<Entity>,
+    card_q: &mut Query<&mut Visibility, With<Card>>,
+    layout: &Layout,
+    schedule_pile_event: &mut EventWriter<SchedulePileEvent>,
+) {
+    for c in cards.iter() {
+        let mut vis = card_q.get_mut(*c).unwrap();
+        *vis = Visibility::Visible;
+    }
+
+    commands
+        .spawn((
+            SpatialBundle {
+                transform: Transform::from_translation(layout.pile_position()),
+                ..Default::default()
+            },
+            Pile,
+        ))
+        .push_children(&cards);
+
+    schedule_pile_event.send(SchedulePileEvent(cards));
+}
+
 fn spawn_player_card(
     mut commands: Commands,
     card_map: Res<CardMap>,
-    mut event_reader: EventReader<MessageEvents>,
+    mut start_game_ev: EventReader<Incoming<AcceptStartGame>>,
     mut schedule_pile_event: EventWriter<SchedulePileEvent>,
     mut card_q: Query<&mut Visibility, With<Card>>,
-    global: Res<Global>,
+    mut order: ResMut<HandOrder>,
+    layout: Res<Layout>,
 ) {
-    for events in event_reader.read() {
-        for message in events.read::<GameSystemChannel, AcceptStartGame>() {
-            info!("GOT ACCEPT START GAME!");
-            let cards: Vec<Entity> = card_map.list_from_str(&message.cards);
-
-            for c in cards.iter() {
-                let mut vis = card_q.get_mut(*c).unwrap();
-                *vis = Visibility::Visible;
-            }
+    for Incoming(message) in start_game_ev.read() {
+        info!("GOT ACCEPT START GAME!");
+        // A new deal, a custom order of the last one means nothing
+        order.0.clear();
+        let cards: Vec<Entity> = card_map.list_from_str(&message.cards);
+
+        spawn_pile(
+            &mut commands,
+            cards,
+            &mut card_q,
+            &layout,
+            &mut schedule_pile_event,
+        );
+    }
+}
 
-            commands
-                .spawn((
-                    SpatialBundle {
-                        transform: Transform::from_translation(global.game.local_player.pile_pos),
-                        ..Default::default()
-                    },
-                    Pile,
-                ))
-                .push_children(&cards);
+/// Hides the hand in the pile and lays out the one of the player taking
+/// the device
+#[allow(clippy::too_many_arguments)]
+fn show_hand(
+    mut commands: Commands,
+    card_map: Res<CardMap>,
+    mut show_hand_ev: EventReader<ShowHand>,
+    mut schedule_pile_event: EventWriter<SchedulePileEvent>,
+    pile_q: Query<(Entity, &Children), With<Pile>>,
+    mut card_q: Query<&mut Visibility, With<Card>>,
+    mut status_q: Query<&mut CStatus, With<Card>>,
+    mut order: ResMut<HandOrder>,
+    layout: Res<Layout>,
+) {
+    for ShowHand(hand) in show_hand_ev.read() {
+        order.0.clear();
 
-            schedule_pile_event.send(SchedulePileEvent(cards));
+        for (pile, children) in pile_q.iter() {
+            for card in children.iter() {
+                if let Ok(mut vis) = card_q.get_mut(*card) {
+                    *vis = Visibility::Hidden;
+                }
+                // Nothing stays selected for the next player
+                if let Ok(mut status) = status_q.get_mut(*card) {
+                    *status = CStatus::Idle;
+                }
+            }
+
+            commands.entity(pile).clear_children();
+            commands.entity(pile).despawn();
         }
+
+        let cards = card_map.list_from_str(hand);
+
+        spawn_pile(
+            &mut commands,
+            cards,
+            &mut card_q,
+            &layout,
+            &mut schedule_pile_event,
+        );
     }
 }
 
 pub fn handle_end_match_event(
-    mut event_reader: EventReader<MessageEvents>,
+    mut end_match_ev: EventReader<Incoming<EndMatch>>,
     mut pile_q: Query<Entity, With<Pile>>,
     mut card_q: Query<&mut Visibility, With<Card>>,
     mut commands: Commands,
 ) {
-    for event in event_reader.read() {
-        for _end_match in event.read::<GameSystemChannel, EndMatch>() {
-            for mut old_card_vis in card_q.iter_mut() {
-                *old_card_vis = Visibility::Hidden;
-            }
+    for _ in end_match_ev.read() {
+        for mut old_card_vis in card_q.iter_mut() {
+            *old_card_vis = Visibility::Hidden;
+        }
 
-            for e in pile_q.iter_mut() {
-                commands.entity(e).clear_children();
-                commands.entity(e).despawn();
-            }
+        for e in pile_q.iter_mut() {
+            commands.entity(e).clear_children();
+            commands.entity(e).despawn();
         }
     }
 }
@@ -0,0 +1,199 @@
+use bevy::prelude::*;
+use bevy_mod_picking::prelude::*;
+use naia_bevy_demo_shared::components::{card::Card as CardValue, cards::Cards, hand::Hand};
+
+use crate::{
+    assets::UiAssets,
+    locale::Locale,
+    settings::{Settings, SortMode},
+};
+
+use super::{super::layout::Layout, CStatus, Card, Pile, SchedulePileEvent};
+
+/// How far the pointer moves before a press on a card becomes a drag
+const DRAG_THRESHOLD: f32 = 5.;
+
+/// The cards in hand from left to right, as last laid out. In custom mode the
+/// next layout keeps it.
+#[derive(Resource, Default)]
+pub struct HandOrder(pub Vec<Entity>);
+
+#[derive(Component)]
+pub struct SortBtn;
+
+#[derive(Component)]
+pub struct SortBtnText;
+
+/// Orders the cards in hand by the sort mode
+pub fn ordered(cards: &[(Entity, CardValue)], mode: SortMode, order: &HandOrder) -> Vec<Entity> {
+    let mut hand = Hand::from_cards(&cards.iter().map(|(_, card)| *card).collect::<Vec<_>>());
+
+    match mode {
+        SortMode::Rank | SortMode::Custom => hand.sort(),
+        SortMode::Suit => hand.sort_suit_descending_rank(),
+        SortMode::Combination => hand.sort_by_combination(),
+    }
+
+    let mut entities: Vec<Entity> = hand
+        .cards
+        .iter()
+        .filter_map(|value| cards.iter().find(|(_, card)| card == value))
+        .map(|(entity, _)| *entity)
+        .collect();
+
+    if mode == SortMode::Custom {
+        // The cards of the custom order first, new ones after by rank
+        entities.sort_by_key(|entity| {
+            order
+                .0
+                .iter()
+                .position(|ordered| ordered == entity)
+                .unwrap_or(usize::MAX)
+        });
+    }
+
+    entities
+}
+
+pub fn drag_card(
+    event: Listener<Pointer<Drag>>,
+    mut card_q: Query<(&mut Transform, &mut CStatus), With<Card>>,
+) {
+    if event.pointer_id.is_touch() {
+        return;
+    }
+    let Ok((mut trans, mut status)) = card_q.get_mut(event.listener()) else {
+        return;
+    };
+
+    match *status {
+        CStatus::Idle if event.distance.length() > DRAG_THRESHOLD => {
+            *status = CStatus::Dragging;
+            trans.translation.x += event.distance.x;
+            trans.translation.z += 10.;
+        }
+        CStatus::Dragging => {
+            trans.translation.x += event.delta.x;
+        }
+        _ => {}
+    }
+}
+
+/// Moves the dropped card to the slot under it and keeps that order
+pub fn drop_card(
+    event: Listener<Pointer<DragEnd>>,
+    mut card_q: Query<(&Transform, &mut CStatus), With<Card>>,
+    pile_q: Query<&Children, With<Pile>>,
+    mut order: ResMut<HandOrder>,
+    mut settings: ResMut<Settings>,
+    layout: Res<Layout>,
+    mut reschedule_pile_ev: EventWriter<SchedulePileEvent>,
+) {
+    let card = event.listener();
+    let Ok((trans, mut status)) = card_q.get_mut(card) else {
+        return;
+    };
+    let CStatus::Dragging = *status else {
+        return;
+    };
+    *status = CStatus::Idle;
+
+    let Ok(children) = pile_q.get_single() else {
+        return;
+    };
+
+    if let Some(from) = order.0.iter().position(|entity| *entity == card) {
+        let last = order.0.len() - 1;
+        let slot = trans.translation.x / layout.card_spacing();
+        let to = (slot.round().max(0.) as usize).min(last);
+
+        if from != to {
+            let card = order.0.remove(from);
+            order.0.insert(to, card);
+
+            if settings.sort_mode != SortMode::Custom {
+                settings.sort_mode = SortMode::Custom;
+            }
+        }
+    }
+
+    reschedule_pile_ev.send(SchedulePileEvent(children.to_vec()));
+}
+
+pub fn spawn_sort_btn(mut commands: Commands, res: Res<UiAssets>, locale: Locale) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(10.),
+                left: Val::Px(10.),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    SortBtn,
+                    ButtonBundle {
+                        style: Style {
+                            padding: UiRect::axes(Val::Px(8.), Val::Px(6.)),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..Default::default()
+                        },
+                        background_color: Color::rgb(0.15, 0.15, 0.15).into(),
+                        ..Default::default()
+                    },
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        SortBtnText,
+                        TextBundle::from_section(
+                            "",
+                            TextStyle {
+                                font: locale.font(&res),
+                                font_size: 12.0,
+                                color: Color::ANTIQUE_WHITE,
+                            },
+                        ),
+                    ));
+                });
+        });
+}
+
+pub fn sort_btn_click(
+    interaction_q: Query<&Interaction, (Changed<Interaction>, With<SortBtn>)>,
+    mut settings: ResMut<Settings>,
+) {
+    for interaction in interaction_q.iter() {
+        if let Interaction::Pressed = interaction {
+            settings.sort_mode = settings.sort_mode.next();
+        }
+    }
+}
+
+/// Shows the current mode, also after a drag or a change in the settings
+pub fn update_sort_btn(
+    mut text_q: Query<(&mut Text, Ref<SortBtnText>)>,
+    settings: Res<Settings>,
+    res: Res<UiAssets>,
+    locale: Locale,
+) {
+    for (mut text, marker) in text_q.iter_mut() {
+        if !marker.is_added() && !settings.is_changed() && !locale.is_changed() {
+            continue;
+        }
+
+        let mode = locale.t(settings.sort_mode.id());
+
+        *text = Text::from_section(
+            locale.t_args("button-sort", &[("mode", &mode)]),
+            TextStyle {
+                font: locale.font(&res),
+                font_size: 12.0,
+                color: Color::ANTIQUE_WHITE,
+            },
+        );
+    }
+}
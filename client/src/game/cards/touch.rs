@@ -0,0 +1,182 @@
+use bevy::prelude::*;
+use bevy_mod_picking::prelude::*;
+use bevy_tweening::{lens::SpriteColorLens, *};
+
+use crate::resources::Global;
+
+use super::{
+    super::controller::{selected_cards, PlayEvent},
+    order::HandOrder,
+    CStatus, Card, Ordinal, ToggleCard,
+};
+
+/// How far, in pixels, a finger moves before a press becomes a swipe
+const SWIPE_THRESHOLD: f32 = 12.;
+/// The hit area of a card reaches below it, where the finger rests
+pub const HIT_AREA: Vec2 = Vec2::new(34., 75.);
+const HIT_AREA_OFFSET: Vec2 = Vec2::new(0., -12.);
+const PRESS_FLASH: Color = Color::rgb(1., 0.9, 0.5);
+
+/// A transparent sprite under a card, larger than it, so fingers find it
+#[derive(Component)]
+pub struct HitArea;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Gesture {
+    /// Sideways, every card crossed takes the state of the first one
+    Range { select: bool },
+    /// Up from a selected card, plays the selection
+    Play,
+}
+
+/// The finger on the hand, the mouse keeps its click and drag
+#[derive(Default)]
+pub struct Swipe {
+    origin: Option<Entity>,
+    gesture: Option<Gesture>,
+}
+
+pub fn hit_area() -> impl Bundle {
+    (
+        HitArea,
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::NONE,
+                custom_size: Some(HIT_AREA),
+                ..Default::default()
+            },
+            transform: Transform::from_translation(HIT_AREA_OFFSET.extend(-0.05)),
+            ..Default::default()
+        },
+    )
+}
+
+/// The card a pointer event is about, also when it hit the hit area
+fn card_of(
+    entity: Entity,
+    hit_area_q: &Query<&Parent, With<HitArea>>,
+    order: &HandOrder,
+) -> Option<Entity> {
+    let card = hit_area_q.get(entity).map_or(entity, |parent| parent.get());
+    order.0.contains(&card).then_some(card)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn touch_gestures(
+    mut down_ev: EventReader<Pointer<Down>>,
+    mut drag_ev: EventReader<Pointer<Drag>>,
+    mut drag_enter_ev: EventReader<Pointer<DragEnter>>,
+    mut up_ev: EventReader<Pointer<Up>>,
+    mut swipe: Local<Swipe>,
+    hit_area_q: Query<&Parent, With<HitArea>>,
+    card_q: Query<(Entity, &CStatus, &Ordinal), With<Card>>,
+    order: Res<HandOrder>,
+    global: Res<Global>,
+    mut toggle_card_ev: EventWriter<ToggleCard>,
+    mut play_ev: EventWriter<PlayEvent>,
+    mut feedback_ev: EventWriter<TouchFeedback>,
+) {
+    let is_active = |card: Entity| {
+        card_q
+            .get(card)
+            .is_ok_and(|(_, status, _)| matches!(status, CStatus::Active))
+    };
+
+    for event in down_ev.read().filter(|event| event.pointer_id.is_touch()) {
+        let origin = card_of(event.target, &hit_area_q, &order);
+        *swipe = Swipe {
+            origin,
+            gesture: None,
+        };
+        if let Some(card) = origin {
+            feedback_ev.send(TouchFeedback(card));
+        }
+    }
+
+    for event in drag_ev.read().filter(|event| event.pointer_id.is_touch()) {
+        let Some(origin) = swipe.origin else {
+            continue;
+        };
+        if swipe.gesture.is_some() {
+            continue;
+        }
+
+        // Screen pixels, up is negative
+        let distance = event.distance;
+        if -distance.y > SWIPE_THRESHOLD && -distance.y > distance.x.abs() {
+            if is_active(origin) {
+                swipe.gesture = Some(Gesture::Play);
+            }
+        } else if distance.x.abs() > SWIPE_THRESHOLD {
+            let select = !is_active(origin);
+            swipe.gesture = Some(Gesture::Range { select });
+            toggle_card_ev.send(ToggleCard(origin));
+        }
+    }
+
+    for event in drag_enter_ev
+        .read()
+        .filter(|event| event.pointer_id.is_touch())
+    {
+        let Some(Gesture::Range { select }) = swipe.gesture else {
+            continue;
+        };
+        let Some(card) = card_of(event.target, &hit_area_q, &order) else {
+            continue;
+        };
+
+        if is_active(card) != select {
+            toggle_card_ev.send(ToggleCard(card));
+            feedback_ev.send(TouchFeedback(card));
+        }
+    }
+
+    for _ in up_ev.read().filter(|event| event.pointer_id.is_touch()) {
+        let Swipe { origin, gesture } = std::mem::take(&mut *swipe);
+
+        let my_turn = global.game.active_player_pos == global.game.local_player.pos;
+
+        match (origin, gesture) {
+            // A tap
+            (Some(card), None) => {
+                toggle_card_ev.send(ToggleCard(card));
+            }
+            (Some(_), Some(Gesture::Play)) if my_turn => {
+                play_ev.send(PlayEvent(selected_cards(&card_q)));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Flashes a card under the finger, and buzzes the phone where it can
+#[derive(Event)]
+pub struct TouchFeedback(pub Entity);
+
+pub fn touch_feedback(mut commands: Commands, mut feedback_ev: EventReader<TouchFeedback>) {
+    for TouchFeedback(card) in feedback_ev.read() {
+        let tween = Tween::new(
+            EaseFunction::QuadraticOut,
+            std::time::Duration::from_millis(80),
+            SpriteColorLens {
+                start: Color::WHITE,
+                end: PRESS_FLASH,
+            },
+        )
+        .with_repeat_count(RepeatCount::Finite(2))
+        .with_repeat_strategy(RepeatStrategy::MirroredRepeat);
+
+        commands.entity(*card).insert(Animator::new(tween));
+        vibrate();
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn vibrate() {
+    if let Some(window) = web_sys::window() {
+        window.navigator().vibrate_with_duration(10);
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn vibrate() {}
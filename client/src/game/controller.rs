@@ -1,18 +1,20 @@
 use bevy::prelude::*;
-use naia_bevy_client::{events::MessageEvents, Client};
 use naia_bevy_demo_shared::{
-    channels::{EntityAssignmentChannel, GameSystemChannel, PlayerActionChannel},
     components::Host,
-    messages::{
-        AcceptPlayCard, AcceptStartGame, EndMatch, EntityAssignment, NewMatch, StartGame,
-        UpdateTurn,
-    },
+    messages::{AcceptPlayCard, AcceptStartGame, EndMatch, NewMatch, StartGame, UpdateTurn},
 };
 
-use crate::{assets::UiAssets, resources::Global, states::MainState};
+use crate::{
+    assets::UiAssets,
+    authority::{Incoming, Outgoing},
+    components::LocalPlayer,
+    locale::Locale,
+    states::MainState,
+};
 
 use super::{
     cards::{CStatus, Card, Ordinal},
+    layout::Layout,
     player_ui::{Bottom, PlayerPos},
 };
 
@@ -30,8 +32,14 @@ impl Plugin for ControllerPlugin {
                     handle_end_match_event,
                 ),
             )
-            .add_systems(OnEnter(MainState::Lobby), spawn_play_controller)
+            // Only once, the lobby comes back when too few players are left
+            .add_systems(OnExit(MainState::Welcome), spawn_play_controller)
+            .add_systems(
+                Update,
+                place_play_controller.run_if(resource_changed::<Layout>()),
+            )
             .add_systems(OnEnter(MainState::Wait), hide_start_btn)
+            .add_systems(Update, show_start_btn.run_if(in_state(MainState::Lobby)))
             .add_systems(Update, player_btn_click.run_if(in_state(MainState::Lobby)))
             .add_systems(
                 Update,
@@ -62,99 +70,110 @@ pub fn hide_start_btn(mut vis_q: Query<&mut Visibility, With<StartContainer>>) {
     }
 }
 
+/// Only the host sees the start button, the role can change in the lobby
+pub fn show_start_btn(
+    local_q: Query<Has<Host>, With<LocalPlayer>>,
+    mut vis_q: Query<&mut Visibility, With<StartContainer>>,
+) {
+    let visibility = match local_q.get_single() {
+        Ok(true) => Visibility::Inherited,
+        _ => Visibility::Hidden,
+    };
+
+    for mut vis in vis_q.iter_mut() {
+        if *vis != visibility {
+            *vis = visibility;
+        }
+    }
+}
+
+/// Once the local player has a seat. In hot seat the marker goes around the
+/// players, the button stays the same
 pub fn spawn_start_btn(
     mut commands: Commands,
-    mut event_reader: EventReader<MessageEvents>,
-    host_query: Query<&Host>,
+    local_q: Query<(), Added<LocalPlayer>>,
+    container_q: Query<(), With<StartContainer>>,
     res: Res<UiAssets>,
-    global: Res<Global>,
+    locale: Locale,
 ) {
-    for events in event_reader.read() {
-        for _message in events.read::<EntityAssignmentChannel, EntityAssignment>() {
-            if let Some(player_entity) = global.player_entity {
-                // This player is not a host
-                if host_query.get(player_entity).is_err() {
-                    return;
-                }
-            }
+    if local_q.is_empty() || !container_q.is_empty() {
+        return;
+    }
 
-            let container = commands
-                .spawn((
-                    StartContainer,
-                    NodeBundle {
-                        style: Style {
-                            position_type: PositionType::Absolute,
-                            top: Val::Px(150.),
-                            justify_content: JustifyContent::Center,
-                            align_items: AlignItems::Center,
-                            width: Val::Percent(100.),
-                            height: Val::Px(100.),
-                            ..Default::default()
-                        },
-                        ..Default::default()
-                    },
-                ))
-                .id();
+    let container = commands
+        .spawn((
+            StartContainer,
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(150.),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    width: Val::Percent(100.),
+                    height: Val::Px(100.),
+                    ..Default::default()
+                },
+                visibility: Visibility::Hidden,
+                ..Default::default()
+            },
+        ))
+        .id();
 
-            let start_btn = commands
+    let start_btn = commands
+        .spawn(ButtonBundle {
+            style: Style {
+                width: Val::Px(60.),
+                height: Val::Px(40.),
+                margin: UiRect::all(Val::Px(4.)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            parent
                 .spawn(ButtonBundle {
                     style: Style {
                         width: Val::Px(60.),
                         height: Val::Px(40.),
-                        margin: UiRect::all(Val::Px(4.)),
                         justify_content: JustifyContent::Center,
                         align_items: AlignItems::Center,
-                        ..Default::default()
+                        ..default()
                     },
-                    ..Default::default()
+
+                    background_color: NORMAL_BUTTON.into(),
+                    ..default()
                 })
                 .with_children(|parent| {
-                    parent
-                        .spawn(ButtonBundle {
-                            style: Style {
-                                width: Val::Px(60.),
-                                height: Val::Px(40.),
-                                justify_content: JustifyContent::Center,
-                                align_items: AlignItems::Center,
-                                ..default()
-                            },
-
-                            background_color: NORMAL_BUTTON.into(),
-                            ..default()
-                        })
-                        .with_children(|parent| {
-                            parent.spawn(TextBundle::from_section(
-                                "Start",
-                                TextStyle {
-                                    font: res.font.clone(),
-                                    font_size: 16.0,
-                                    color: Color::rgb(0.9, 0.9, 0.9),
-                                },
-                            ));
-                        })
-                        .insert(StartBtn);
+                    parent.spawn(TextBundle::from_section(
+                        locale.t("button-start"),
+                        TextStyle {
+                            font: locale.font(&res),
+                            font_size: 16.0,
+                            color: Color::rgb(0.9, 0.9, 0.9),
+                        },
+                    ));
                 })
-                .id();
+                .insert(StartBtn);
+        })
+        .id();
 
-            commands.entity(container).add_child(start_btn);
-        }
-    }
+    commands.entity(container).add_child(start_btn);
 }
 
 pub fn handle_skip_event(
     mut vis_q: Query<&mut Visibility, With<PlayContainer>>,
-    mut event_reader: EventReader<MessageEvents>,
+    mut update_turn_ev: EventReader<Incoming<UpdateTurn>>,
     player_q: Query<&PlayerPos, With<Bottom>>,
 ) {
-    for event in event_reader.read() {
-        for message in event.read::<GameSystemChannel, UpdateTurn>() {
-            let mut vis = vis_q.get_single_mut().unwrap();
-            for player_pos in player_q.iter() {
-                if player_pos.0 == message.0 as i32 {
-                    *vis = Visibility::Visible;
-                } else {
-                    *vis = Visibility::Hidden;
-                }
+    for Incoming(message) in update_turn_ev.read() {
+        let mut vis = vis_q.get_single_mut().unwrap();
+        for player_pos in player_q.iter() {
+            if player_pos.0 == message.0 as i32 {
+                *vis = Visibility::Visible;
+            } else {
+                *vis = Visibility::Hidden;
             }
         }
     }
@@ -162,18 +181,16 @@ pub fn handle_skip_event(
 
 pub fn handle_start_game_event(
     mut vis_q: Query<&mut Visibility, With<PlayContainer>>,
-    mut event_reader: EventReader<MessageEvents>,
+    mut start_game_ev: EventReader<Incoming<AcceptStartGame>>,
     player_q: Query<&PlayerPos, With<Bottom>>,
 ) {
-    for event in event_reader.read() {
-        for message in event.read::<GameSystemChannel, AcceptStartGame>() {
-            let mut vis = vis_q.get_single_mut().unwrap();
-            for player_pos in player_q.iter() {
-                if player_pos.0 == message.active_player as i32 {
-                    *vis = Visibility::Visible;
-                } else {
-                    *vis = Visibility::Hidden;
-                }
+    for Incoming(message) in start_game_ev.read() {
+        let mut vis = vis_q.get_single_mut().unwrap();
+        for player_pos in player_q.iter() {
+            if player_pos.0 == message.active_player as i32 {
+                *vis = Visibility::Visible;
+            } else {
+                *vis = Visibility::Hidden;
             }
         }
     }
@@ -181,36 +198,54 @@ pub fn handle_start_game_event(
 
 pub fn update_play_controller(
     mut vis_q: Query<&mut Visibility, With<PlayContainer>>,
-    mut event_reader: EventReader<MessageEvents>,
+    mut accept_play_ev: EventReader<Incoming<AcceptPlayCard>>,
+    mut new_match_ev: EventReader<Incoming<NewMatch>>,
     player_q: Query<&PlayerPos, With<Bottom>>,
 ) {
-    for event in event_reader.read() {
-        for message in event.read::<GameSystemChannel, AcceptPlayCard>() {
-            let mut vis = vis_q.get_single_mut().unwrap();
-            for player_pos in player_q.iter() {
-                if player_pos.0 == message.next_player as i32 {
-                    *vis = Visibility::Visible;
-                } else {
-                    *vis = Visibility::Hidden;
-                }
+    for Incoming(message) in accept_play_ev.read() {
+        let mut vis = vis_q.get_single_mut().unwrap();
+        for player_pos in player_q.iter() {
+            if player_pos.0 == message.next_player as i32 {
+                *vis = Visibility::Visible;
+            } else {
+                *vis = Visibility::Hidden;
             }
         }
+    }
 
-        for message in event.read::<GameSystemChannel, NewMatch>() {
-            let mut vis = vis_q.get_single_mut().unwrap();
-            for player_pos in player_q.iter() {
-                if player_pos.0 == message.active_player as i32 {
-                    *vis = Visibility::Visible;
-                } else {
-                    *vis = Visibility::Hidden;
-                }
+    for Incoming(message) in new_match_ev.read() {
+        let mut vis = vis_q.get_single_mut().unwrap();
+        for player_pos in player_q.iter() {
+            if player_pos.0 == message.active_player as i32 {
+                *vis = Visibility::Visible;
+            } else {
+                *vis = Visibility::Hidden;
             }
         }
     }
 }
 
-pub fn spawn_play_controller(mut commands: Commands, res: Res<UiAssets>) {
+/// Keeps the play and pass buttons above the hand
+fn place_play_controller(
+    layout: Res<Layout>,
+    mut container_q: Query<&mut Style, With<PlayContainer>>,
+) {
+    let (bottom, right) = layout.controls_position();
+
+    for mut style in container_q.iter_mut() {
+        style.bottom = bottom;
+        style.right = right;
+    }
+}
+
+pub fn spawn_play_controller(
+    mut commands: Commands,
+    res: Res<UiAssets>,
+    locale: Locale,
+    layout: Res<Layout>,
+) {
     let show_player_control = Visibility::Hidden;
+    let (bottom, right) = layout.controls_position();
 
     let play_container = commands
         .spawn((
@@ -218,8 +253,8 @@ pub fn spawn_play_controller(mut commands: Commands, res: Res<UiAssets>) {
             NodeBundle {
                 style: Style {
                     position_type: PositionType::Absolute,
-                    bottom: Val::Px(230.),
-                    right: Val::Px(230.),
+                    bottom,
+                    right,
                     justify_content: JustifyContent::SpaceAround,
                     align_items: AlignItems::Center,
                     width: Val::Px(100.),
@@ -259,9 +294,9 @@ pub fn spawn_play_controller(mut commands: Commands, res: Res<UiAssets>) {
                 })
                 .with_children(|parent| {
                     parent.spawn(TextBundle::from_section(
-                        "Play",
+                        locale.t("button-play"),
                         TextStyle {
-                            font: res.font.clone(),
+                            font: locale.font(&res),
                             font_size: 12.0,
                             color: Color::ANTIQUE_WHITE,
                         },
@@ -298,9 +333,9 @@ pub fn spawn_play_controller(mut commands: Commands, res: Res<UiAssets>) {
                 })
                 .with_children(|parent| {
                     parent.spawn(TextBundle::from_section(
-                        "Skip",
+                        locale.t("button-skip"),
                         TextStyle {
-                            font: res.font.clone(),
+                            font: locale.font(&res),
                             font_size: 12.0,
                             color: Color::ANTIQUE_WHITE,
                         },
@@ -328,6 +363,20 @@ pub struct PlayBtn;
 #[derive(Component)]
 pub struct SkipBtn;
 
+/// The raised cards, low to high
+pub fn selected_cards(card_q: &Query<(Entity, &CStatus, &Ordinal), With<Card>>) -> Vec<Entity> {
+    let mut cards = vec![];
+
+    for (entity, status, ordinal) in card_q.iter() {
+        if let CStatus::Active = *status {
+            cards.push((entity, ordinal.get()));
+        }
+    }
+
+    cards.sort_by_key(|c| c.1);
+    cards.iter().map(|c| c.0).collect()
+}
+
 #[allow(clippy::type_complexity)]
 pub fn player_btn_click(
     mut interaction_query: Query<
@@ -337,7 +386,7 @@ pub fn player_btn_click(
         ),
         (Changed<Interaction>, With<Button>),
     >,
-    mut client: Client,
+    mut start_game_ev: EventWriter<Outgoing<StartGame>>,
     mut play_event_writer: EventWriter<PlayEvent>,
     mut skip_ev: EventWriter<SkipTurnEvent>,
     card_q: Query<(Entity, &CStatus, &Ordinal), With<Card>>,
@@ -346,20 +395,11 @@ pub fn player_btn_click(
         if let Interaction::Pressed = *interaction {
             if start_btn.is_some() {
                 // info!("Clicked start!");
-                client.send_message::<PlayerActionChannel, StartGame>(&StartGame::default());
+                start_game_ev.send(Outgoing(StartGame::default()));
             }
             if play_btn.is_some() {
                 // info!("Clicked play!");
-                let mut cards = vec![];
-
-                for (entity, status, ordinal) in card_q.iter() {
-                    if let CStatus::Active = *status {
-                        cards.push((entity, ordinal.get()));
-                    }
-                }
-
-                cards.sort_by_key(|c| c.1);
-                play_event_writer.send(PlayEvent(cards.iter().map(|c| c.0).collect()));
+                play_event_writer.send(PlayEvent(selected_cards(&card_q)));
             }
             if skip_btn.is_some() {
                 // info!("Clicked skip!");
@@ -370,14 +410,12 @@ pub fn player_btn_click(
 }
 
 pub fn handle_end_match_event(
-    mut event_reader: EventReader<MessageEvents>,
+    mut end_match_ev: EventReader<Incoming<EndMatch>>,
     mut vis_q: Query<&mut Visibility, With<PlayContainer>>,
 ) {
-    for event in event_reader.read() {
-        for _end_match in event.read::<GameSystemChannel, EndMatch>() {
-            for mut vis in vis_q.iter_mut() {
-                *vis = Visibility::Hidden;
-            }
+    for _ in end_match_ev.read() {
+        for mut vis in vis_q.iter_mut() {
+            *vis = Visibility::Hidden;
         }
     }
 }
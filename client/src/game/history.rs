@@ -0,0 +1,88 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use naia_bevy_demo_shared::components::{card::Card, hand::Hand, suit::Suit, Player, Table};
+
+use crate::{locale::Locale, states::MainState};
+
+/// A collapsible list of the plays and passes of the current round, read from
+/// the replicated table so a reconnecting client sees it too
+pub struct HistoryPlugin;
+
+impl Plugin for HistoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, history_panel.run_if(in_state(MainState::Game)));
+    }
+}
+
+fn short_card(card: &Card) -> String {
+    let rank = match card.rank.to_char() {
+        'T' => "10".to_string(),
+        rank => rank.to_string(),
+    };
+    let suit = match card.suit {
+        Suit::Spades => '♠',
+        Suit::Clubs => '♣',
+        Suit::Diamonds => '♦',
+        Suit::Hearts => '♥',
+    };
+
+    format!("{}{}", rank, suit)
+}
+
+/// Rank and suit symbol of each card, low to high
+pub fn short_hand(hand: &Hand) -> String {
+    let mut cards = hand.cards.clone();
+    cards.sort_by(|a, b| a.cmp_rank_suit(b));
+
+    cards.iter().map(short_card).collect::<Vec<_>>().join(" ")
+}
+
+fn history_panel(
+    mut egui_ctx: EguiContexts,
+    table_q: Query<&Table>,
+    player_q: Query<&Player>,
+    locale: Locale,
+) {
+    let round = table_q
+        .get_single()
+        .map(|table| table.round())
+        .unwrap_or_default();
+
+    egui::Window::new(locale.t("history-title"))
+        .id(egui::Id::new("history-window"))
+        .default_open(false)
+        .resizable(false)
+        .anchor(egui::Align2::LEFT_TOP, [8., 8.])
+        .show(egui_ctx.ctx_mut(), |ui| {
+            if round.is_empty() {
+                ui.weak(locale.t("history-empty"));
+                return;
+            }
+
+            egui::Grid::new("history-grid")
+                .num_columns(2)
+                .spacing([12., 4.])
+                .striped(true)
+                .show(ui, |ui| {
+                    for trick in round.iter() {
+                        let name = player_q
+                            .iter()
+                            .find(|player| *player.pos == trick.pos)
+                            .map(|player| player.name())
+                            .unwrap_or_default();
+                        ui.label(name);
+
+                        match &trick.cards {
+                            Some(cards) => {
+                                ui.label(short_hand(cards))
+                                    .on_hover_text(locale.hand_name(cards));
+                            }
+                            None => {
+                                ui.weak(locale.t("history-passed"));
+                            }
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+}
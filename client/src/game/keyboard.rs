@@ -0,0 +1,250 @@
+use bevy::prelude::*;
+use bevy_egui::EguiContexts;
+use naia_bevy_demo_shared::components::{
+    card::Card as CardValue, cards::ThirteenCombination, rank::Rank, suit::Suit,
+};
+
+use crate::{
+    resources::Global,
+    settings::{Settings, SettingsPanel},
+    states::MainState,
+};
+
+use super::{
+    cards::{order::HandOrder, CStatus, Card, Ordinal, ToggleCard},
+    controller::{selected_cards, PlayEvent, SkipTurnEvent},
+};
+
+/// Plays the hand without the mouse: a cursor over the cards in hand, keys to
+/// select, play and pass, and the number keys to pick a combination
+pub struct KeyboardPlugin;
+
+impl Plugin for KeyboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CardCursor>()
+            .add_systems(OnExit(MainState::Welcome), spawn_focus)
+            .add_systems(OnExit(MainState::Game), hide_focus)
+            .add_systems(
+                Update,
+                (keyboard_input, update_focus)
+                    .chain()
+                    .run_if(in_state(MainState::Game)),
+            );
+    }
+}
+
+/// The combinations the number keys select, from 1
+const QUICK_SELECT: [(KeyCode, ThirteenCombination); 6] = [
+    (KeyCode::Key1, ThirteenCombination::Single),
+    (KeyCode::Key2, ThirteenCombination::Pair),
+    (KeyCode::Key3, ThirteenCombination::Triple),
+    (KeyCode::Key4, ThirteenCombination::Quartet),
+    (KeyCode::Key5, ThirteenCombination::Sequence),
+    (KeyCode::Key6, ThirteenCombination::DoubleSequence),
+];
+
+/// The focused slot of the hand, hidden until a key moves it
+#[derive(Resource, Default)]
+pub struct CardCursor {
+    pub index: usize,
+    pub visible: bool,
+}
+
+#[derive(Component)]
+struct FocusHighlight;
+
+fn spawn_focus(mut commands: Commands) {
+    commands.spawn((
+        FocusHighlight,
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgba(1., 0.85, 0.2, 0.5),
+                custom_size: Some(Vec2::new(36., 51.)),
+                ..Default::default()
+            },
+            visibility: Visibility::Hidden,
+            ..Default::default()
+        },
+    ));
+}
+
+fn hide_focus(
+    mut cursor: ResMut<CardCursor>,
+    mut focus_q: Query<&mut Visibility, With<FocusHighlight>>,
+) {
+    cursor.visible = false;
+    for mut vis in focus_q.iter_mut() {
+        *vis = Visibility::Hidden;
+    }
+}
+
+/// The cards to select for a combination with the focused card, `None` if the
+/// hand has none
+fn quick_select(
+    hand: &[(Entity, CardValue)],
+    focused: Entity,
+    kind: ThirteenCombination,
+) -> Option<Vec<Entity>> {
+    let (_, card) = hand.iter().find(|(entity, _)| *entity == focused)?;
+
+    let same_rank = |rank: Rank, count: usize| -> Option<Vec<Entity>> {
+        // The focused card first so it is always part of the set
+        let mut cards: Vec<Entity> = hand
+            .iter()
+            .filter(|(entity, other)| other.rank == rank && *entity != focused)
+            .map(|(entity, _)| *entity)
+            .collect();
+        if rank == card.rank {
+            cards.insert(0, focused);
+        }
+        (cards.len() >= count).then(|| cards.into_iter().take(count).collect())
+    };
+
+    // A straight from the focused rank up, as long as the hand allows
+    let run = |width: usize| -> Option<Vec<Entity>> {
+        let mut cards = vec![];
+        let mut ordinal = card.rank.ordinal();
+
+        while ordinal < Rank::Two.ordinal() {
+            let Some(rank) = Rank::iterator().find(|rank| rank.ordinal() == ordinal) else {
+                break;
+            };
+            let Some(step) = same_rank(*rank, width) else {
+                break;
+            };
+            cards.extend(step);
+            ordinal += 1;
+        }
+
+        (cards.len() >= 3 * width).then_some(cards)
+    };
+
+    match kind {
+        ThirteenCombination::Single => same_rank(card.rank, 1),
+        ThirteenCombination::Pair => same_rank(card.rank, 2),
+        ThirteenCombination::Triple => same_rank(card.rank, 3),
+        ThirteenCombination::Quartet => same_rank(card.rank, 4),
+        ThirteenCombination::Sequence => run(1),
+        ThirteenCombination::DoubleSequence => run(2),
+        ThirteenCombination::NonSense => None,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn keyboard_input(
+    keys: Res<Input<KeyCode>>,
+    settings: Res<Settings>,
+    panel: Res<SettingsPanel>,
+    mut egui_ctx: EguiContexts,
+    global: Res<Global>,
+    order: Res<HandOrder>,
+    mut cursor: ResMut<CardCursor>,
+    card_q: Query<(Entity, &CStatus, &Ordinal), With<Card>>,
+    value_q: Query<(&Rank, &Suit), With<Card>>,
+    mut toggle_card_ev: EventWriter<ToggleCard>,
+    mut play_ev: EventWriter<PlayEvent>,
+    mut skip_ev: EventWriter<SkipTurnEvent>,
+) {
+    if panel.rebinding.is_some() || egui_ctx.ctx_mut().wants_keyboard_input() {
+        return;
+    }
+
+    let bindings = &settings.keys;
+    let len = order.0.len();
+
+    if len > 0 {
+        if cursor.index >= len {
+            cursor.index = len - 1;
+        }
+
+        // The first press only shows the cursor where it was
+        if keys.just_pressed(bindings.left) {
+            if cursor.visible {
+                cursor.index = cursor.index.saturating_sub(1);
+            }
+            cursor.visible = true;
+        }
+        if keys.just_pressed(bindings.right) {
+            if cursor.visible {
+                cursor.index = (cursor.index + 1).min(len - 1);
+            }
+            cursor.visible = true;
+        }
+
+        let focused = order.0[cursor.index];
+
+        if keys.just_pressed(bindings.toggle) {
+            toggle_card_ev.send(ToggleCard(focused));
+            cursor.visible = true;
+        }
+
+        for (key, kind) in QUICK_SELECT {
+            if !keys.just_pressed(key) {
+                continue;
+            }
+
+            let hand: Vec<(Entity, CardValue)> = order
+                .0
+                .iter()
+                .filter_map(|entity| {
+                    let (rank, suit) = value_q.get(*entity).ok()?;
+                    Some((*entity, CardValue::new(*rank, *suit)))
+                })
+                .collect();
+
+            let Some(wanted) = quick_select(&hand, focused, kind) else {
+                continue;
+            };
+
+            // Only the cards on the wrong side move
+            for (entity, status, _) in card_q.iter() {
+                if !order.0.contains(&entity) {
+                    continue;
+                }
+                let selected = matches!(status, CStatus::Active);
+                if selected != wanted.contains(&entity) {
+                    toggle_card_ev.send(ToggleCard(entity));
+                }
+            }
+            cursor.visible = true;
+        }
+    }
+
+    if global.game.active_player_pos != global.game.local_player.pos {
+        return;
+    }
+
+    if keys.just_pressed(bindings.play) {
+        play_ev.send(PlayEvent(selected_cards(&card_q)));
+    }
+    if keys.just_pressed(bindings.pass) {
+        skip_ev.send_default();
+    }
+}
+
+/// Keeps the highlight behind the focused card, also while it moves
+fn update_focus(
+    cursor: Res<CardCursor>,
+    order: Res<HandOrder>,
+    card_q: Query<(&GlobalTransform, &Visibility), (With<Card>, Without<FocusHighlight>)>,
+    mut focus_q: Query<(&mut Transform, &mut Visibility), With<FocusHighlight>>,
+) {
+    let Ok((mut trans, mut vis)) = focus_q.get_single_mut() else {
+        return;
+    };
+
+    let focused = order
+        .0
+        .get(cursor.index)
+        .and_then(|entity| card_q.get(*entity).ok())
+        .filter(|(_, card_vis)| cursor.visible && **card_vis != Visibility::Hidden);
+
+    match focused {
+        Some((card_trans, _)) => {
+            let translation = card_trans.translation();
+            trans.translation = translation.truncate().extend(translation.z - 0.5);
+            *vis = Visibility::Visible;
+        }
+        None => *vis = Visibility::Hidden,
+    }
+}
@@ -0,0 +1,213 @@
+use bevy::{prelude::*, render::camera::ScalingMode, window::PrimaryWindow};
+use serde::Deserialize;
+
+use crate::{resources::Global, systems::MainCamera};
+
+/// Keeps the table in view whatever the window size, and switches to the
+/// portrait arrangement when the window is taller than wide
+pub struct LayoutPlugin;
+
+impl Plugin for LayoutPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Layout>().add_systems(
+            Update,
+            (
+                detect_orientation,
+                fit_camera,
+                sync_global.run_if(resource_changed::<Layout>()),
+            )
+                .chain(),
+        );
+    }
+}
+
+/// The bevy UI follows the view, within limits so it stays readable
+const UI_SCALE_RANGE: (f64, f64) = (0.6, 2.);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Orientation {
+    #[default]
+    Landscape,
+    Portrait,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Seat {
+    Bottom,
+    Left,
+    Top,
+    Right,
+}
+
+/// Where everything goes on the table, in world units. The camera always shows
+/// the whole `view`, so the cards grow and shrink with the window.
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct Arrangement {
+    view: (f32, f32),
+    pile_position: (f32, f32, f32),
+    table_position: (f32, f32, f32),
+    board_position: (f32, f32, f32),
+    board_size: (f32, f32),
+    card_spacing: f32,
+    /// Distance of the play and pass buttons from the bottom right corner, in
+    /// percent of the window
+    controls_position: (f32, f32),
+    p0_position: (f32, f32),
+    p1_position: (f32, f32),
+    p2_position: (f32, f32),
+    p3_position: (f32, f32),
+}
+
+/// The built in arrangements, `game.config.ron` may replace them
+const LANDSCAPE: Arrangement = Arrangement {
+    view: (960., 600.),
+    pile_position: (-200., -100., 10.),
+    table_position: (-150., 50., 10.),
+    board_position: (0., 50., 3.),
+    board_size: (600., 300.),
+    card_spacing: 35.,
+    controls_position: (38., 24.),
+    p0_position: (0., -180.),
+    p1_position: (-315., 45.),
+    p2_position: (0., 200.),
+    p3_position: (315., 45.),
+};
+
+const PORTRAIT: Arrangement = Arrangement {
+    view: (480., 860.),
+    pile_position: (-180., -260., 10.),
+    table_position: (-120., 20., 10.),
+    board_position: (0., 20., 3.),
+    board_size: (440., 300.),
+    card_spacing: 30.,
+    controls_position: (27., 30.),
+    p0_position: (0., -350.),
+    p1_position: (-170., 240.),
+    p2_position: (0., 360.),
+    p3_position: (170., 240.),
+};
+
+#[derive(Resource, Debug)]
+pub struct Layout {
+    pub orientation: Orientation,
+    pub landscape: Arrangement,
+    pub portrait: Arrangement,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self {
+            orientation: Orientation::default(),
+            landscape: LANDSCAPE,
+            portrait: PORTRAIT,
+        }
+    }
+}
+
+impl Layout {
+    fn current(&self) -> &Arrangement {
+        match self.orientation {
+            Orientation::Landscape => &self.landscape,
+            Orientation::Portrait => &self.portrait,
+        }
+    }
+
+    pub fn view(&self) -> Vec2 {
+        Vec2::from(self.current().view)
+    }
+
+    /// Where the local hand starts
+    pub fn pile_position(&self) -> Vec3 {
+        Vec3::from(self.current().pile_position)
+    }
+
+    /// Where the last played cards start
+    pub fn table_position(&self) -> Vec3 {
+        Vec3::from(self.current().table_position)
+    }
+
+    pub fn board_position(&self) -> Vec3 {
+        Vec3::from(self.current().board_position)
+    }
+
+    pub fn board_size(&self) -> Vec2 {
+        Vec2::from(self.current().board_size)
+    }
+
+    /// Room between two cards, in hand and on the table
+    pub fn card_spacing(&self) -> f32 {
+        self.current().card_spacing
+    }
+
+    pub fn controls_position(&self) -> (Val, Val) {
+        let (bottom, right) = self.current().controls_position;
+        (Val::Percent(bottom), Val::Percent(right))
+    }
+
+    pub fn seat(&self, seat: Seat) -> Vec3 {
+        let arrangement = self.current();
+        let (x, y) = match seat {
+            Seat::Bottom => arrangement.p0_position,
+            Seat::Left => arrangement.p1_position,
+            Seat::Top => arrangement.p2_position,
+            Seat::Right => arrangement.p3_position,
+        };
+        Vec3::new(x, y, 5.)
+    }
+}
+
+fn detect_orientation(
+    window_q: Query<&Window, (With<PrimaryWindow>, Changed<Window>)>,
+    mut layout: ResMut<Layout>,
+) {
+    let Ok(window) = window_q.get_single() else {
+        return;
+    };
+
+    let orientation = if window.height() > window.width() {
+        Orientation::Portrait
+    } else {
+        Orientation::Landscape
+    };
+
+    if layout.orientation != orientation {
+        info!("Layout: {:?}", orientation);
+        layout.orientation = orientation;
+    }
+}
+
+/// Shows the whole view, and scales the bevy UI along
+fn fit_camera(
+    layout: Res<Layout>,
+    window_q: Query<Ref<Window>, With<PrimaryWindow>>,
+    mut camera_q: Query<&mut OrthographicProjection, With<MainCamera>>,
+    mut ui_scale: ResMut<UiScale>,
+) {
+    let Ok(window) = window_q.get_single() else {
+        return;
+    };
+    if !layout.is_changed() && !window.is_changed() {
+        return;
+    }
+
+    let view = layout.view();
+
+    for mut projection in camera_q.iter_mut() {
+        projection.scaling_mode = ScalingMode::AutoMin {
+            min_width: view.x,
+            min_height: view.y,
+        };
+    }
+
+    let scale = (window.width() / view.x).min(window.height() / view.y) as f64;
+    ui_scale.0 = scale.clamp(UI_SCALE_RANGE.0, UI_SCALE_RANGE.1);
+}
+
+/// The positions the older systems read from the global resource
+fn sync_global(layout: Res<Layout>, mut global: ResMut<Global>) {
+    global.game.local_player.draw_pos = layout.seat(Seat::Bottom).truncate();
+    global.game.player_1.draw_pos = layout.seat(Seat::Left).truncate();
+    global.game.player_2.draw_pos = layout.seat(Seat::Top).truncate();
+    global.game.player_3.draw_pos = layout.seat(Seat::Right).truncate();
+    global.game.local_player.pile_pos = layout.pile_position();
+}
@@ -1,25 +1,38 @@
 mod cards;
 mod controller;
+mod history;
+mod keyboard;
+pub mod layout;
 mod player_ui;
+mod profile;
+mod results;
+mod seats;
 mod status;
 mod table;
+mod tracker;
 use std::collections::BTreeMap;
 
 use bevy::prelude::*;
-use naia_bevy_client::{events::MessageEvents, Client};
 use naia_bevy_demo_shared::{
-    channels::{GameSystemChannel, PlayerActionChannel},
     components::card::Card,
     messages::{AcceptStartGame, SkipTurn},
 };
 
-use crate::states::MainState;
+use crate::{
+    authority::{Incoming, Outgoing},
+    states::MainState,
+};
 
 use self::{
-    cards::CardPlugin, controller::ControllerPlugin, player_ui::PlayerUiPlugin, table::TablePlugin,
+    cards::CardPlugin, controller::ControllerPlugin, history::HistoryPlugin,
+    keyboard::KeyboardPlugin, layout::LayoutPlugin, player_ui::PlayerUiPlugin,
+    profile::ProfilePlugin, results::ResultsPlugin, seats::SeatsPlugin, table::TablePlugin,
+    tracker::TrackerPlugin,
 };
 use self::{controller::SkipTurnEvent, status::StatusPlugin};
 
+pub use self::cards::ShowHand;
+
 pub struct GamePlugin;
 impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
@@ -29,8 +42,15 @@ impl Plugin for GamePlugin {
             .add_plugins(StatusPlugin)
             .add_plugins(CardPlugin)
             .add_plugins(ControllerPlugin)
+            .add_plugins(HistoryPlugin)
+            .add_plugins(KeyboardPlugin)
+            .add_plugins(LayoutPlugin)
             .add_plugins(PlayerUiPlugin)
+            .add_plugins(ProfilePlugin)
+            .add_plugins(ResultsPlugin)
+            .add_plugins(SeatsPlugin)
             .add_plugins(TablePlugin)
+            .add_plugins(TrackerPlugin)
             .add_systems(Startup, local_init)
             // .add_systems(Update, spawn_player.run_if(on_event::<LocalStartGame>()))
             .add_systems(Update, wait_to_ingame.run_if(in_state(MainState::Wait)))
@@ -61,19 +81,17 @@ fn local_init(mut commands: Commands) {
     commands.spawn(LocalPlayerCards::default());
 }
 
-pub fn skip_turn(mut client: Client) {
+pub fn skip_turn(mut skip_turn_ev: EventWriter<Outgoing<SkipTurn>>) {
     info!("skip turn!!!");
-    client.send_message::<PlayerActionChannel, SkipTurn>(&SkipTurn::default());
+    skip_turn_ev.send(Outgoing(SkipTurn));
 }
 
 pub fn wait_to_ingame(
-    mut event_reader: EventReader<MessageEvents>,
+    mut start_game_ev: EventReader<Incoming<AcceptStartGame>>,
     mut next_state: ResMut<NextState<MainState>>,
 ) {
-    for event in event_reader.read() {
-        for _ in event.read::<GameSystemChannel, AcceptStartGame>() {
-            info!("Switching to InGame");
-            next_state.set(MainState::Game);
-        }
+    for _ in start_game_ev.read() {
+        info!("Switching to InGame");
+        next_state.set(MainState::Game);
     }
 }
@@ -1,24 +1,26 @@
-use std::{ops::Add, time::Duration};
+use std::time::Duration;
 
 use bevy::time::common_conditions::on_timer;
 use bevy::{prelude::*, text::Text2dBounds};
-use naia_bevy_client::events::MessageEvents;
-use naia_bevy_demo_shared::{channels::GameSystemChannel, messages::AcceptPlayerReady};
-
+use bevy_mod_picking::prelude::*;
 use naia_bevy_demo_shared::components::Player;
 
-use crate::{assets::UiAssets, components::LocalPlayer, resources::Global, states::MainState};
+use crate::{
+    assets::UiAssets, components::LocalPlayer, locale::Locale, resources::Global,
+    settings::Settings, states::MainState,
+};
+
+use super::{
+    layout::{Layout, Seat},
+    profile::open_profile,
+};
 
 pub struct PlayerUiPlugin;
 
 impl Plugin for PlayerUiPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<PlayerMessageEvent>()
-            .add_event::<LoadExistPlayerEvent>()
-            .add_systems(
-                Update,
-                (new_player_join, handle_load_exist_player).run_if(in_state(MainState::Lobby)),
-            )
+            .add_systems(Update, seat_players.run_if(in_state(MainState::Lobby)))
             .add_systems(
                 Update,
                 (
@@ -34,21 +36,24 @@ impl Plugin for PlayerUiPlugin {
                 animatetext_update.run_if(
                     in_state(MainState::Game).and_then(on_timer(Duration::from_millis(800))),
                 ),
-            );
+            )
+            .add_systems(Update, tint_back_cards)
+            .add_systems(Update, relayout_seats.run_if(resource_changed::<Layout>()));
     }
 }
 
 const AVATAR_SIZE: f32 = 55.;
 
-#[derive(Event, Default)]
-pub struct LoadExistPlayerEvent(pub usize);
-
 #[derive(Default, Event)]
 pub struct PlayerMessageEvent(pub usize, pub String);
 
 #[derive(Component)]
 pub struct BackCard;
 
+/// Opens the player's profile when clicked
+#[derive(Component)]
+pub struct Avatar;
+
 #[derive(Component)]
 pub struct ForeignPlayer;
 
@@ -82,6 +87,19 @@ pub struct CleanMessageCounter {
     pos: i32,
 }
 
+/// Part of a player's UI, placed from their seat so it follows the layout
+#[derive(Component, Copy, Clone)]
+pub struct Seated {
+    seat: Seat,
+    offset: Vec3,
+}
+
+impl Seated {
+    fn translation(&self, layout: &Layout) -> Vec3 {
+        layout.seat(self.seat) + self.offset
+    }
+}
+
 #[derive(Component, Copy, Clone)]
 pub struct Bottom(usize);
 
@@ -96,10 +114,11 @@ pub struct Right(usize);
 
 pub trait PlayerDirection {
     fn from_server_pos(pos: usize) -> Self;
-    fn get_translation(&self) -> Vec3;
-    fn back_card_translation(&self) -> Vec3;
-    fn timer_translation(&self) -> Vec3 {
-        self.get_translation().add(Vec3::new(50., 0., 15.))
+    fn seat(&self) -> Seat;
+    /// From the avatar
+    fn back_card_offset(&self) -> Vec3;
+    fn timer_offset(&self) -> Vec3 {
+        Vec3::new(50., 0., 15.)
     }
 }
 
@@ -108,261 +127,127 @@ impl PlayerDirection for Bottom {
         Self(pos)
     }
 
-    fn get_translation(&self) -> Vec3 {
-        Vec3::new(0., -180., 5.)
+    fn seat(&self) -> Seat {
+        Seat::Bottom
     }
 
-    fn back_card_translation(&self) -> Vec3 {
-        self.get_translation().add(Vec3::new(-1000., 0., 0.))
+    fn back_card_offset(&self) -> Vec3 {
+        Vec3::new(-1000., 0., 0.)
     }
 }
 
 impl PlayerDirection for Left {
-    fn get_translation(&self) -> Vec3 {
-        Vec3::new(-315., 45., 5.)
+    fn seat(&self) -> Seat {
+        Seat::Left
     }
 
     fn from_server_pos(pos: usize) -> Self {
         Self(pos)
     }
 
-    fn back_card_translation(&self) -> Vec3 {
-        self.get_translation().add(Vec3::new(60., 0., 0.))
+    fn back_card_offset(&self) -> Vec3 {
+        Vec3::new(60., 0., 0.)
     }
 
-    fn timer_translation(&self) -> Vec3 {
-        self.get_translation().add(Vec3::new(-50., 0., 15.))
+    fn timer_offset(&self) -> Vec3 {
+        Vec3::new(-50., 0., 15.)
     }
 }
 
 impl PlayerDirection for Top {
-    fn get_translation(&self) -> Vec3 {
-        Vec3::new(0., 200., 5.)
+    fn seat(&self) -> Seat {
+        Seat::Top
     }
 
     fn from_server_pos(pos: usize) -> Self {
         Self(pos)
     }
 
-    fn back_card_translation(&self) -> Vec3 {
-        self.get_translation().add(Vec3::new(60., 0., 0.))
+    fn back_card_offset(&self) -> Vec3 {
+        Vec3::new(60., 0., 0.)
     }
 
-    fn timer_translation(&self) -> Vec3 {
-        self.get_translation().add(Vec3::new(-50., 0., 15.))
+    fn timer_offset(&self) -> Vec3 {
+        Vec3::new(-50., 0., 15.)
     }
 }
 
 impl PlayerDirection for Right {
-    fn get_translation(&self) -> Vec3 {
-        Vec3::new(315., 45., 5.)
+    fn seat(&self) -> Seat {
+        Seat::Right
     }
 
     fn from_server_pos(pos: usize) -> Self {
         Self(pos)
     }
 
-    fn back_card_translation(&self) -> Vec3 {
-        self.get_translation().add(Vec3::new(-50., 0., 0.))
+    fn back_card_offset(&self) -> Vec3 {
+        Vec3::new(-50., 0., 0.)
     }
 }
 
-pub fn handle_load_exist_player(
-    mut commands: Commands,
-    res: Res<UiAssets>,
-    mut event_reader: EventReader<LoadExistPlayerEvent>,
-    player_q: Query<&Player, Without<LocalPlayer>>,
-) {
-    for event in event_reader.read() {
-        let cur_local_pos = event.0;
+/// Chairs around the table, the local player always sits at the bottom
+pub const SEATS: usize = 4;
 
-        if cur_local_pos == 0 {
-            return;
-        }
-
-        if cur_local_pos == 1 {
-            for p in player_q.iter() {
-                let right = Right::from_server_pos(*p.pos);
-
-                let entity = create_player_ui(
-                    &mut commands,
-                    right,
-                    &res,
-                    *p.pos as i32,
-                    &p.score.to_string(),
-                    &p.name.to_string(),
-                );
-
-                commands.entity(entity).insert(right);
-            }
-        }
-
-        if cur_local_pos == 2 {
-            for p in player_q.iter() {
-                if *p.pos == 0 {
-                    let top = Top::from_server_pos(*p.pos);
-                    let entity = create_player_ui(
-                        &mut commands,
-                        top,
-                        &res,
-                        *p.pos as i32,
-                        &p.score.to_string(),
-                        &p.name.to_string(),
-                    );
-
-                    commands.entity(entity).insert(top);
-                }
-
-                if *p.pos == 1 {
-                    let right = Right::from_server_pos(*p.pos);
-                    let entity = create_player_ui(
-                        &mut commands,
-                        right,
-                        &res,
-                        *p.pos as i32,
-                        &p.score.to_string(),
-                        &p.name.to_string(),
-                    );
-
-                    commands.entity(entity).insert(right);
-                }
-            }
-        }
-
-        if cur_local_pos == 3 {
-            for p in player_q.iter() {
-                if *p.pos == 0 {
-                    let left = Left::from_server_pos(*p.pos);
-                    let entity = create_player_ui(
-                        &mut commands,
-                        left,
-                        &res,
-                        *p.pos as i32,
-                        &p.score.to_string(),
-                        &p.name.to_string(),
-                    );
-
-                    commands.entity(entity).insert(left);
-                }
-
-                if *p.pos == 2 {
-                    let right = Right::from_server_pos(*p.pos);
-                    let entity = create_player_ui(
-                        &mut commands,
-                        right,
-                        &res,
-                        *p.pos as i32,
-                        &p.score.to_string(),
-                        &p.name.to_string(),
-                    );
-
-                    commands.entity(entity).insert(right);
-                }
-
-                if *p.pos == 1 {
-                    let top = Top::from_server_pos(*p.pos);
-                    let entity = create_player_ui(
-                        &mut commands,
-                        top,
-                        &res,
-                        *p.pos as i32,
-                        &p.score.to_string(),
-                        &p.name.to_string(),
-                    );
-
-                    commands.entity(entity).insert(top);
-                }
-            }
-        }
-    }
+fn spawn_player_ui<T: PlayerDirection + Component + Copy>(
+    commands: &mut Commands,
+    res: &Res<UiAssets>,
+    locale: &Locale,
+    layout: &Layout,
+    player: &Player,
+) {
+    let direction = T::from_server_pos(*player.pos);
+
+    let entity = create_player_ui(
+        commands,
+        direction,
+        res,
+        locale,
+        layout,
+        *player.pos as i32,
+        &player.score.to_string(),
+        &player.name(),
+        *player.avatar,
+    );
+
+    commands.entity(entity).insert(direction);
 }
 
-#[allow(clippy::too_many_arguments)]
-pub fn new_player_join(
+/// Lays every player out around the local seat, again whenever somebody
+/// joins, leaves or changes seats in the lobby
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn seat_players(
     mut commands: Commands,
     res: Res<UiAssets>,
-    mut event_reader: EventReader<MessageEvents>,
-    mut load_exist_player_event: EventWriter<LoadExistPlayerEvent>,
-    bottom_player_q: Query<(), With<Bottom>>,
-    left_player_q: Query<(), With<Left>>,
-    top_player_q: Query<(), With<Top>>,
-    right_player_q: Query<(), With<Right>>,
+    locale: Locale,
+    layout: Res<Layout>,
+    changed_q: Query<(), Or<(Changed<Player>, Added<LocalPlayer>)>>,
+    mut removed: RemovedComponents<Player>,
+    local_q: Query<&Player, With<LocalPlayer>>,
+    player_q: Query<&Player>,
+    ui_q: Query<Entity, With<PlayerPos>>,
 ) {
-    for events in event_reader.read() {
-        for new_player in events.read::<GameSystemChannel, AcceptPlayerReady>() {
-            let player_name = new_player.name;
-            let player_pos = new_player.server_pos as i32;
-            let player_score = "0";
-
-            if bottom_player_q.is_empty() {
-                let bottom = Bottom::from_server_pos(player_pos as usize);
-
-                let entity = create_player_ui(
-                    &mut commands,
-                    bottom,
-                    &res,
-                    player_pos,
-                    player_score,
-                    &player_name,
-                );
-
-                commands.entity(entity).insert(bottom);
-
-                load_exist_player_event.send(LoadExistPlayerEvent(new_player.server_pos));
-
-                return;
-            }
-
-            if left_player_q.is_empty() {
-                let left = Left::from_server_pos(player_pos as usize);
-
-                let entity = create_player_ui(
-                    &mut commands,
-                    left,
-                    &res,
-                    player_pos,
-                    player_score,
-                    &player_name,
-                );
-
-                commands.entity(entity).insert(left);
-
-                return;
-            }
-
-            if top_player_q.is_empty() {
-                let top = Top::from_server_pos(player_pos as usize);
-
-                let entity = create_player_ui(
-                    &mut commands,
-                    top,
-                    &res,
-                    player_pos,
-                    player_score,
-                    &player_name,
-                );
-
-                commands.entity(entity).insert(top);
-
-                return;
-            }
-
-            if right_player_q.is_empty() {
-                let right = Right::from_server_pos(player_pos as usize);
+    let left = removed.read().count() > 0;
+    if changed_q.is_empty() && !left {
+        return;
+    }
+    // Nowhere to sit the others around yet
+    let Ok(local) = local_q.get_single() else {
+        return;
+    };
 
-                let entity = create_player_ui(
-                    &mut commands,
-                    right,
-                    &res,
-                    player_pos,
-                    player_score,
-                    &player_name,
-                );
+    for entity in ui_q.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
 
-                commands.entity(entity).insert(right);
+    for player in player_q.iter() {
+        let relative = (*player.pos + SEATS - *local.pos) % SEATS;
 
-                return;
-            }
+        match relative {
+            0 => spawn_player_ui::<Bottom>(&mut commands, &res, &locale, &layout, player),
+            1 => spawn_player_ui::<Left>(&mut commands, &res, &locale, &layout, player),
+            2 => spawn_player_ui::<Top>(&mut commands, &res, &locale, &layout, player),
+            _ => spawn_player_ui::<Right>(&mut commands, &res, &locale, &layout, player),
         }
     }
 }
@@ -371,9 +256,10 @@ pub fn update_score(
     mut text_q: Query<(&mut Text, &PlayerPos), With<Score>>,
     global: Res<Global>,
     res: Res<UiAssets>,
+    locale: Locale,
 ) {
     let text_style = TextStyle {
-        font: res.font.clone(),
+        font: locale.font(&res),
         font_size: 15.0,
         color: Color::WHITE,
     };
@@ -381,22 +267,22 @@ pub fn update_score(
     // TODO: O(N^2) here, worst  case only 8 iterate but still bother me
     for (mut text, player_pos) in text_q.iter_mut() {
         if player_pos.0 == global.game.local_player.pos {
-            let new_score = format!("Score: {}", global.game.local_player.score);
+            let new_score = locale.t_args("score", &[("score", &global.game.local_player.score)]);
             *text = Text::from_section(new_score, text_style.clone());
         }
 
         if player_pos.0 == global.game.player_1.pos {
-            let new_score = format!("Score: {}", global.game.player_1.score);
+            let new_score = locale.t_args("score", &[("score", &global.game.player_1.score)]);
             *text = Text::from_section(new_score, text_style.clone());
         }
 
         if player_pos.0 == global.game.player_2.pos {
-            let new_score = format!("Score: {}", global.game.player_2.score);
+            let new_score = locale.t_args("score", &[("score", &global.game.player_2.score)]);
             *text = Text::from_section(new_score, text_style.clone());
         }
 
         if player_pos.0 == global.game.player_3.pos {
-            let new_score = format!("Score: {}", global.game.player_3.score);
+            let new_score = locale.t_args("score", &[("score", &global.game.player_3.score)]);
             *text = Text::from_section(new_score, text_style.clone());
         }
     }
@@ -524,26 +410,48 @@ pub fn clean_player_message(
     }
 }
 
+/// Moves every player's UI to their seat in the new layout
+fn relayout_seats(layout: Res<Layout>, mut seated_q: Query<(&mut Transform, &Seated)>) {
+    for (mut trans, seated) in seated_q.iter_mut() {
+        trans.translation = seated.translation(&layout);
+    }
+}
+
+/// Follows the card back picked in the settings, also for new players
+fn tint_back_cards(settings: Res<Settings>, mut back_card_q: Query<(&mut Sprite, Ref<BackCard>)>) {
+    for (mut sprite, back_card) in back_card_q.iter_mut() {
+        if settings.is_changed() || back_card.is_added() {
+            sprite.color = settings.card_back.color();
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn create_player_ui<T: PlayerDirection>(
     commands: &mut Commands,
     direction: T,
     res: &Res<UiAssets>,
+    locale: &Locale,
+    layout: &Layout,
     player_pos: i32,
     player_score: &str,
     player_name: &str,
+    avatar: usize,
 ) -> Entity {
-    let draw_pos = direction.get_translation();
+    let seat = direction.seat();
+    let seated = |offset: Vec3| Seated { seat, offset };
+    let draw_pos = layout.seat(seat);
 
     let text_style = TextStyle {
-        font: res.font.clone(),
+        font: locale.font(res),
         font_size: 15.0,
         color: Color::WHITE,
     };
 
-    let avatar_handle = res.avatars.get(&player_pos).unwrap().clone();
-    let score = format!("Score: {}", player_score);
+    let avatar_handle = res.avatars.get(&(avatar as i32)).unwrap().clone();
+    let score = locale.t_args("score", &[("score", &player_score)]);
 
-    let avatar = SpriteBundle {
+    let avatar_sprite = SpriteBundle {
         transform: Transform::from_xyz(draw_pos.x, draw_pos.y, draw_pos.z),
         texture: avatar_handle,
         sprite: Sprite {
@@ -553,46 +461,44 @@ pub fn create_player_ui<T: PlayerDirection>(
         ..default()
     };
 
+    let name = seated(Vec3::new(0., 35., 10.));
     commands.spawn((
         Name,
         AnimateText,
         PlayerPos(player_pos),
+        name,
         Text2dBundle {
             text: Text::from_section(player_name, text_style.clone())
                 .with_alignment(TextAlignment::Left),
             text_2d_bounds: Text2dBounds {
                 size: Vec2::new(100., 30.),
             },
-            transform: Transform::from_translation(Vec3::from_array([
-                draw_pos.x,
-                draw_pos.y + 35.,
-                15.,
-            ])),
+            transform: Transform::from_translation(name.translation(layout)),
             ..default()
         },
     ));
 
+    let score_seat = seated(Vec3::new(0., -35., 10.));
     commands.spawn((
         Score,
         PlayerPos(player_pos),
+        score_seat,
         Text2dBundle {
             text: Text::from_section(score, text_style.clone())
                 .with_alignment(TextAlignment::Center),
             text_2d_bounds: Text2dBounds {
                 size: Vec2::new(100., 30.),
             },
-            transform: Transform::from_translation(Vec3::from_array([
-                draw_pos.x,
-                draw_pos.y - 35.,
-                15.,
-            ])),
+            transform: Transform::from_translation(score_seat.translation(layout)),
             ..default()
         },
     ));
 
+    let timer = seated(direction.timer_offset());
     commands.spawn((
         PlayerTimerContainer,
         PlayerPos(player_pos),
+        timer,
         Text2dBundle {
             text: Text::from_section("0".to_string(), text_style.clone())
                 .with_alignment(TextAlignment::Center),
@@ -600,46 +506,54 @@ pub fn create_player_ui<T: PlayerDirection>(
                 size: Vec2::new(100., 30.),
             },
             visibility: Visibility::Hidden,
-            transform: Transform::from_translation(direction.timer_translation()),
+            transform: Transform::from_translation(timer.translation(layout)),
             ..default()
         },
     ));
 
+    let message = seated(Vec3::new(20., 45., 10.));
     commands.spawn((
         PlayerMessageContainer,
         PlayerPos(player_pos),
+        message,
         Text2dBundle {
             text: Text::from_section("".to_string(), text_style)
                 .with_alignment(TextAlignment::Center),
             text_2d_bounds: Text2dBounds {
                 size: Vec2::new(100., 30.),
             },
-            transform: Transform::from_translation(Vec3::from_array([
-                draw_pos.x + 20.,
-                draw_pos.y + 45.,
-                15.,
-            ])),
+            transform: Transform::from_translation(message.translation(layout)),
             ..default()
         },
     ));
 
     let back_card_handle = res.back_card.clone();
 
+    let back_card = seated(direction.back_card_offset());
     commands.spawn((
         BackCard,
         PlayerPos(player_pos),
+        back_card,
         SpriteBundle {
             texture: back_card_handle,
             sprite: Sprite {
                 custom_size: Some(Vec2::new(30., 45.)),
                 ..default()
             },
-            transform: Transform::from_translation(direction.back_card_translation()),
+            transform: Transform::from_translation(back_card.translation(layout)),
             ..default()
         },
     ));
 
-    let entity = commands.spawn((avatar, PlayerPos(player_pos))).id();
+    let entity = commands
+        .spawn((
+            avatar_sprite,
+            Avatar,
+            PlayerPos(player_pos),
+            seated(Vec3::ZERO),
+            On::<Pointer<Click>>::run(open_profile),
+        ))
+        .id();
 
     entity
 }
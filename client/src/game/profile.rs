@@ -0,0 +1,107 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use bevy_mod_picking::prelude::*;
+use naia_bevy_demo_shared::components::Player;
+
+use crate::{locale::Locale, resources::Global, states::MainState};
+
+use super::player_ui::PlayerPos;
+
+/// Name, score and record of an opponent, next to their avatar once clicked
+pub struct ProfilePlugin;
+
+impl Plugin for ProfilePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Profile>()
+            .add_systems(OnExit(MainState::Game), close_profile)
+            .add_systems(Update, profile_popover.run_if(in_state(MainState::Game)));
+    }
+}
+
+/// The seat whose profile is open, and where the click was
+#[derive(Resource, Default)]
+pub struct Profile {
+    pos: Option<usize>,
+    anchor: egui::Pos2,
+}
+
+/// Clicking the same avatar again closes the popover
+pub fn open_profile(
+    event: Listener<Pointer<Click>>,
+    pos_q: Query<&PlayerPos>,
+    global: Res<Global>,
+    mut profile: ResMut<Profile>,
+) {
+    let Ok(PlayerPos(pos)) = pos_q.get(event.listener()) else {
+        return;
+    };
+    if *pos == global.game.local_player.pos {
+        return;
+    }
+
+    let pos = *pos as usize;
+    if profile.pos == Some(pos) {
+        profile.pos = None;
+        return;
+    }
+
+    let location = event.pointer_location.position;
+    *profile = Profile {
+        pos: Some(pos),
+        anchor: egui::pos2(location.x, location.y),
+    };
+}
+
+fn close_profile(mut profile: ResMut<Profile>) {
+    profile.pos = None;
+}
+
+fn profile_popover(
+    mut egui_ctx: EguiContexts,
+    mut profile: ResMut<Profile>,
+    player_q: Query<&Player>,
+    locale: Locale,
+) {
+    let Some(pos) = profile.pos else {
+        return;
+    };
+    // Gone from the table
+    let Some(player) = player_q.iter().find(|player| *player.pos == pos) else {
+        profile.pos = None;
+        return;
+    };
+
+    let mut open = true;
+
+    egui::Window::new(player.name())
+        .id(egui::Id::new("profile-window"))
+        .collapsible(false)
+        .resizable(false)
+        .fixed_pos(profile.anchor)
+        .open(&mut open)
+        .show(egui_ctx.ctx_mut(), |ui| {
+            egui::Grid::new("profile-grid")
+                .num_columns(2)
+                .spacing([12., 4.])
+                .show(ui, |ui| {
+                    ui.label(locale.t("profile-score"));
+                    ui.label(player.score.to_string());
+                    ui.end_row();
+
+                    ui.label(locale.t("profile-matches"));
+                    ui.label(player.matches.to_string());
+                    ui.end_row();
+
+                    ui.label(locale.t("profile-win-rate"));
+                    match player.win_rate() {
+                        Some(rate) => ui.label(format!("{:.0}%", rate * 100.)),
+                        None => ui.weak(locale.t("profile-no-matches")),
+                    };
+                    ui.end_row();
+                });
+        });
+
+    if !open {
+        profile.pos = None;
+    }
+}
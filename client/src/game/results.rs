@@ -0,0 +1,134 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use naia_bevy_demo_shared::{
+    components::{hand::Hand, Player},
+    messages::{AcceptStartGame, MatchResults, RequestStart, Standing},
+};
+
+use crate::{
+    authority::{Incoming, Outgoing},
+    locale::Locale,
+    resources::Global,
+};
+
+use super::history::short_hand;
+
+/// The results of the last match over the table, until the next deal
+pub struct ResultsPlugin;
+
+impl Plugin for ResultsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Results>()
+            .add_systems(Update, (handle_results_events, results_panel).chain());
+    }
+}
+
+#[derive(Resource, Default)]
+struct Results {
+    standings: Vec<Standing>,
+    open: bool,
+    /// The next deal was asked for, it waits for the other players
+    ready: bool,
+}
+
+fn handle_results_events(
+    mut results_ev: EventReader<Incoming<MatchResults>>,
+    mut start_game_ev: EventReader<Incoming<AcceptStartGame>>,
+    mut results: ResMut<Results>,
+) {
+    for Incoming(message) in results_ev.read() {
+        *results = Results {
+            standings: message.standings.clone(),
+            open: true,
+            ready: false,
+        };
+    }
+
+    for _ in start_game_ev.read() {
+        results.open = false;
+    }
+}
+
+fn place_id(place: usize, players: usize) -> String {
+    if players > 1 && place == players - 1 {
+        return "results-place-last".to_string();
+    }
+
+    format!("results-place-{}", place + 1)
+}
+
+fn results_panel(
+    mut egui_ctx: EguiContexts,
+    mut results: ResMut<Results>,
+    player_q: Query<&Player>,
+    global: Res<Global>,
+    mut request_start_ev: EventWriter<Outgoing<RequestStart>>,
+    locale: Locale,
+) {
+    if !results.open {
+        return;
+    }
+
+    let local_pos = global.game.local_player.pos as usize;
+    let mut ready = results.ready;
+
+    egui::Window::new(locale.t("results-title"))
+        .id(egui::Id::new("results-window"))
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0., 0.])
+        .show(egui_ctx.ctx_mut(), |ui| {
+            let players = results.standings.len();
+
+            egui::Grid::new("results-grid")
+                .num_columns(5)
+                .spacing([16., 6.])
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.strong(locale.t("results-place"));
+                    ui.strong(locale.t("results-player"));
+                    ui.strong(locale.t("results-points"));
+                    ui.strong(locale.t("results-total"));
+                    ui.strong(locale.t("results-cards-left"));
+                    ui.end_row();
+
+                    for (place, standing) in results.standings.iter().enumerate() {
+                        let name = player_q
+                            .iter()
+                            .find(|player| *player.pos == standing.pos)
+                            .map(|player| player.name())
+                            .unwrap_or_default();
+
+                        ui.label(locale.t(&place_id(place, players)));
+                        if standing.pos == local_pos {
+                            ui.strong(name);
+                        } else {
+                            ui.label(name);
+                        }
+                        ui.label(format!("+{}", standing.points));
+                        ui.label(standing.score.to_string());
+                        if standing.cards_left.is_empty() {
+                            ui.weak("-");
+                        } else {
+                            ui.label(short_hand(&Hand::from_str(&standing.cards_left)));
+                        }
+                        ui.end_row();
+                    }
+                });
+
+            ui.separator();
+
+            ui.vertical_centered(|ui| {
+                if ready {
+                    ui.weak(locale.t("results-waiting"));
+                } else if ui.button(locale.t("results-ready")).clicked() {
+                    request_start_ev.send(Outgoing(RequestStart));
+                    ready = true;
+                }
+            });
+        });
+
+    if ready != results.ready {
+        results.ready = ready;
+    }
+}
@@ -0,0 +1,130 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use naia_bevy_demo_shared::{
+    components::{Host, Player, TableOptions},
+    messages::{KickPlayer, SetCardTracker, TakeSeat, TransferHost},
+};
+
+use crate::{
+    authority::{Mode, Outgoing},
+    components::LocalPlayer,
+    locale::Locale,
+    states::MainState,
+};
+
+use super::player_ui::SEATS;
+
+/// The chairs of the table before the match: sit elsewhere, and for the host
+/// remove a player, hand the host role over or set the table options
+pub struct SeatsPlugin;
+
+impl Plugin for SeatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, seats_panel.run_if(in_state(MainState::Lobby)));
+    }
+}
+
+enum SeatAction {
+    Sit(usize),
+    Kick(usize),
+    MakeHost(usize),
+    CardTracker(bool),
+}
+
+#[allow(clippy::too_many_arguments)]
+fn seats_panel(
+    mut egui_ctx: EguiContexts,
+    mut take_seat_ev: EventWriter<Outgoing<TakeSeat>>,
+    mut kick_ev: EventWriter<Outgoing<KickPlayer>>,
+    mut transfer_host_ev: EventWriter<Outgoing<TransferHost>>,
+    mut card_tracker_ev: EventWriter<Outgoing<SetCardTracker>>,
+    mode: Res<Mode>,
+    player_q: Query<(&Player, Has<Host>, Has<LocalPlayer>)>,
+    options_q: Query<&TableOptions>,
+    locale: Locale,
+) {
+    // Not seated yet
+    let Some((_, local_is_host, _)) = player_q.iter().find(|(.., is_local)| *is_local) else {
+        return;
+    };
+
+    let mut action = None;
+
+    egui::Window::new(locale.t("seats-title"))
+        .id(egui::Id::new("seats-window"))
+        .resizable(false)
+        .anchor(egui::Align2::LEFT_TOP, [8., 8.])
+        .show(egui_ctx.ctx_mut(), |ui| {
+            egui::Grid::new("seats-grid")
+                .num_columns(3)
+                .spacing([12., 4.])
+                .striped(true)
+                .show(ui, |ui| {
+                    for pos in 0..SEATS {
+                        ui.label(locale.t_args("seats-seat", &[("seat", &(pos + 1))]));
+
+                        let sitting = player_q.iter().find(|(player, ..)| *player.pos == pos);
+
+                        match sitting {
+                            Some((player, is_host, is_local)) => {
+                                let mut name = player.name();
+                                if is_host {
+                                    name = locale.t_args("seats-host", &[("name", &name)]);
+                                }
+                                if is_local {
+                                    ui.strong(name);
+                                } else {
+                                    ui.label(name);
+                                }
+
+                                ui.horizontal(|ui| {
+                                    if !local_is_host || is_local {
+                                        return;
+                                    }
+                                    // Bots can not host
+                                    if *mode == Mode::Online
+                                        && ui.small_button(locale.t("seats-make-host")).clicked()
+                                    {
+                                        action = Some(SeatAction::MakeHost(pos));
+                                    }
+                                    if ui.small_button(locale.t("seats-kick")).clicked() {
+                                        action = Some(SeatAction::Kick(pos));
+                                    }
+                                });
+                            }
+                            None => {
+                                ui.weak(locale.t("seats-empty"));
+                                if ui.small_button(locale.t("seats-sit")).clicked() {
+                                    action = Some(SeatAction::Sit(pos));
+                                }
+                            }
+                        }
+                        ui.end_row();
+                    }
+                });
+
+            let Ok(options) = options_q.get_single() else {
+                return;
+            };
+
+            ui.separator();
+            if *options.ranked {
+                ui.weak(locale.t("seats-ranked"));
+                return;
+            }
+
+            let mut card_tracker = *options.card_tracker;
+            let checkbox = egui::Checkbox::new(&mut card_tracker, locale.t("seats-card-tracker"));
+            if ui.add_enabled(local_is_host, checkbox).changed() {
+                action = Some(SeatAction::CardTracker(card_tracker));
+            }
+        });
+
+    match action {
+        Some(SeatAction::Sit(pos)) => take_seat_ev.send(Outgoing(TakeSeat(pos))),
+        Some(SeatAction::Kick(pos)) => kick_ev.send(Outgoing(KickPlayer(pos))),
+        Some(SeatAction::MakeHost(pos)) => transfer_host_ev.send(Outgoing(TransferHost(pos))),
+        Some(SeatAction::CardTracker(on)) => card_tracker_ev.send(Outgoing(SetCardTracker(on))),
+        None => {}
+    }
+}
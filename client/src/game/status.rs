@@ -1,12 +1,23 @@
 use bevy::prelude::*;
-use naia_bevy_client::{events::MessageEvents, Client};
 use naia_bevy_demo_shared::{
-    channels::{GameSystemChannel, PlayerActionChannel},
-    messages::{EndMatch, ErrorCode, GameError, RequestStart, WaitForStart},
+    components::{table::Phase, Player},
+    messages::{
+        BackToLobby, EndMatch, ErrorCode, GameError, RequestStart, SystemMessage, WaitForStart,
+    },
 };
 use std::time::Duration;
 
-use crate::{assets::UiAssets, states::MainState};
+use crate::{
+    assets::UiAssets,
+    authority::{Incoming, Outgoing},
+    locale::Locale,
+    states::MainState,
+};
+
+use super::{
+    cards::{CStatus, Card, CardMap, HighlightCards, Ordinal},
+    controller::selected_cards,
+};
 
 pub struct StatusPlugin;
 
@@ -19,14 +30,19 @@ impl Plugin for StatusPlugin {
                 Update,
                 (
                     handle_server_error_event,
+                    handle_system_message_event,
                     handle_wait_event,
                     handle_end_match_event,
+                    handle_back_to_lobby_event,
                 ),
             )
             .add_systems(
                 Update,
-                (draw_status, delete_status, update_wait_for_status)
-                    .run_if(in_state(MainState::Wait).or_else(in_state(MainState::Game))),
+                (draw_status, delete_status, update_wait_for_status).run_if(
+                    in_state(MainState::Lobby)
+                        .or_else(in_state(MainState::Wait))
+                        .or_else(in_state(MainState::Game)),
+                ),
             );
     }
 }
@@ -85,9 +101,10 @@ pub fn update_wait_for_status(
     mut counter_q: Query<(Entity, &mut WaitForCounterConfig)>,
     mut text_q: Query<(&mut Text, &mut WaitForText)>,
     res: Res<UiAssets>,
+    locale: Locale,
     status_container_q: Query<Entity, With<StatusContainer>>,
     time: Res<Time>,
-    mut client: Client,
+    mut request_start_ev: EventWriter<Outgoing<RequestStart>>,
 ) {
     for (entity, mut counter) in counter_q.iter_mut() {
         counter.timer.tick(time.delta());
@@ -98,22 +115,21 @@ pub fn update_wait_for_status(
 
             for (mut text, mut wait_for) in text_q.iter_mut() {
                 let text_style = TextStyle {
-                    font: res.font.clone(),
+                    font: locale.font(&res),
                     font_size: 16.0,
                     color: Color::YELLOW_GREEN,
                 };
 
                 wait_for.0 -= 1;
 
-                let new_status = format!("Game start in {} seconds", wait_for.0);
+                let new_status = locale.t_args("status-start-in", &[("seconds", &wait_for.0)]);
                 *text = Text::from_section(new_status, text_style.clone());
 
                 if wait_for.0 == 0 {
                     commands.entity(entity).despawn();
                     commands.entity(status_container).despawn_descendants();
 
-                    client
-                        .send_message::<PlayerActionChannel, RequestStart>(&RequestStart::default());
+                    request_start_ev.send(Outgoing(RequestStart));
                 }
             }
         }
@@ -144,6 +160,7 @@ pub fn draw_status(
     mut status_ev: EventReader<DrawStatus>,
     status_container_q: Query<Entity, With<StatusContainer>>,
     res: Res<UiAssets>,
+    locale: Locale,
 ) {
     let status_container = status_container_q.get_single().unwrap();
 
@@ -154,7 +171,7 @@ pub fn draw_status(
                     .spawn(TextBundle::from_section(
                         msg,
                         TextStyle {
-                            font: res.font.clone(),
+                            font: locale.font(&res),
                             font_size: 16.0,
                             color: Color::ORANGE_RED,
                         },
@@ -166,16 +183,32 @@ pub fn draw_status(
                     timer: Timer::new(Duration::from_secs(3), TimerMode::Once),
                 });
             }
-            DrawStatus::Info(_) => todo!(),
+            DrawStatus::Info(msg) => {
+                let status_text = commands
+                    .spawn(TextBundle::from_section(
+                        msg,
+                        TextStyle {
+                            font: locale.font(&res),
+                            font_size: 16.0,
+                            color: Color::ANTIQUE_WHITE,
+                        },
+                    ))
+                    .id();
+
+                commands.entity(status_container).add_child(status_text);
+                commands.spawn(CounterConfig {
+                    timer: Timer::new(Duration::from_secs(5), TimerMode::Once),
+                });
+            }
             DrawStatus::WaitFor(wait_for) => match wait_for {
                 WaitFor::StartMatch(time) => {
-                    let msg = format!("Game start in {} seconds", time);
+                    let msg = locale.t_args("status-start-in", &[("seconds", time)]);
                     let status_text = commands
                         .spawn((
                             TextBundle::from_section(
                                 msg,
                                 TextStyle {
-                                    font: res.font.clone(),
+                                    font: locale.font(&res),
                                     font_size: 16.0,
                                     color: Color::YELLOW_GREEN,
                                 },
@@ -191,13 +224,13 @@ pub fn draw_status(
                     });
                 }
                 WaitFor::EndMatch(time) => {
-                    let msg = "Match ended".to_string();
+                    let msg = locale.t("status-match-ended");
                     let status_text = commands
                         .spawn((
                             TextBundle::from_section(
                                 msg,
                                 TextStyle {
-                                    font: res.font.clone(),
+                                    font: locale.font(&res),
                                     font_size: 16.0,
                                     color: Color::YELLOW_GREEN,
                                 },
@@ -218,61 +251,138 @@ pub fn draw_status(
 }
 
 pub fn handle_wait_event(
-    mut event_reader: EventReader<MessageEvents>,
+    mut wait_ev: EventReader<Incoming<WaitForStart>>,
     mut draw_status_ev: EventWriter<DrawStatus>,
     mut next_state: ResMut<NextState<MainState>>,
 ) {
-    for events in event_reader.read() {
-        for wait in events.read::<GameSystemChannel, WaitForStart>() {
-            draw_status_ev.send(DrawStatus::WaitFor(WaitFor::StartMatch(wait.0)));
-            next_state.set(MainState::Wait);
-        }
+    for Incoming(wait) in wait_ev.read() {
+        draw_status_ev.send(DrawStatus::WaitFor(WaitFor::StartMatch(wait.0)));
+        next_state.set(MainState::Wait);
     }
 }
 
 pub fn handle_end_match_event(
-    mut event_reader: EventReader<MessageEvents>,
+    mut end_match_ev: EventReader<Incoming<EndMatch>>,
+    mut next_state: ResMut<NextState<MainState>>,
+    mut draw_status_ev: EventWriter<DrawStatus>,
+) {
+    for Incoming(end_match) in end_match_ev.read() {
+        draw_status_ev.send(DrawStatus::WaitFor(WaitFor::EndMatch(end_match.0)));
+        next_state.set(MainState::Wait);
+    }
+}
+
+/// Stops counting down to the next deal, the host starts again from the lobby
+pub fn handle_back_to_lobby_event(
+    mut commands: Commands,
+    mut back_to_lobby_ev: EventReader<Incoming<BackToLobby>>,
+    counter_q: Query<Entity, With<WaitForCounterConfig>>,
+    text_q: Query<Entity, With<WaitForText>>,
     mut next_state: ResMut<NextState<MainState>>,
     mut draw_status_ev: EventWriter<DrawStatus>,
+    locale: Locale,
 ) {
-    for event in event_reader.read() {
-        for end_match in event.read::<GameSystemChannel, EndMatch>() {
-            draw_status_ev.send(DrawStatus::WaitFor(WaitFor::EndMatch(end_match.0)));
-            next_state.set(MainState::Wait);
+    for _ in back_to_lobby_ev.read() {
+        for entity in counter_q.iter().chain(text_q.iter()) {
+            commands.entity(entity).despawn_recursive();
         }
+
+        draw_status_ev.send(DrawStatus::Info(locale.t("status-back-to-lobby")));
+        next_state.set(MainState::Lobby);
+    }
+}
+
+pub fn handle_system_message_event(
+    mut system_message_ev: EventReader<Incoming<SystemMessage>>,
+    mut draw_status_ev: EventWriter<DrawStatus>,
+) {
+    for Incoming(message) in system_message_ev.read() {
+        draw_status_ev.send(DrawStatus::Info(message.0.clone()));
     }
 }
 
 pub fn handle_server_error_event(
-    mut event_reader: EventReader<MessageEvents>,
+    mut error_ev: EventReader<Incoming<ErrorCode>>,
     mut draw_status_ev: EventWriter<DrawStatus>,
+    mut highlight_ev: EventWriter<HighlightCards>,
+    card_map: Res<CardMap>,
+    card_q: Query<(Entity, &CStatus, &Ordinal), With<Card>>,
+    player_q: Query<&Player>,
+    locale: Locale,
 ) {
-    for events in event_reader.read() {
-        for error_code in events.read::<GameSystemChannel, ErrorCode>() {
-            let game_error = GameError::from(error_code);
-            match game_error {
-                GameError::InvalidCards => {
-                    draw_status_ev.send(DrawStatus::Error("Your cards are week!".to_string()));
-                }
-                GameError::WrongCombination => {
-                    draw_status_ev.send(DrawStatus::Error(
-                        "Your cards are not the same combination".to_string(),
-                    ));
-                }
-                GameError::CanNotSkipTurn => {
-                    draw_status_ev.send(DrawStatus::Error(
-                        "You can not skip turn, you can play any card now".to_string(),
-                    ));
-                }
-                GameError::WrongTurn => {
-                    draw_status_ev.send(DrawStatus::Error(
-                        "Not your turn now! Game bug probably".to_string(),
-                    ));
-                }
-                GameError::UnknownError => {
-                    draw_status_ev.send(DrawStatus::Error("Unexpected error happend".to_string()));
-                }
-            }
+    for Incoming(error_code) in error_ev.read() {
+        let error = GameError::from(error_code);
+
+        draw_status_ev.send(DrawStatus::Error(explain(&error, &player_q, &locale)));
+
+        // The play to beat or the cards the server says we lack, otherwise
+        // the selection that does not fit
+        let cards = match error.cards() {
+            Some(hand) => hand
+                .cards
+                .iter()
+                .filter_map(|card| card_map.0.get(&card.to_str()).copied())
+                .collect(),
+            None if matches!(error, GameError::WrongCombination { .. }) => selected_cards(&card_q),
+            None => vec![],
+        };
+
+        if !cards.is_empty() {
+            highlight_ev.send(HighlightCards(cards));
+        }
+    }
+}
+
+fn explain(error: &GameError, player_q: &Query<&Player>, locale: &Locale) -> String {
+    match error {
+        GameError::InvalidCards { to_beat } => locale.t_args(
+            "error-invalid-cards",
+            &[("hand", &locale.hand_name(to_beat))],
+        ),
+        GameError::WrongTurn { active_player } => {
+            let name = player_q
+                .iter()
+                .find(|player| *player.pos == *active_player)
+                .map(|player| player.name())
+                .unwrap_or_else(|| locale.t_args("seats-seat", &[("seat", &(active_player + 1))]));
+
+            locale.t_args("error-wrong-turn", &[("name", &name)])
+        }
+        GameError::WrongCombination {
+            expected: Some((combination, len)),
+        } => locale.t_args(
+            "error-wrong-combination-expected",
+            &[("combination", &locale.combination_name(*combination, *len))],
+        ),
+        GameError::WrongCombination { expected: None } => locale.t("error-wrong-combination"),
+        GameError::MissingCards { cards } => {
+            let names = cards
+                .cards
+                .iter()
+                .map(|card| locale.card_name(card))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            locale.t_args("error-missing-cards", &[("cards", &names)])
+        }
+        GameError::CanNotSkipTurn => locale.t("error-can-not-skip"),
+        GameError::NotEnoughPlayers { needed } => {
+            locale.t_args("error-not-enough-players", &[("needed", needed)])
+        }
+        GameError::PlayersNotReady => locale.t("error-players-not-ready"),
+        GameError::TableFull => locale.t("error-table-full"),
+        GameError::WrongPhase { phase } => {
+            let phase = locale.t(match phase {
+                Phase::Lobby => "phase-lobby",
+                Phase::Dealing => "phase-dealing",
+                Phase::Playing => "phase-playing",
+            });
+
+            locale.t_args("error-wrong-phase", &[("phase", &phase)])
         }
+        GameError::SeatTaken => locale.t("error-seat-taken"),
+        GameError::NotHost => locale.t("error-not-host"),
+        GameError::NoSeat => locale.t("error-no-seat"),
+        GameError::RankedTable => locale.t("error-ranked-table"),
     }
 }
@@ -1,55 +1,91 @@
 use bevy::prelude::*;
 use std::collections::VecDeque;
 
-use naia_bevy_client::events::MessageEvents;
-use naia_bevy_demo_shared::{channels::GameSystemChannel, messages::AcceptPlayCard};
+use naia_bevy_demo_shared::{
+    components::{hand::Hand, Player},
+    messages::AcceptPlayCard,
+};
 
-use crate::{assets::UiAssets, states::MainState};
+use crate::{
+    assets::UiAssets, authority::Incoming, locale::Locale, settings::Settings, states::MainState,
+};
 
-use super::cards::{Card, CardMap};
+use super::{
+    cards::{Card, CardMap},
+    layout::Layout,
+    status::DrawStatus,
+};
 
 pub struct TablePlugin;
 
 impl Plugin for TablePlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(MainState::Lobby), setup)
+        app.add_systems(OnExit(MainState::Welcome), setup)
             .add_systems(
                 Update,
                 handle_accept_play_event.run_if(in_state(MainState::Game)),
-            );
+            )
+            .add_systems(
+                Update,
+                update_board.run_if(resource_exists::<UiAssets>().and_then(
+                    resource_changed::<Settings>().or_else(resource_changed::<UiAssets>()),
+                )),
+            )
+            .add_systems(Update, relayout_table.run_if(resource_changed::<Layout>()));
     }
 }
 
 #[derive(Component)]
 pub struct Table;
 
+/// The table sprite, follows the table theme of the settings
+#[derive(Component)]
+pub struct Board;
+
 #[derive(Component)]
 pub struct TablePile(VecDeque<Vec<Entity>>);
 
 fn handle_accept_play_event(
     card_map: Res<CardMap>,
-    mut event_reader: EventReader<MessageEvents>,
+    mut accept_play_ev: EventReader<Incoming<AcceptPlayCard>>,
     mut card_q: Query<&mut Visibility, With<Card>>,
     mut table_pile_q: Query<&mut TablePile>,
+    player_q: Query<&Player>,
+    mut draw_status_ev: EventWriter<DrawStatus>,
+    locale: Locale,
 ) {
-    for events in event_reader.read() {
-        for data in events.read::<GameSystemChannel, AcceptPlayCard>() {
-            let mut table_pile = table_pile_q.get_single_mut().unwrap();
-            if let Some(pile) = table_pile.0.back() {
-                for entity in pile.iter() {
-                    let mut vis = card_q.get_mut(*entity).unwrap();
-                    *vis = Visibility::Hidden;
-                }
+    for Incoming(data) in accept_play_ev.read() {
+        let mut table_pile = table_pile_q.get_single_mut().unwrap();
+        if let Some(pile) = table_pile.0.back() {
+            for entity in pile.iter() {
+                let mut vis = card_q.get_mut(*entity).unwrap();
+                *vis = Visibility::Hidden;
             }
+        }
 
-            let new_pile = card_map.list_from_str(&data.cards);
+        let new_pile = card_map.list_from_str(&data.cards);
 
-            table_pile.0.push_back(new_pile);
+        table_pile.0.push_back(new_pile);
+
+        if let Some(player) = player_q.iter().find(|p| *p.pos == data.cur_player) {
+            let played = locale.t_args(
+                "status-played",
+                &[
+                    ("name", &player.name()),
+                    ("hand", &locale.hand_name(&Hand::from_str(&data.cards))),
+                ],
+            );
+            draw_status_ev.send(DrawStatus::Info(played));
         }
     }
 }
 
-pub fn setup(mut commands: Commands, res: Res<UiAssets>) {
+pub fn setup(
+    mut commands: Commands,
+    res: Res<UiAssets>,
+    settings: Res<Settings>,
+    layout: Res<Layout>,
+) {
     let table = commands
         .spawn((
             SpatialBundle {
@@ -61,18 +97,60 @@ pub fn setup(mut commands: Commands, res: Res<UiAssets>) {
         .id();
 
     let sprite = commands
-        .spawn(SpriteBundle {
-            sprite: Sprite {
-                custom_size: Some(Vec2::new(600., 300.)),
+        .spawn((
+            Board,
+            SpriteBundle {
+                sprite: Sprite {
+                    custom_size: Some(layout.board_size()),
+                    ..Default::default()
+                },
+                texture: res.board(settings.table_theme),
+                transform: Transform::from_translation(layout.board_position()),
                 ..Default::default()
             },
-            texture: res.board.clone(),
-            transform: Transform::from_xyz(0., 50., 3.),
-            ..Default::default()
-        })
+        ))
         .id();
 
     commands.entity(table).add_child(sprite);
 
     commands.spawn(TablePile(VecDeque::new()));
 }
+
+fn update_board(
+    res: Res<UiAssets>,
+    settings: Res<Settings>,
+    mut board_q: Query<&mut Handle<Image>, With<Board>>,
+) {
+    for mut texture in board_q.iter_mut() {
+        *texture = res.board(settings.table_theme);
+    }
+}
+
+/// Fits the board and the last played cards to the new layout
+#[allow(clippy::type_complexity)]
+fn relayout_table(
+    layout: Res<Layout>,
+    mut board_q: Query<(&mut Transform, &mut Sprite), (With<Board>, Without<Card>)>,
+    table_pile_q: Query<&TablePile>,
+    mut card_q: Query<&mut Transform, With<Card>>,
+) {
+    for (mut trans, mut sprite) in board_q.iter_mut() {
+        trans.translation = layout.board_position();
+        sprite.custom_size = Some(layout.board_size());
+    }
+
+    let Some(pile) = table_pile_q
+        .iter()
+        .find_map(|table_pile| table_pile.0.back())
+    else {
+        return;
+    };
+
+    let mut table_pos = layout.table_position();
+    for entity in pile.iter() {
+        if let Ok(mut trans) = card_q.get_mut(*entity) {
+            trans.translation = table_pos;
+            table_pos.x += layout.card_spacing();
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use naia_bevy_demo_shared::{
+    components::{card::Card as CardValue, rank::Rank, suit::Suit, Player, TableOptions},
+    messages::{AcceptPlayCard, AcceptStartGame},
+};
+
+use crate::{authority::Incoming, components::LocalPlayer, locale::Locale, states::MainState};
+
+use super::cards::{Card, CardMap};
+
+const CARD_SIZE: [f32; 2] = [18., 27.];
+const PLAYED_TINT: egui::Color32 = egui::Color32::from_gray(90);
+const IN_HAND_TINT: egui::Color32 = egui::Color32::from_rgb(150, 190, 255);
+
+/// Which of the 52 cards were played this match, for tables where the host
+/// turned the tracker on
+pub struct TrackerPlugin;
+
+impl Plugin for TrackerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PlayedCards>()
+            .add_systems(Update, track_played_cards)
+            .add_systems(Update, tracker_panel.run_if(in_state(MainState::Game)));
+    }
+}
+
+/// Kept up to date even with the tracker off, so turning it on mid match
+/// shows everything
+#[derive(Resource, Default)]
+pub struct PlayedCards(HashSet<CardValue>);
+
+fn track_played_cards(
+    mut start_game_ev: EventReader<Incoming<AcceptStartGame>>,
+    mut accept_play_ev: EventReader<Incoming<AcceptPlayCard>>,
+    mut played: ResMut<PlayedCards>,
+) {
+    if start_game_ev.read().count() > 0 {
+        played.0.clear();
+    }
+
+    for Incoming(message) in accept_play_ev.read() {
+        played.0.extend(
+            message
+                .cards
+                .split(',')
+                .filter_map(|card| CardValue::from_str(card).ok()),
+        );
+    }
+}
+
+fn tracker_panel(
+    mut egui_ctx: EguiContexts,
+    options_q: Query<&TableOptions>,
+    local_q: Query<&Player, With<LocalPlayer>>,
+    card_map: Res<CardMap>,
+    texture_q: Query<&Handle<Image>, With<Card>>,
+    played: Res<PlayedCards>,
+    locale: Locale,
+) {
+    if !options_q
+        .get_single()
+        .is_ok_and(|options| *options.card_tracker)
+    {
+        return;
+    }
+
+    let in_hand: HashSet<CardValue> = local_q
+        .get_single()
+        .map(|player| {
+            player
+                .cards
+                .split(',')
+                .filter_map(|card| CardValue::from_str(card).ok())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut ranks = Rank::ranks().to_vec();
+    ranks.sort_by_key(Rank::ordinal);
+    let mut suits = Suit::gen_suit().to_vec();
+    suits.sort_by_key(Suit::ordinal);
+
+    // Low to high along a row, one row per suit
+    let mut rows = vec![];
+    for suit in suits {
+        let mut row = vec![];
+        for rank in ranks.iter() {
+            let card = CardValue::new(*rank, suit);
+            let texture = card_map
+                .0
+                .get(&card.to_str())
+                .and_then(|entity| texture_q.get(*entity).ok())
+                .map(|handle| egui_ctx.add_image(handle.clone_weak()));
+            row.push((card, texture));
+        }
+        rows.push(row);
+    }
+
+    egui::Window::new(locale.t("tracker-title"))
+        .id(egui::Id::new("tracker-window"))
+        .resizable(false)
+        .anchor(egui::Align2::RIGHT_BOTTOM, [-8., -8.])
+        .show(egui_ctx.ctx_mut(), |ui| {
+            egui::Grid::new("tracker-grid")
+                .spacing([2., 2.])
+                .show(ui, |ui| {
+                    for row in rows.iter() {
+                        for (card, texture) in row {
+                            let tint = if played.0.contains(card) {
+                                PLAYED_TINT
+                            } else if in_hand.contains(card) {
+                                IN_HAND_TINT
+                            } else {
+                                egui::Color32::WHITE
+                            };
+
+                            match texture {
+                                Some(texture) => {
+                                    let image = egui::load::SizedTexture::new(*texture, CARD_SIZE);
+                                    ui.add(egui::Image::new(image).tint(tint))
+                                        .on_hover_text(locale.card_name(card));
+                                }
+                                None => {
+                                    ui.colored_label(tint, card.to_str());
+                                }
+                            }
+                        }
+                        ui.end_row();
+                    }
+                });
+
+            ui.horizontal(|ui| {
+                ui.colored_label(egui::Color32::WHITE, locale.t("tracker-unseen"));
+                ui.colored_label(IN_HAND_TINT, locale.t("tracker-in-hand"));
+                ui.colored_label(PLAYED_TINT, locale.t("tracker-played"));
+            });
+        });
+}
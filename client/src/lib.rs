@@ -4,6 +4,9 @@ extern crate cfg_if;
 cfg_if! {
     if #[cfg(target_arch = "wasm32")] {
         mod fps;
+        mod locale;
+        mod settings;
+        mod theme;
         mod game;
         mod system_set;
         mod welcome;
@@ -11,9 +14,12 @@ cfg_if! {
         mod systems;
         mod ui;
         mod app;
+        mod authority;
         mod components;
         mod states;
         mod assets;
+        mod audio;
+        mod connection;
 
         use wasm_bindgen::prelude::*;
 
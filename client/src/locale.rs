@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+
+use bevy::{ecs::system::SystemParam, prelude::*, reflect::TypePath};
+use bevy_common_assets::ron::RonAssetPlugin;
+use naia_bevy_demo_shared::components::{
+    card::Card,
+    cards::{evaluate_combination, ThirteenCombination},
+    hand::Hand,
+};
+
+use crate::assets::UiAssets;
+
+/// Loads the string tables in `assets/locales` and keeps the language picked
+/// on the welcome screen
+pub struct LocalePlugin;
+
+impl Plugin for LocalePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(RonAssetPlugin::<StringTable>::new(&["locale.ron"]))
+            .insert_resource(Language::detect())
+            .add_systems(Startup, load_tables);
+    }
+}
+
+#[derive(
+    Resource, Clone, Copy, Debug, Default, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize,
+)]
+pub enum Language {
+    #[default]
+    English,
+    Vietnamese,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::Vietnamese];
+
+    fn code(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Vietnamese => "vi",
+        }
+    }
+
+    /// Name of the language in that language, for the selector
+    pub fn native_name(&self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Vietnamese => "Tiếng Việt",
+        }
+    }
+
+    fn from_tag(tag: &str) -> Self {
+        if tag.to_lowercase().starts_with("vi") {
+            Language::Vietnamese
+        } else {
+            Language::English
+        }
+    }
+
+    /// Language of the browser
+    #[cfg(target_arch = "wasm32")]
+    fn detect() -> Self {
+        web_sys::window()
+            .and_then(|window| window.navigator().language())
+            .map(|tag| Self::from_tag(&tag))
+            .unwrap_or_default()
+    }
+
+    /// Language of the system, from the usual locale env vars
+    #[cfg(not(target_arch = "wasm32"))]
+    fn detect() -> Self {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .find_map(|name| std::env::var(name).ok().filter(|tag| !tag.is_empty()))
+            .map(|tag| Self::from_tag(&tag))
+            .unwrap_or_default()
+    }
+
+    /// The decorative font has no Vietnamese letters
+    pub fn font(&self, res: &UiAssets) -> Handle<Font> {
+        match self {
+            Language::English => res.font.clone(),
+            Language::Vietnamese => res.text_font.clone(),
+        }
+    }
+}
+
+/// `<code>.locale.ron`, a map from message id to text. `{name}` in a text is
+/// replaced by the argument with that name.
+#[derive(serde::Deserialize, Asset, TypePath, Debug)]
+#[serde(transparent)]
+pub struct StringTable(HashMap<String, String>);
+
+#[derive(Resource)]
+struct StringTables(HashMap<Language, Handle<StringTable>>);
+
+fn load_tables(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let tables = Language::ALL
+        .iter()
+        .map(|language| {
+            let path = format!("locales/{}.locale.ron", language.code());
+            (*language, asset_server.load(path))
+        })
+        .collect();
+
+    commands.insert_resource(StringTables(tables));
+}
+
+/// Looks texts up in the current language, then in English, then gives back
+/// the id so a missing text is easy to spot
+#[derive(SystemParam)]
+pub struct Locale<'w> {
+    language: Res<'w, Language>,
+    handles: Res<'w, StringTables>,
+    tables: Res<'w, Assets<StringTable>>,
+}
+
+impl Locale<'_> {
+    fn lookup(&self, language: Language, id: &str) -> Option<&String> {
+        let handle = self.handles.0.get(&language)?;
+        self.tables.get(handle)?.0.get(id)
+    }
+
+    pub fn language(&self) -> Language {
+        *self.language
+    }
+
+    /// The player picked another language since the system last ran
+    pub fn is_changed(&self) -> bool {
+        self.language.is_changed()
+    }
+
+    pub fn font(&self, res: &UiAssets) -> Handle<Font> {
+        self.language.font(res)
+    }
+
+    pub fn t(&self, id: &str) -> String {
+        self.lookup(*self.language, id)
+            .or_else(|| self.lookup(Language::English, id))
+            .cloned()
+            .unwrap_or_else(|| id.to_string())
+    }
+
+    pub fn t_args(&self, id: &str, args: &[(&str, &dyn ToString)]) -> String {
+        args.iter().fold(self.t(id), |text, (name, value)| {
+            text.replace(&format!("{{{}}}", name), &value.to_string())
+        })
+    }
+
+    pub fn card_name(&self, card: &Card) -> String {
+        self.t_args(
+            "card",
+            &[
+                ("rank", &self.rank_name(card)),
+                (
+                    "suit",
+                    &self.t(&format!("suit-{}", card.suit.to_str().to_lowercase())),
+                ),
+            ],
+        )
+    }
+
+    fn rank_name(&self, card: &Card) -> String {
+        self.t(&format!("rank-{}", card.rank.to_str().to_lowercase()))
+    }
+
+    /// "Pair of 5", "Đôi 5", "Sequence 3 to 7"...
+    pub fn hand_name(&self, hand: &Hand) -> String {
+        let mut cards = hand.cards.clone();
+        cards.sort();
+
+        let (Some(lowest), Some(highest)) = (cards.first(), cards.last()) else {
+            return String::new();
+        };
+
+        let id = match evaluate_combination(&cards) {
+            ThirteenCombination::Single => return self.card_name(lowest),
+            ThirteenCombination::Pair => "hand-pair",
+            ThirteenCombination::Triple => "hand-triple",
+            ThirteenCombination::Quartet => "hand-quartet",
+            ThirteenCombination::Sequence => "hand-sequence",
+            ThirteenCombination::DoubleSequence => "hand-double-sequence",
+            ThirteenCombination::NonSense => "hand-nonsense",
+        };
+
+        self.t_args(
+            id,
+            &[
+                ("rank", &self.rank_name(lowest)),
+                ("from", &self.rank_name(lowest)),
+                ("to", &self.rank_name(highest)),
+            ],
+        )
+    }
+
+    /// "a pair", "một đôi", "a straight of 5 cards"...
+    pub fn combination_name(&self, combination: ThirteenCombination, len: usize) -> String {
+        let id = match combination {
+            ThirteenCombination::Single => "combination-single",
+            ThirteenCombination::Pair => "combination-pair",
+            ThirteenCombination::Triple => "combination-triple",
+            ThirteenCombination::Quartet => "combination-quartet",
+            ThirteenCombination::Sequence => "combination-sequence",
+            ThirteenCombination::DoubleSequence => "combination-double-sequence",
+            ThirteenCombination::NonSense => "hand-nonsense",
+        };
+
+        self.t_args(id, &[("count", &len)])
+    }
+}
@@ -8,10 +8,16 @@ cfg_if! {
         mod systems;
         mod ui;
         mod app;
+        mod authority;
         mod components;
         mod states;
         mod assets;
+        mod audio;
+        mod connection;
         mod fps;
+        mod locale;
+        mod settings;
+        mod theme;
         mod game;
         mod welcome;
 
@@ -13,6 +13,9 @@ pub struct OwnedEntity {
 #[derive(Resource)]
 pub struct Global {
     pub player_name: String,
+    pub player_avatar: usize,
+    /// The other players on this device, in hot seat
+    pub guest_names: Vec<String>,
     pub player_entity: Option<Entity>,
     pub player_cards: BTreeMap<usize, Card>,
     pub owned_entity: Option<OwnedEntity>,
@@ -148,6 +151,8 @@ impl Default for Global {
     fn default() -> Self {
         Self {
             player_name: String::new(),
+            player_avatar: 0,
+            guest_names: Vec::new(),
             player_entity: None,
             player_cards: BTreeMap::new(),
             owned_entity: None,
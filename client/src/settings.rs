@@ -0,0 +1,583 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    fps::ScreenDiagsState,
+    locale::{Language, Locale},
+    states::MainState,
+    theme::{ThemePacks, DEFAULT_THEME},
+};
+
+/// Client preferences, loaded before the first frame and saved whenever they
+/// change: to localStorage on the web, to `settings.ron` in the config
+/// directory on native
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        let settings = Settings::load();
+
+        // Otherwise the language detected by the locale plugin stays
+        if let Some(language) = settings.language {
+            app.insert_resource(language);
+        }
+
+        app.insert_resource(settings)
+            .init_resource::<SettingsPanel>()
+            .add_systems(
+                Update,
+                (
+                    toggle_panel,
+                    settings_panel.run_if(not(in_state(MainState::LoadAssets))),
+                    remember_language.run_if(resource_changed::<Language>()),
+                    apply_fps_overlay.run_if(resource_changed::<Settings>()),
+                    save_settings,
+                )
+                    .chain(),
+            );
+    }
+}
+
+const STORAGE_KEY: &str = "tienlen.settings";
+/// Sliders change the settings every frame, wait until they stop moving
+const SAVE_DELAY: Duration = Duration::from_millis(500);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AnimationSpeed {
+    Slow,
+    #[default]
+    Normal,
+    Fast,
+}
+
+impl AnimationSpeed {
+    pub const ALL: [AnimationSpeed; 3] = [Self::Slow, Self::Normal, Self::Fast];
+
+    /// Scales the duration of a tween made for the normal speed
+    pub fn scale(&self, millis: u64) -> Duration {
+        match self {
+            Self::Slow => Duration::from_millis(millis * 3 / 2),
+            Self::Normal => Duration::from_millis(millis),
+            Self::Fast => Duration::from_millis(millis / 2),
+        }
+    }
+
+    fn id(&self) -> &'static str {
+        match self {
+            Self::Slow => "settings-speed-slow",
+            Self::Normal => "settings-speed-normal",
+            Self::Fast => "settings-speed-fast",
+        }
+    }
+}
+
+/// How the cards in hand are laid out
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SortMode {
+    #[default]
+    Rank,
+    Suit,
+    /// Runs, then quartets, triples and pairs, then the rest
+    Combination,
+    /// The order the player dragged the cards in
+    Custom,
+}
+
+impl SortMode {
+    /// The modes to pick from, custom comes from dragging cards around
+    pub const ALL: [SortMode; 3] = [Self::Rank, Self::Suit, Self::Combination];
+
+    /// The mode after this one, for the sort button
+    pub fn next(&self) -> Self {
+        match self {
+            Self::Rank => Self::Suit,
+            Self::Suit => Self::Combination,
+            Self::Combination | Self::Custom => Self::Rank,
+        }
+    }
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::Rank => "settings-sort-rank",
+            Self::Suit => "settings-sort-suit",
+            Self::Combination => "settings-sort-combination",
+            Self::Custom => "settings-sort-custom",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TableTheme {
+    #[default]
+    Blue,
+    Green,
+    Red,
+}
+
+impl TableTheme {
+    pub const ALL: [TableTheme; 3] = [Self::Blue, Self::Green, Self::Red];
+
+    pub fn asset_path(&self) -> &'static str {
+        match self {
+            Self::Blue => "cards/tables/table_blue.png",
+            Self::Green => "cards/tables/table_green.png",
+            Self::Red => "cards/tables/table_red.png",
+        }
+    }
+
+    fn id(&self) -> &'static str {
+        match self {
+            Self::Blue => "settings-color-blue",
+            Self::Green => "settings-color-green",
+            Self::Red => "settings-color-red",
+        }
+    }
+}
+
+/// Tint of the back of the cards
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CardBack {
+    #[default]
+    Classic,
+    Blue,
+    Green,
+    Red,
+}
+
+impl CardBack {
+    pub const ALL: [CardBack; 4] = [Self::Classic, Self::Blue, Self::Green, Self::Red];
+
+    pub fn color(&self) -> Color {
+        match self {
+            Self::Classic => Color::WHITE,
+            Self::Blue => Color::rgb(0.6, 0.7, 1.),
+            Self::Green => Color::rgb(0.6, 1., 0.7),
+            Self::Red => Color::rgb(1., 0.6, 0.6),
+        }
+    }
+
+    fn id(&self) -> &'static str {
+        match self {
+            Self::Classic => "settings-color-classic",
+            Self::Blue => "settings-color-blue",
+            Self::Green => "settings-color-green",
+            Self::Red => "settings-color-red",
+        }
+    }
+}
+
+/// Keys for playing without the mouse. The number keys quick-select a
+/// combination with the focused card and are not bound here.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyBindings {
+    pub left: KeyCode,
+    pub right: KeyCode,
+    pub toggle: KeyCode,
+    pub play: KeyCode,
+    pub pass: KeyCode,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            left: KeyCode::Left,
+            right: KeyCode::Right,
+            toggle: KeyCode::Space,
+            play: KeyCode::Return,
+            pass: KeyCode::P,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Binding {
+    Left,
+    Right,
+    Toggle,
+    Play,
+    Pass,
+}
+
+impl Binding {
+    const ALL: [Binding; 5] = [
+        Self::Left,
+        Self::Right,
+        Self::Toggle,
+        Self::Play,
+        Self::Pass,
+    ];
+
+    fn key_mut<'a>(&self, keys: &'a mut KeyBindings) -> &'a mut KeyCode {
+        match self {
+            Self::Left => &mut keys.left,
+            Self::Right => &mut keys.right,
+            Self::Toggle => &mut keys.toggle,
+            Self::Play => &mut keys.play,
+            Self::Pass => &mut keys.pass,
+        }
+    }
+
+    fn id(&self) -> &'static str {
+        match self {
+            Self::Left => "settings-key-left",
+            Self::Right => "settings-key-right",
+            Self::Toggle => "settings-key-toggle",
+            Self::Play => "settings-key-play",
+            Self::Pass => "settings-key-pass",
+        }
+    }
+}
+
+#[derive(Resource, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub animation_speed: AnimationSpeed,
+    pub sort_mode: SortMode,
+    /// 0 is muted, 1 is full volume
+    pub volume: f32,
+    pub music: bool,
+    pub show_fps: bool,
+    pub table_theme: TableTheme,
+    pub card_back: CardBack,
+    /// Id of the theme pack, see `assets/themes`
+    pub theme: String,
+    /// `None` until the player picks one, the system language is used
+    pub language: Option<Language>,
+    pub keys: KeyBindings,
+    /// The avatar picked on the welcome screen last time
+    pub avatar: usize,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            animation_speed: AnimationSpeed::default(),
+            sort_mode: SortMode::default(),
+            volume: 0.8,
+            music: false,
+            show_fps: true,
+            table_theme: TableTheme::default(),
+            card_back: CardBack::default(),
+            theme: DEFAULT_THEME.to_string(),
+            language: None,
+            keys: KeyBindings::default(),
+            avatar: 0,
+        }
+    }
+}
+
+impl Settings {
+    /// Saved settings, or the defaults when there are none or they are broken
+    fn load() -> Self {
+        let Some(saved) = read_saved() else {
+            return Self::default();
+        };
+
+        ron::from_str(&saved).unwrap_or_else(|err| {
+            warn!("Ignoring saved settings: {}", err);
+            Self::default()
+        })
+    }
+
+    fn save(&self) {
+        match ron::to_string(self) {
+            Ok(saved) => write_saved(&saved),
+            Err(err) => warn!("Can not save settings: {}", err),
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn read_saved() -> Option<String> {
+    let storage = web_sys::window()?.local_storage().ok()??;
+    storage.get_item(STORAGE_KEY).ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+fn write_saved(saved: &str) {
+    let storage = web_sys::window().and_then(|window| window.local_storage().ok().flatten());
+
+    if let Some(storage) = storage {
+        if storage.set_item(STORAGE_KEY, saved).is_err() {
+            warn!("Can not save settings: localStorage is full or disabled");
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn settings_path() -> Option<std::path::PathBuf> {
+    Some(dirs::config_dir()?.join("tienlen").join("settings.ron"))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read_saved() -> Option<String> {
+    std::fs::read_to_string(settings_path()?).ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write_saved(saved: &str) {
+    let Some(path) = settings_path() else {
+        return warn!("Can not save settings: no config directory");
+    };
+
+    let written = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| std::fs::write(&path, saved));
+
+    if let Err(err) = written {
+        warn!("Can not save settings to {:?}: {}", path, err);
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct SettingsPanel {
+    pub open: bool,
+    /// Waits for the key to bind, every other key handler stays quiet
+    pub rebinding: Option<Binding>,
+}
+
+fn toggle_panel(
+    keys: Res<Input<KeyCode>>,
+    mut panel: ResMut<SettingsPanel>,
+    mut settings: ResMut<Settings>,
+) {
+    if let Some(binding) = panel.rebinding {
+        // Escape keeps the old key
+        if let Some(key) = keys.get_just_pressed().next() {
+            if *key != KeyCode::Escape {
+                *binding.key_mut(&mut settings.keys) = *key;
+            }
+            panel.rebinding = None;
+        }
+        return;
+    }
+
+    if keys.just_pressed(KeyCode::Escape) {
+        panel.open = !panel.open;
+    }
+}
+
+fn settings_panel(
+    mut egui_ctx: EguiContexts,
+    mut panel: ResMut<SettingsPanel>,
+    mut settings: ResMut<Settings>,
+    mut language: ResMut<Language>,
+    packs: Res<ThemePacks>,
+    locale: Locale,
+) {
+    let ctx = egui_ctx.ctx_mut();
+
+    egui::Area::new("settings-button")
+        .anchor(egui::Align2::RIGHT_TOP, [-8., 8.])
+        .show(ctx, |ui| {
+            if ui
+                .button("⚙")
+                .on_hover_text(locale.t("settings-title"))
+                .clicked()
+            {
+                panel.open = !panel.open;
+            }
+        });
+
+    // Only touch the resources on a real edit, a change triggers a save
+    let mut edited = settings.clone();
+    let mut picked_language = *language;
+    let mut open = panel.open;
+    let mut rebinding = panel.rebinding;
+
+    egui::Window::new(locale.t("settings-title"))
+        .open(&mut open)
+        .id(egui::Id::new("settings-window"))
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::RIGHT_TOP, [-8., 40.])
+        .show(ctx, |ui| {
+            egui::Grid::new("settings-grid")
+                .num_columns(2)
+                .spacing([16., 8.])
+                .show(ui, |ui| {
+                    ui.label(locale.t("settings-animation-speed"));
+                    ui.horizontal(|ui| {
+                        for speed in AnimationSpeed::ALL {
+                            ui.radio_value(
+                                &mut edited.animation_speed,
+                                speed,
+                                locale.t(speed.id()),
+                            );
+                        }
+                    });
+                    ui.end_row();
+
+                    ui.label(locale.t("settings-sort-mode"));
+                    ui.horizontal(|ui| {
+                        for mode in SortMode::ALL {
+                            ui.radio_value(&mut edited.sort_mode, mode, locale.t(mode.id()));
+                        }
+                    });
+                    ui.end_row();
+
+                    ui.label(locale.t("settings-volume"));
+                    ui.add(
+                        egui::Slider::new(&mut edited.volume, 0.0..=1.0)
+                            .custom_formatter(|volume, _| format!("{:.0}%", volume * 100.)),
+                    );
+                    ui.end_row();
+
+                    ui.label(locale.t("settings-music"));
+                    ui.checkbox(&mut edited.music, "");
+                    ui.end_row();
+
+                    ui.label(locale.t("settings-show-fps"));
+                    ui.checkbox(&mut edited.show_fps, "");
+                    ui.end_row();
+
+                    ui.label(locale.t("settings-theme"));
+                    let picked = packs
+                        .loaded
+                        .iter()
+                        .find(|(id, _)| *id == edited.theme)
+                        .map_or(edited.theme.clone(), |(_, name)| name.clone());
+                    egui::ComboBox::from_id_source("settings-theme")
+                        .selected_text(picked)
+                        .show_ui(ui, |ui| {
+                            for (id, name) in packs.loaded.iter() {
+                                ui.selectable_value(&mut edited.theme, id.clone(), name);
+                            }
+                        });
+                    ui.end_row();
+
+                    ui.label(locale.t("settings-table"));
+                    egui::ComboBox::from_id_source("settings-table")
+                        .selected_text(locale.t(edited.table_theme.id()))
+                        .show_ui(ui, |ui| {
+                            for theme in TableTheme::ALL {
+                                ui.selectable_value(
+                                    &mut edited.table_theme,
+                                    theme,
+                                    locale.t(theme.id()),
+                                );
+                            }
+                        });
+                    ui.end_row();
+
+                    ui.label(locale.t("settings-card-back"));
+                    egui::ComboBox::from_id_source("settings-card-back")
+                        .selected_text(locale.t(edited.card_back.id()))
+                        .show_ui(ui, |ui| {
+                            for back in CardBack::ALL {
+                                ui.selectable_value(
+                                    &mut edited.card_back,
+                                    back,
+                                    locale.t(back.id()),
+                                );
+                            }
+                        });
+                    ui.end_row();
+
+                    ui.label(locale.t("settings-language"));
+                    egui::ComboBox::from_id_source("settings-language")
+                        .selected_text(picked_language.native_name())
+                        .show_ui(ui, |ui| {
+                            for choice in Language::ALL {
+                                ui.selectable_value(
+                                    &mut picked_language,
+                                    choice,
+                                    choice.native_name(),
+                                );
+                            }
+                        });
+                    ui.end_row();
+                });
+
+            ui.separator();
+
+            egui::CollapsingHeader::new(locale.t("settings-keys"))
+                .id_source("settings-keys")
+                .show(ui, |ui| {
+                    egui::Grid::new("settings-keys-grid")
+                        .num_columns(2)
+                        .spacing([16., 4.])
+                        .show(ui, |ui| {
+                            for binding in Binding::ALL {
+                                ui.label(locale.t(binding.id()));
+
+                                let key = if rebinding == Some(binding) {
+                                    locale.t("settings-press-key")
+                                } else {
+                                    format!("{:?}", binding.key_mut(&mut edited.keys))
+                                };
+                                if ui.button(key).clicked() {
+                                    rebinding = Some(binding);
+                                }
+                                ui.end_row();
+                            }
+                        });
+
+                    ui.label(locale.t("settings-quick-select"));
+                });
+
+            ui.separator();
+
+            if ui.button(locale.t("settings-reset")).clicked() {
+                edited = Settings {
+                    language: settings.language,
+                    ..Default::default()
+                };
+            }
+        });
+
+    if open != panel.open {
+        panel.open = open;
+    }
+    if rebinding != panel.rebinding {
+        panel.rebinding = rebinding;
+    }
+    if edited != *settings {
+        *settings = edited;
+    }
+    if picked_language != *language {
+        *language = picked_language;
+    }
+}
+
+/// The language is picked on the welcome screen or in the panel
+fn remember_language(language: Res<Language>, mut settings: ResMut<Settings>) {
+    // Also runs for the language the app started with, nothing picked yet
+    if language.is_added() {
+        return;
+    }
+
+    if settings.language != Some(*language) {
+        settings.language = Some(*language);
+    }
+}
+
+fn apply_fps_overlay(settings: Res<Settings>, mut diags: ResMut<ScreenDiagsState>) {
+    if settings.show_fps != diags.enabled() {
+        if settings.show_fps {
+            diags.enable();
+        } else {
+            diags.disable();
+        }
+    }
+}
+
+fn save_settings(settings: Res<Settings>, time: Res<Time>, mut pending: Local<Option<Timer>>) {
+    if settings.is_changed() && !settings.is_added() {
+        *pending = Some(Timer::new(SAVE_DELAY, TimerMode::Once));
+    }
+
+    let Some(timer) = pending.as_mut() else {
+        return;
+    };
+
+    if timer.tick(time.delta()).finished() {
+        settings.save();
+        *pending = None;
+    }
+}
@@ -8,6 +8,8 @@ pub enum MainState {
     Lobby,
     Wait,
     Game,
+    /// The server speaks another protocol version, nothing to do but update
+    Outdated,
 }
 
 #[derive(Clone, Debug, Default, Hash, Eq, States, PartialEq)]
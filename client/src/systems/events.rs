@@ -18,13 +18,12 @@ use naia_bevy_demo_shared::messages::{
     UpdateScore, UpdateTurn,
 };
 use naia_bevy_demo_shared::{
-    channels::{
-        EntityAssignmentChannel, GameSystemChannel, PlayerActionChannel, PlayerCommandChannel,
-    },
+    channels::{EntityAssignmentChannel, PlayerActionChannel, PlayerCommandChannel},
     components::player::Player,
 };
 
 use crate::{
+    authority::Incoming,
     components::LocalPlayer,
     game::LocalStartGame,
     resources::Global,
@@ -44,8 +43,10 @@ pub fn connect_events(
         };
         info("Client connected to: {server_address}");
 
-        client
-            .send_message::<PlayerActionChannel, NewPlayer>(&NewPlayer(global.player_name.clone()));
+        client.send_message::<PlayerActionChannel, NewPlayer>(&NewPlayer {
+            name: global.player_name.clone(),
+            avatar: global.player_avatar,
+        });
 
         next_state.set(MainState::Lobby);
     }
@@ -63,49 +64,15 @@ pub fn disconnect_events(mut event_reader: EventReader<DisconnectEvent>) {
     }
 }
 
-#[allow(clippy::too_many_arguments)]
+/// The local player's entity, only the server can hand it over
 pub fn message_events(
     mut commands: Commands,
     mut client: Client,
     mut global: ResMut<Global>,
     mut event_reader: EventReader<MessageEvents>,
     player_query: Query<&Player>,
-    mut start_game_ev: EventWriter<LocalStartGame>,
-    // mut draw_status_ev: EventWriter<DrawStatus>,
-    // mut update_player_cards_ev: EventWriter<UpdatePlayerCards>,
-    mut update_score_ev: EventWriter<UpdateScoreUI>,
-    mut new_player_join_ev: EventWriter<NewPlayerJoin>,
-    mut player_message_ev: EventWriter<PlayerMessageEvent>,
 ) {
     for events in event_reader.read() {
-        for message in events.read::<GameSystemChannel, PlayerMessage>() {
-            let event = PlayerMessageEvent(message.0, message.1.to_string());
-            player_message_ev.send(event);
-        }
-
-        for message in events.read::<GameSystemChannel, AcceptStartGame>() {
-            global.game.active_player_pos = 0;
-            start_game_ev.send(LocalStartGame(message.cards));
-        }
-
-        for _ in events.read::<GameSystemChannel, NewPlayer>() {
-            new_player_join_ev.send_default();
-        }
-
-        for _ in events.read::<GameSystemChannel, UpdateScore>() {
-            update_score_ev.send_default();
-        }
-
-        for update_turn in events.read::<GameSystemChannel, UpdateTurn>() {
-            let active_player_pos = update_turn.0 as i32;
-            global.game.active_player_pos = active_player_pos;
-        }
-
-        for update_turn in events.read::<GameSystemChannel, UpdateTurn>() {
-            let active_player_pos = update_turn.0 as i32;
-            global.game.active_player_pos = active_player_pos;
-        }
-
         for message in events.read::<EntityAssignmentChannel, EntityAssignment>() {
             let assign = message.assign;
 
@@ -130,6 +97,43 @@ pub fn message_events(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
+pub fn game_message_events(
+    mut global: ResMut<Global>,
+    mut player_message_ev: EventReader<Incoming<PlayerMessage>>,
+    mut accept_start_ev: EventReader<Incoming<AcceptStartGame>>,
+    mut new_player_ev: EventReader<Incoming<NewPlayer>>,
+    mut update_score_ev: EventReader<Incoming<UpdateScore>>,
+    mut update_turn_ev: EventReader<Incoming<UpdateTurn>>,
+    mut start_game_ev: EventWriter<LocalStartGame>,
+    mut update_score_ui_ev: EventWriter<UpdateScoreUI>,
+    mut new_player_join_ev: EventWriter<NewPlayerJoin>,
+    mut player_message_ui_ev: EventWriter<PlayerMessageEvent>,
+) {
+    for Incoming(message) in player_message_ev.read() {
+        let event = PlayerMessageEvent(message.0, message.1.to_string());
+        player_message_ui_ev.send(event);
+    }
+
+    for Incoming(message) in accept_start_ev.read() {
+        global.game.active_player_pos = 0;
+        start_game_ev.send(LocalStartGame(message.cards.clone()));
+    }
+
+    for _ in new_player_ev.read() {
+        new_player_join_ev.send_default();
+    }
+
+    for _ in update_score_ev.read() {
+        update_score_ui_ev.send_default();
+    }
+
+    for Incoming(update_turn) in update_turn_ev.read() {
+        let active_player_pos = update_turn.0 as i32;
+        global.game.active_player_pos = active_player_pos;
+    }
+}
+
 pub fn spawn_entity_events(mut event_reader: EventReader<SpawnEntityEvent>) {
     for SpawnEntityEvent(_entity) in event_reader.read() {
         info("spawned entity");
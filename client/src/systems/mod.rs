@@ -5,3 +5,4 @@ pub mod sync;
 mod init;
 pub use init::init;
 pub use init::my_cursor_system;
+pub use init::MainCamera;
@@ -0,0 +1,324 @@
+use std::collections::{BTreeMap, HashMap};
+
+use bevy::{asset::LoadState, prelude::*, reflect::TypePath};
+use bevy_common_assets::ron::RonAssetPlugin;
+use naia_bevy_demo_shared::components::card::Card;
+
+use crate::{assets::UiAssets, settings::Settings, states::MainState};
+
+/// Card faces, back, table, background and fonts come from theme packs in
+/// `assets/themes`, switched from the settings while playing
+pub struct ThemePlugin;
+
+impl Plugin for ThemePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(RonAssetPlugin::<ThemeIndex>::new(&["themes.ron"]))
+            .add_plugins(RonAssetPlugin::<ThemePack>::new(&["theme.ron"]))
+            .init_resource::<ThemePacks>()
+            .add_systems(OnEnter(MainState::LoadAssets), load_packs)
+            .add_systems(
+                Update,
+                (list_packs, load_theme, apply_theme)
+                    .chain()
+                    .run_if(resource_exists::<UiAssets>()),
+            );
+    }
+}
+
+/// The pack settings start with
+pub const DEFAULT_THEME: &str = "classic";
+
+/// `assets/themes/index.themes.ron`, the packs to offer by id: the file
+/// name of the manifest without `.theme.ron`
+#[derive(serde::Deserialize, Asset, TypePath, Debug)]
+struct ThemeIndex {
+    packs: Vec<String>,
+}
+
+/// The manifest of a theme pack. Whatever it leaves out comes from the
+/// built-in theme, so does any of its files that fails to load
+#[derive(serde::Deserialize, Asset, TypePath, Debug, Clone)]
+#[serde(default)]
+pub struct ThemePack {
+    /// Shown in the settings, the id when left out
+    pub name: String,
+    /// Path of a card face, `{suit}` and `{rank}` are filled in per card
+    pub card_faces: String,
+    pub card_back: String,
+    /// Takes the place of the table colour picked in the settings
+    pub table: Option<String>,
+    pub background: String,
+    /// For titles, names and scores
+    pub font: String,
+    /// For the other texts, it needs the Vietnamese letters
+    pub text_font: String,
+}
+
+impl Default for ThemePack {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            card_faces: "cards/standard/solitaire/individuals/{suit}/{rank}.png".to_string(),
+            card_back: "cards/standard/solitaire/individuals/card_back/card_back.png".to_string(),
+            table: None,
+            background: "cards/backgrounds/background_1.png".to_string(),
+            font: "fonts/font.ttf".to_string(),
+            text_font: "fonts/DejaVuSans.ttf".to_string(),
+        }
+    }
+}
+
+/// The files of a theme, loaded
+#[derive(Clone)]
+pub struct ThemeHandles {
+    /// By `Card::name`
+    pub cards: HashMap<String, Handle<Image>>,
+    pub back_card: Handle<Image>,
+    pub table: Option<Handle<Image>>,
+    pub background: Handle<Image>,
+    pub font: Handle<Font>,
+    pub text_font: Handle<Font>,
+}
+
+impl ThemeHandles {
+    pub fn load(pack: &ThemePack, asset_server: &AssetServer) -> Self {
+        Self {
+            cards: Card::all_cards()
+                .iter()
+                .map(|card| {
+                    let handle = asset_server.load(card.asset_path(&pack.card_faces));
+                    (card.name(), handle)
+                })
+                .collect(),
+            back_card: asset_server.load(pack.card_back.clone()),
+            table: pack.table.clone().map(|path| asset_server.load(path)),
+            background: asset_server.load(pack.background.clone()),
+            font: asset_server.load(pack.font.clone()),
+            text_font: asset_server.load(pack.text_font.clone()),
+        }
+    }
+
+    fn images(&self) -> impl Iterator<Item = UntypedAssetId> + '_ {
+        self.cards
+            .values()
+            .chain([&self.back_card, &self.background])
+            .chain(self.table.iter())
+            .map(|handle| handle.id().untyped())
+    }
+
+    fn fonts(&self) -> impl Iterator<Item = UntypedAssetId> + '_ {
+        [&self.font, &self.text_font]
+            .into_iter()
+            .map(|handle| handle.id().untyped())
+    }
+
+    /// Still waiting on some file
+    fn loading(&self, asset_server: &AssetServer) -> bool {
+        self.images().chain(self.fonts()).any(|id| {
+            matches!(
+                asset_server.get_load_state(id),
+                Some(LoadState::NotLoaded | LoadState::Loading)
+            )
+        })
+    }
+
+    /// Every file that failed to load replaced by the one of `fallback`
+    fn or(mut self, fallback: &ThemeHandles, asset_server: &AssetServer) -> Self {
+        let failed =
+            |id: UntypedAssetId| asset_server.get_load_state(id) == Some(LoadState::Failed);
+
+        for (name, handle) in self.cards.iter_mut() {
+            if failed(handle.id().untyped()) {
+                *handle = fallback.cards[name].clone();
+            }
+        }
+        if failed(self.back_card.id().untyped()) {
+            self.back_card = fallback.back_card.clone();
+        }
+        if self
+            .table
+            .as_ref()
+            .is_some_and(|table| failed(table.id().untyped()))
+        {
+            self.table = fallback.table.clone();
+        }
+        if failed(self.background.id().untyped()) {
+            self.background = fallback.background.clone();
+        }
+        if failed(self.font.id().untyped()) {
+            self.font = fallback.font.clone();
+        }
+        if failed(self.text_font.id().untyped()) {
+            self.text_font = fallback.text_font.clone();
+        }
+
+        self
+    }
+}
+
+/// The packs there are to pick from
+#[derive(Resource, Default)]
+pub struct ThemePacks {
+    index: Handle<ThemeIndex>,
+    manifests: BTreeMap<String, Handle<ThemePack>>,
+    /// Id and name of every pack loaded so far, for the settings
+    pub loaded: Vec<(String, String)>,
+}
+
+/// The theme being loaded, it takes over once all its files are there
+#[derive(Resource)]
+struct PendingTheme(ThemeHandles);
+
+/// The theme shipped with the game
+#[derive(Resource)]
+struct BuiltInTheme(ThemeHandles);
+
+fn load_packs(
+    mut commands: Commands,
+    mut packs: ResMut<ThemePacks>,
+    asset_server: Res<AssetServer>,
+) {
+    packs.index = asset_server.load("themes/index.themes.ron");
+    commands.insert_resource(BuiltInTheme(ThemeHandles::load(
+        &ThemePack::default(),
+        &asset_server,
+    )));
+}
+
+fn list_packs(
+    mut packs: ResMut<ThemePacks>,
+    indexes: Res<Assets<ThemeIndex>>,
+    manifests: Res<Assets<ThemePack>>,
+    asset_server: Res<AssetServer>,
+) {
+    if packs.manifests.is_empty() {
+        let Some(index) = indexes.get(&packs.index) else {
+            return;
+        };
+
+        packs.manifests = index
+            .packs
+            .iter()
+            .map(|id| {
+                let path = format!("themes/{}.theme.ron", id);
+                (id.clone(), asset_server.load(path))
+            })
+            .collect();
+    }
+
+    let loaded: Vec<(String, String)> = packs
+        .manifests
+        .iter()
+        .filter_map(|(id, handle)| {
+            let pack = manifests.get(handle)?;
+            let name = if pack.name.is_empty() { id } else { &pack.name };
+            Some((id.clone(), name.clone()))
+        })
+        .collect();
+
+    if packs.loaded != loaded {
+        packs.loaded = loaded;
+    }
+}
+
+/// Starts loading the files of the pack picked in the settings
+fn load_theme(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    packs: Res<ThemePacks>,
+    manifests: Res<Assets<ThemePack>>,
+    indexes: Res<Assets<ThemeIndex>>,
+    asset_server: Res<AssetServer>,
+    mut loaded: Local<Option<String>>,
+) {
+    if loaded.as_ref() == Some(&settings.theme) {
+        return;
+    }
+
+    let pack = match packs.manifests.get(&settings.theme) {
+        Some(handle) => match manifests.get(handle) {
+            Some(pack) => pack.clone(),
+            None if asset_server.get_load_state(handle.id()) == Some(LoadState::Failed) => {
+                warn!("Theme {} is broken, using the built-in one", settings.theme);
+                ThemePack::default()
+            }
+            None => return,
+        },
+        // Gone from the index, or no index at all
+        None if indexes.contains(packs.index.id())
+            || asset_server.get_load_state(packs.index.id()) == Some(LoadState::Failed) =>
+        {
+            warn!("No theme {}, using the built-in one", settings.theme);
+            ThemePack::default()
+        }
+        None => return,
+    };
+
+    info!("Loading theme {}", settings.theme);
+    commands.insert_resource(PendingTheme(ThemeHandles::load(&pack, &asset_server)));
+    *loaded = Some(settings.theme.clone());
+}
+
+/// Once the files of the new theme are there, swaps them in for the ones of
+/// the old theme everywhere they show. The table follows `UiAssets` itself
+fn apply_theme(
+    mut commands: Commands,
+    pending: Option<Res<PendingTheme>>,
+    built_in: Res<BuiltInTheme>,
+    asset_server: Res<AssetServer>,
+    mut res: ResMut<UiAssets>,
+    mut image_q: Query<&mut Handle<Image>>,
+    mut text_q: Query<&mut Text>,
+) {
+    let Some(pending) = pending else {
+        return;
+    };
+    if pending.0.loading(&asset_server) || built_in.0.loading(&asset_server) {
+        return;
+    }
+    commands.remove_resource::<PendingTheme>();
+
+    let theme = pending.0.clone().or(&built_in.0, &asset_server);
+
+    let mut images: HashMap<AssetId<Image>, Handle<Image>> = res
+        .cards
+        .iter()
+        .map(|(name, old)| (old.id(), theme.cards[name].clone()))
+        .collect();
+    images.insert(res.back_card.id(), theme.back_card.clone());
+    images.insert(res.background.id(), theme.background.clone());
+
+    let fonts: HashMap<AssetId<Font>, Handle<Font>> = [
+        (res.font.id(), theme.font.clone()),
+        (res.text_font.id(), theme.text_font.clone()),
+    ]
+    .into_iter()
+    .collect();
+
+    for mut image in image_q.iter_mut() {
+        if let Some(new) = images.get(&image.id()) {
+            if *new != *image {
+                *image = new.clone();
+            }
+        }
+    }
+
+    for mut text in text_q.iter_mut() {
+        let themed = |section: &TextSection| {
+            fonts
+                .get(&section.style.font.id())
+                .is_some_and(|new| *new != section.style.font)
+        };
+        if !text.sections.iter().any(themed) {
+            continue;
+        }
+
+        for section in text.sections.iter_mut() {
+            if let Some(new) = fonts.get(&section.style.font.id()) {
+                section.style.font = new.clone();
+            }
+        }
+    }
+
+    res.set_theme(theme);
+}
@@ -3,6 +3,7 @@ use naia_bevy_demo_shared::components::card::Card;
 use std::collections::HashMap;
 
 use super::UiAssets;
+use crate::theme::ThemePack;
 
 pub fn load_assets(
     mut commands: Commands,
@@ -35,8 +36,9 @@ pub fn load_assets(
     let mut cards = HashMap::new();
     let all_cards: &[Card] = Card::all_cards();
 
+    let faces = ThemePack::default().card_faces;
     for card in all_cards {
-        let handle = asset_server.load(card.to_path());
+        let handle = asset_server.load(card.asset_path(&faces));
         cards.insert(card.name(), handle);
     }
 
@@ -1,7 +1,18 @@
-use crate::{assets::UiAssets, resources::Global};
+use crate::{
+    assets::UiAssets,
+    authority::Mode,
+    connection,
+    locale::{Language, Locale},
+    resources::Global,
+    settings::Settings,
+};
 use bevy::prelude::*;
 use bevy_egui::{egui, EguiContexts, EguiPlugin};
-use naia_bevy_client::{transport::webrtc, Client};
+use naia_bevy_client::{events::MessageEvents, Client};
+use naia_bevy_demo_shared::{
+    channels::GameSystemChannel, components::player::AVATAR_COUNT, messages::IncompatibleVersion,
+    PROTOCOL_VERSION,
+};
 
 use crate::states::MainState;
 
@@ -14,9 +25,14 @@ impl Plugin for WelcomeScreenPlugin {
             .init_resource::<UiState>()
             .add_systems(OnEnter(MainState::Welcome), setup)
             .add_systems(Update, join.run_if(on_event::<JoinEvent>()))
+            .add_systems(Update, incompatible_version_events)
             .add_systems(
                 Update,
                 name_input_system.run_if(in_state(MainState::Welcome)),
+            )
+            .add_systems(
+                Update,
+                outdated_screen.run_if(in_state(MainState::Outdated)),
             );
     }
 }
@@ -24,19 +40,41 @@ impl Plugin for WelcomeScreenPlugin {
 #[derive(Default, Resource)]
 struct UiState {
     name: String,
+    avatar: usize,
     can_join: bool,
+    /// Names of the others playing on this device
+    guests: [String; GUESTS],
+    /// Protocol version of the server, set when it turned us away
+    server_version: Option<String>,
 }
 
+const AVATAR_PICKER_SIZE: f32 = 48.;
+/// Up to four players share a device
+const GUESTS: usize = 3;
+
 #[derive(Default, Event)]
-struct JoinEvent(String);
+struct JoinEvent {
+    name: String,
+    avatar: usize,
+    mode: Mode,
+    /// The other players, in hot seat
+    guests: Vec<String>,
+}
 
 impl JoinEvent {
     pub fn player_name(&self) -> String {
-        self.0.clone()
+        self.name.clone()
     }
 }
 
-pub fn setup(mut commands: Commands, res: Res<UiAssets>) {
+fn setup(
+    mut commands: Commands,
+    res: Res<UiAssets>,
+    settings: Res<Settings>,
+    mut ui_state: ResMut<UiState>,
+) {
+    ui_state.avatar = settings.avatar.min(AVATAR_COUNT - 1);
+
     let background = res.background.clone();
 
     commands.spawn(SpriteBundle {
@@ -46,67 +84,224 @@ pub fn setup(mut commands: Commands, res: Res<UiAssets>) {
     });
 }
 
-fn join(mut client: Client, mut join_ev: EventReader<JoinEvent>, mut global: ResMut<Global>) {
-    // Process connect sever here?
-    // FIXME: I don't want to messing up with these env
-    // let auth_user_name = env!("AUTH_USER_NAME");
-    // let auth_user_pass = env!("AUTH_USER_PASS");
-    let server_address = env!("SERVER_INIT_ADDRESS");
-    //
-    // client.auth(Auth::new(auth_user_name, auth_user_pass));
-    let socket = webrtc::Socket::new(server_address, client.socket_config());
-    client.connect(socket);
-
+fn join(
+    mut client: Client,
+    mut join_ev: EventReader<JoinEvent>,
+    mut global: ResMut<Global>,
+    mut settings: ResMut<Settings>,
+    mut mode: ResMut<Mode>,
+    mut next_state: ResMut<NextState<MainState>>,
+) {
     for join_data in join_ev.read() {
         info!("Sending Player Data: {:?}", join_data.player_name());
         // Pass to global is a hack!!!
         global.player_name = join_data.player_name();
-    }
+        global.player_avatar = join_data.avatar;
+
+        if settings.avatar != join_data.avatar {
+            settings.avatar = join_data.avatar;
+        }
 
-    // next_state.set(MainState::Lobby);
+        *mode = join_data.mode;
+        if mode.is_local() {
+            global.guest_names = join_data.guests.clone();
+            next_state.set(MainState::Lobby);
+        } else {
+            connection::connect(&mut client);
+        }
+    }
 }
 
 fn name_input_system(
     mut egui_ctx: EguiContexts,
     mut ui_state: ResMut<UiState>,
     mut join_event: EventWriter<JoinEvent>,
+    mut language: ResMut<Language>,
+    res: Res<UiAssets>,
+    locale: Locale,
     client: Client,
 ) {
+    let avatars: Vec<egui::TextureId> = (0..AVATAR_COUNT as i32)
+        .filter_map(|i| res.avatars.get(&i))
+        .map(|handle| egui_ctx.add_image(handle.clone_weak()))
+        .collect();
+
     egui::CentralPanel::default().show(egui_ctx.ctx_mut(), |ui| {
         ui_state.can_join = !ui_state.name.is_empty();
 
         ui.add_space(30.);
 
-        ui.vertical_centered(|ui| ui.heading("Tien Len Online"));
+        ui.vertical_centered(|ui| ui.heading(locale.t("welcome-title")));
 
         ui.add_space(30.);
 
         ui.vertical_centered(|ui| {
             ui.add_sized(
                 [150.0, 20.0],
-                egui::TextEdit::singleline(&mut ui_state.name).hint_text("Enter your name here..."),
+                egui::TextEdit::singleline(&mut ui_state.name)
+                    .hint_text(locale.t("welcome-name-hint")),
             );
 
             ui.add_space(10.);
 
+            ui.label(locale.t("welcome-avatar"));
+            // Centered by hand, a horizontal layout fills the whole width
+            let width = avatars.len() as f32 * (AVATAR_PICKER_SIZE + 12.);
+            ui.allocate_ui(egui::vec2(width, AVATAR_PICKER_SIZE + 12.), |ui| {
+                ui.horizontal(|ui| {
+                    for (index, texture) in avatars.iter().enumerate() {
+                        let image = egui::load::SizedTexture::new(
+                            *texture,
+                            [AVATAR_PICKER_SIZE, AVATAR_PICKER_SIZE],
+                        );
+                        if ui
+                            .add(egui::ImageButton::new(image).selected(ui_state.avatar == index))
+                            .clicked()
+                        {
+                            ui_state.avatar = index;
+                        }
+                    }
+                });
+            });
+
+            ui.add_space(10.);
+
             if client.is_connecting() {
                 ui.spinner();
                 ui.add_space(5.);
-                ui.label("Connecting to server...");
-            } else if ui
-                .add_enabled(ui_state.can_join, egui::Button::new("Join"))
-                .clicked()
-            {
-                join_event.send(JoinEvent(ui_state.name.clone()))
+                ui.label(locale.t("welcome-connecting"));
+            } else {
+                let join = ui.add_enabled(
+                    ui_state.can_join,
+                    egui::Button::new(locale.t("welcome-join")),
+                );
+                let practice = ui
+                    .add_enabled(
+                        ui_state.can_join,
+                        egui::Button::new(locale.t("welcome-practice")),
+                    )
+                    .on_hover_text(locale.t("welcome-practice-hint"));
+
+                if join.clicked() || practice.clicked() {
+                    join_event.send(JoinEvent {
+                        name: ui_state.name.clone(),
+                        avatar: ui_state.avatar,
+                        mode: if practice.clicked() {
+                            Mode::Practice
+                        } else {
+                            Mode::Online
+                        },
+                        guests: vec![],
+                    })
+                }
+
+                ui.add_space(10.);
+
+                ui.collapsing(locale.t("welcome-hot-seat"), |ui| {
+                    for guest in ui_state.guests.iter_mut() {
+                        ui.add_sized(
+                            [150.0, 20.0],
+                            egui::TextEdit::singleline(guest)
+                                .hint_text(locale.t("welcome-guest-hint")),
+                        );
+                    }
+
+                    let guests: Vec<String> = ui_state
+                        .guests
+                        .iter()
+                        .map(|guest| guest.trim().to_string())
+                        .filter(|guest| !guest.is_empty())
+                        .collect();
+
+                    let hot_seat = ui.add_enabled(
+                        ui_state.can_join && !guests.is_empty(),
+                        egui::Button::new(locale.t("welcome-hot-seat-start")),
+                    );
+                    if hot_seat.clicked() {
+                        join_event.send(JoinEvent {
+                            name: ui_state.name.clone(),
+                            avatar: ui_state.avatar,
+                            mode: Mode::HotSeat,
+                            guests,
+                        })
+                    }
+                });
             }
+
+            ui.add_space(20.);
+
+            ui.horizontal(|ui| {
+                for choice in Language::ALL {
+                    if ui
+                        .selectable_label(*language == choice, choice.native_name())
+                        .clicked()
+                    {
+                        *language = choice;
+                    }
+                }
+            });
         });
 
         ui.with_layout(egui::Layout::bottom_up(egui::Align::Center), |ui| {
             ui.add(egui::Hyperlink::from_label_and_url(
-                "Developed by Tung To",
+                locale.t("welcome-credits"),
                 "https://github.com/tungtose",
             ));
         });
         egui::warn_if_debug_build(ui);
     });
 }
+
+fn incompatible_version_events(
+    mut client: Client,
+    mut ui_state: ResMut<UiState>,
+    mut next_state: ResMut<NextState<MainState>>,
+    mut event_reader: EventReader<MessageEvents>,
+) {
+    for events in event_reader.read() {
+        for message in events.read::<GameSystemChannel, IncompatibleVersion>() {
+            info!(
+                "Server speaks protocol {}, this client {}",
+                message.server_version, PROTOCOL_VERSION
+            );
+
+            ui_state.server_version = Some(message.server_version);
+            client.disconnect();
+            next_state.set(MainState::Outdated);
+        }
+    }
+}
+
+fn outdated_screen(mut egui_ctx: EguiContexts, ui_state: Res<UiState>, locale: Locale) {
+    let server_version = ui_state.server_version.as_deref().unwrap_or("unknown");
+
+    egui::CentralPanel::default().show(egui_ctx.ctx_mut(), |ui| {
+        ui.add_space(30.);
+
+        ui.vertical_centered(|ui| {
+            ui.heading(locale.t("outdated-title"));
+
+            ui.add_space(20.);
+
+            ui.label(locale.t_args(
+                "outdated-versions",
+                &[("server", &server_version), ("client", &PROTOCOL_VERSION)],
+            ));
+
+            ui.add_space(10.);
+
+            if cfg!(target_arch = "wasm32") {
+                ui.label(locale.t("outdated-refresh"));
+
+                #[cfg(target_arch = "wasm32")]
+                if ui.button(locale.t("outdated-refresh-button")).clicked() {
+                    if let Some(window) = web_sys::window() {
+                        let _ = window.location().reload();
+                    }
+                }
+            } else {
+                ui.label(locale.t("outdated-update"));
+            }
+        });
+    });
+}
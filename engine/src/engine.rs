@@ -0,0 +1,1313 @@
+use std::{
+    cmp::Ordering,
+    collections::{BTreeSet, HashSet, VecDeque},
+};
+
+use naia_bevy_demo_shared::{
+    components::{
+        cards::Cards,
+        deck::Deck,
+        hand::Hand,
+        table::{Phase, Trick},
+        turn::Turn,
+    },
+    messages::GameError,
+};
+
+const CARDS_PER_PLAYER: usize = 13;
+const MAX_NAME_LEN: usize = 10;
+
+#[derive(Clone, Debug)]
+pub struct Rules {
+    pub min_players: usize,
+    pub max_players: usize,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            min_players: 2,
+            max_players: 4,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Seat {
+    pub name: String,
+    pub hand: Hand,
+    /// Points over the whole session
+    pub score: u32,
+    pub ready: bool,
+}
+
+/// Something a player (or the clock) wants to do, `pos` is the seat position
+#[derive(Clone, Debug)]
+pub enum Action {
+    Join {
+        name: String,
+    },
+    Ready {
+        pos: usize,
+    },
+    /// Moves to an empty seat, only in the lobby
+    TakeSeat {
+        pos: usize,
+        to: usize,
+    },
+    /// The player is gone, the seat is freed once no match is running
+    Leave {
+        pos: usize,
+    },
+    /// Sets the table up, the deal follows once everyone asked for it
+    Start,
+    RequestDeal {
+        pos: usize,
+    },
+    Play {
+        pos: usize,
+        cards: Hand,
+    },
+    Skip {
+        pos: usize,
+    },
+    /// The active player ran out of time
+    Timeout,
+    /// Ends the running match right away
+    EndMatch,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    PlayerJoined {
+        pos: usize,
+        name: String,
+    },
+    PlayerReady {
+        pos: usize,
+        name: String,
+    },
+    SeatChanged {
+        from: usize,
+        to: usize,
+    },
+    PlayerLeft {
+        pos: usize,
+    },
+    /// Too few players are left to go on, the table waits for more
+    BackToLobby,
+    TableSetUp,
+    /// `hands` is indexed by seat position, empty seats get an empty hand
+    MatchStarted {
+        hands: Vec<Hand>,
+        active_player: usize,
+    },
+    CardsPlayed {
+        pos: usize,
+        cards: Hand,
+        hand_left: Hand,
+        next_player: usize,
+        run_out: bool,
+    },
+    TurnChanged(usize),
+    TurnSkipped {
+        pos: usize,
+        next_player: usize,
+    },
+    /// `points` earned for this match, `score` for the session
+    PlayerFinished {
+        pos: usize,
+        points: u32,
+        score: u32,
+    },
+    /// Best first, the players still holding cards at the end last
+    MatchEnded {
+        standings: Vec<Standing>,
+    },
+}
+
+/// Where a player ended a match
+#[derive(Clone, Debug, PartialEq)]
+pub struct Standing {
+    pub pos: usize,
+    /// Earned this match
+    pub points: u32,
+    pub score: u32,
+    /// Empty for the players who ran out
+    pub hand_left: Hand,
+}
+
+/// One table of Tien Len
+#[derive(Debug)]
+pub struct GameEngine {
+    rules: Rules,
+    /// One per chair, `None` where nobody sits
+    seats: Vec<Option<Seat>>,
+    turn: Turn,
+    /// Every hand played in the current match, the last one is the one to beat
+    table: VecDeque<Hand>,
+    /// The active player leads the round and can play any combination
+    leader_turn: bool,
+    /// Plays and passes since the lead was last free
+    round: Vec<Trick>,
+    /// Seats that ran out of cards this match, with the points they got
+    finished: Vec<(usize, u32)>,
+    /// Seats that asked for the next deal, a seat asking twice counts once
+    deal_requests: BTreeSet<usize>,
+    /// Seats of the players gone in the middle of a match, freed once it ends
+    leaving: BTreeSet<usize>,
+    phase: Phase,
+}
+
+impl Default for GameEngine {
+    fn default() -> Self {
+        Self::new(Rules::default())
+    }
+}
+
+impl GameEngine {
+    pub fn new(rules: Rules) -> Self {
+        Self {
+            seats: vec![None; rules.max_players],
+            rules,
+            turn: Turn::default(),
+            table: VecDeque::new(),
+            leader_turn: true,
+            round: vec![],
+            finished: vec![],
+            deal_requests: BTreeSet::new(),
+            leaving: BTreeSet::new(),
+            phase: Phase::Lobby,
+        }
+    }
+
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    /// The taken seats with their position
+    pub fn seats(&self) -> impl Iterator<Item = (usize, &Seat)> {
+        self.seats
+            .iter()
+            .enumerate()
+            .filter_map(|(pos, seat)| Some((pos, seat.as_ref()?)))
+    }
+
+    pub fn seat(&self, pos: usize) -> Option<&Seat> {
+        self.seats.get(pos)?.as_ref()
+    }
+
+    fn players(&self) -> usize {
+        self.seats().count()
+    }
+
+    fn empty_seats(&self) -> HashSet<usize> {
+        (0..self.seats.len())
+            .filter(|pos| self.seats[*pos].is_none())
+            .collect()
+    }
+
+    /// Position of the player who has to move, only while playing
+    pub fn active_player(&self) -> Option<usize> {
+        if self.phase != Phase::Playing {
+            return None;
+        }
+
+        self.turn.current_active_player()
+    }
+
+    pub fn leader_turn(&self) -> bool {
+        self.leader_turn
+    }
+
+    /// Last hand played in the current match
+    pub fn last_played(&self) -> Option<&Hand> {
+        self.table.back()
+    }
+
+    /// Every play and pass of the current round, oldest first
+    pub fn round(&self) -> &[Trick] {
+        &self.round
+    }
+
+    pub fn apply(&mut self, action: Action) -> Result<Vec<Event>, GameError> {
+        match action {
+            Action::Join { name } => self.join(name),
+            Action::Ready { pos } => self.ready(pos),
+            Action::TakeSeat { pos, to } => self.take_seat(pos, to),
+            Action::Leave { pos } => self.leave(pos),
+            Action::Start => self.start(),
+            Action::RequestDeal { pos } => self.request_deal(pos),
+            Action::Play { pos, cards } => self.play(pos, cards),
+            Action::Skip { pos } => self.skip(pos),
+            Action::Timeout => self.timeout(),
+            Action::EndMatch => {
+                self.expect_phase(Phase::Playing)?;
+                Ok(self.end_match())
+            }
+        }
+    }
+
+    fn expect_phase(&self, phase: Phase) -> Result<(), GameError> {
+        if self.phase != phase {
+            return Err(GameError::WrongPhase { phase: self.phase });
+        }
+
+        Ok(())
+    }
+
+    fn seat_mut(&mut self, pos: usize) -> Result<&mut Seat, GameError> {
+        self.seats
+            .get_mut(pos)
+            .and_then(Option::as_mut)
+            .ok_or(GameError::NoSeat)
+    }
+
+    fn join(&mut self, name: String) -> Result<Vec<Event>, GameError> {
+        self.expect_phase(Phase::Lobby)?;
+
+        // The first empty seat, whoever left it
+        let Some(pos) = self.seats.iter().position(Option::is_none) else {
+            return Err(GameError::TableFull);
+        };
+
+        let name = name.chars().take(MAX_NAME_LEN).collect::<String>();
+
+        self.seats[pos] = Some(Seat {
+            name: name.clone(),
+            ..Default::default()
+        });
+
+        Ok(vec![Event::PlayerJoined { pos, name }])
+    }
+
+    fn ready(&mut self, pos: usize) -> Result<Vec<Event>, GameError> {
+        let seat = self.seat_mut(pos)?;
+        seat.ready = true;
+
+        Ok(vec![Event::PlayerReady {
+            pos,
+            name: seat.name.clone(),
+        }])
+    }
+
+    fn take_seat(&mut self, pos: usize, to: usize) -> Result<Vec<Event>, GameError> {
+        self.expect_phase(Phase::Lobby)?;
+        self.seat_mut(pos)?;
+
+        match self.seats.get(to) {
+            None => return Err(GameError::NoSeat),
+            Some(Some(_)) if to == pos => return Ok(vec![]),
+            Some(Some(_)) => return Err(GameError::SeatTaken),
+            Some(None) => {}
+        }
+
+        self.seats[to] = self.seats[pos].take();
+
+        Ok(vec![Event::SeatChanged { from: pos, to }])
+    }
+
+    fn leave(&mut self, pos: usize) -> Result<Vec<Event>, GameError> {
+        self.seat_mut(pos)?;
+
+        // The clock plays for them until the match is over
+        if self.phase == Phase::Playing {
+            self.leaving.insert(pos);
+            return Ok(vec![]);
+        }
+
+        let mut events = self.free_seat(pos);
+        // The others may all be waiting for the deal already
+        if self.phase == Phase::Dealing {
+            events.extend(self.deal_if_requested());
+        }
+
+        Ok(events)
+    }
+
+    /// Empties the seat, back to the lobby when too few are left to play
+    fn free_seat(&mut self, pos: usize) -> Vec<Event> {
+        self.seats[pos] = None;
+        self.deal_requests.remove(&pos);
+
+        let mut events = vec![Event::PlayerLeft { pos }];
+
+        if self.phase != Phase::Lobby && self.players() < self.rules.min_players {
+            self.phase = Phase::Lobby;
+            events.push(Event::BackToLobby);
+        }
+
+        events
+    }
+
+    fn start(&mut self) -> Result<Vec<Event>, GameError> {
+        self.expect_phase(Phase::Lobby)?;
+
+        if self.players() < self.rules.min_players {
+            return Err(GameError::NotEnoughPlayers {
+                needed: self.rules.min_players,
+            });
+        }
+
+        if self.seats().any(|(_, seat)| !seat.ready) {
+            return Err(GameError::PlayersNotReady);
+        }
+
+        self.turn = Turn::with_empty_seats(self.seats.len(), self.empty_seats());
+        self.phase = Phase::Dealing;
+
+        Ok(vec![Event::TableSetUp])
+    }
+
+    fn request_deal(&mut self, pos: usize) -> Result<Vec<Event>, GameError> {
+        self.expect_phase(Phase::Dealing)?;
+        self.seat_mut(pos)?;
+
+        self.deal_requests.insert(pos);
+
+        Ok(self.deal_if_requested())
+    }
+
+    /// Deals once every seat asked for it
+    fn deal_if_requested(&mut self) -> Vec<Event> {
+        if self.deal_requests.len() < self.players() {
+            return vec![];
+        }
+
+        vec![self.deal()]
+    }
+
+    fn deal(&mut self) -> Event {
+        // Players may have left since the last match
+        self.turn = Turn::with_empty_seats(self.seats.len(), self.empty_seats());
+        self.reset_match();
+
+        let mut deck = Deck::new();
+
+        for seat in self.seats.iter_mut().flatten() {
+            seat.hand = Hand {
+                cards: deck.deal(CARDS_PER_PLAYER),
+            };
+        }
+
+        self.phase = Phase::Playing;
+
+        Event::MatchStarted {
+            hands: self
+                .seats
+                .iter()
+                .map(|seat| {
+                    seat.as_ref()
+                        .map(|seat| seat.hand.clone())
+                        .unwrap_or_default()
+                })
+                .collect(),
+            active_player: self.turn.current_active_player().unwrap_or_default(),
+        }
+    }
+
+    fn play(&mut self, pos: usize, cards: Hand) -> Result<Vec<Event>, GameError> {
+        self.expect_phase(Phase::Playing)?;
+        self.expect_turn(pos)?;
+
+        let hand = &self.seat(pos).ok_or(GameError::NoSeat)?.hand;
+        let missing: Vec<_> = cards
+            .cards
+            .iter()
+            .filter(|card| !hand.cards.contains(card))
+            .cloned()
+            .collect();
+        if !missing.is_empty() {
+            return Err(GameError::MissingCards {
+                cards: Hand { cards: missing },
+            });
+        }
+
+        // The leader of a round can play anything
+        let last_played = self.table.back().filter(|_| !self.leader_turn);
+        check_play(last_played, &cards)?;
+
+        self.accept_play(pos, cards)
+    }
+
+    fn expect_turn(&self, pos: usize) -> Result<(), GameError> {
+        match self.turn.current_active_player() {
+            Some(active_player) if active_player != pos => {
+                Err(GameError::WrongTurn { active_player })
+            }
+            Some(_) => Ok(()),
+            None => Err(GameError::NoSeat),
+        }
+    }
+
+    fn accept_play(&mut self, pos: usize, cards: Hand) -> Result<Vec<Event>, GameError> {
+        let seat = self.seat_mut(pos)?;
+        seat.hand.remove_cards(cards.cards.as_slice());
+        let hand_left = seat.hand.clone();
+
+        // Leading starts a new round
+        if self.leader_turn {
+            self.round.clear();
+        }
+        self.round.push(Trick {
+            pos,
+            cards: Some(cards.clone()),
+        });
+
+        self.leader_turn = false;
+        self.table.push_back(cards.clone());
+
+        if !hand_left.is_empty() {
+            let next_player = self.turn.next_turn().unwrap();
+            // Everybody else passed already, the player leads again
+            if next_player == pos {
+                self.leader_turn = true;
+            }
+
+            return Ok(vec![
+                Event::CardsPlayed {
+                    pos,
+                    cards,
+                    hand_left,
+                    next_player,
+                    run_out: false,
+                },
+                Event::TurnChanged(next_player),
+            ]);
+        }
+
+        let points = self.turn.next_score();
+        let seat = self.seat_mut(pos)?;
+        seat.score += points;
+        let score = seat.score;
+        self.finished.push((pos, points));
+
+        let next_player = self.turn.player_out();
+
+        let mut events = vec![
+            Event::CardsPlayed {
+                pos,
+                cards,
+                hand_left,
+                next_player,
+                run_out: true,
+            },
+            Event::PlayerFinished { pos, points, score },
+        ];
+
+        if self.turn.only_one_player_left() {
+            events.extend(self.end_match());
+        }
+
+        Ok(events)
+    }
+
+    fn skip(&mut self, pos: usize) -> Result<Vec<Event>, GameError> {
+        self.expect_phase(Phase::Playing)?;
+
+        self.expect_turn(pos)?;
+
+        if self.leader_turn {
+            return Err(GameError::CanNotSkipTurn);
+        }
+
+        Ok(self.pass(pos))
+    }
+
+    fn pass(&mut self, pos: usize) -> Vec<Event> {
+        let (leader_turn, Some(next_player)) = self.turn.skip_turn() else {
+            return vec![];
+        };
+
+        // Everybody else passed, the next player leads a new round
+        self.leader_turn = leader_turn;
+        self.round.push(Trick { pos, cards: None });
+
+        vec![Event::TurnSkipped { pos, next_player }]
+    }
+
+    /// The leader plays their smallest card, anyone else passes
+    fn timeout(&mut self) -> Result<Vec<Event>, GameError> {
+        self.expect_phase(Phase::Playing)?;
+
+        let pos = self.turn.current_active_player().ok_or(GameError::NoSeat)?;
+
+        if !self.leader_turn {
+            return Ok(self.pass(pos));
+        }
+
+        let smallest_card = self.seat_mut(pos)?.hand.clone().remove_smallest_card();
+
+        self.accept_play(
+            pos,
+            Hand {
+                cards: vec![smallest_card],
+            },
+        )
+    }
+
+    fn end_match(&mut self) -> Vec<Event> {
+        let standings = self.standings();
+
+        self.reset_match();
+        self.phase = Phase::Dealing;
+
+        let mut events = vec![Event::MatchEnded { standings }];
+        for pos in std::mem::take(&mut self.leaving) {
+            events.extend(self.free_seat(pos));
+        }
+
+        events
+    }
+
+    fn standings(&self) -> Vec<Standing> {
+        let standing = |pos: usize, points: u32| {
+            let (score, hand_left) = self
+                .seat(pos)
+                .map(|seat| (seat.score, seat.hand.clone()))
+                .unwrap_or_default();
+
+            Standing {
+                pos,
+                points,
+                score,
+                hand_left,
+            }
+        };
+
+        let mut still_playing: Vec<usize> = self
+            .seats()
+            .map(|(pos, _)| pos)
+            .filter(|pos| !self.finished.iter().any(|(finished, _)| finished == pos))
+            .collect();
+        // Fewer cards left is better when the match was cut short
+        still_playing.sort_by_key(|pos| (self.seat(*pos).map_or(0, |seat| seat.hand.len()), *pos));
+
+        self.finished
+            .iter()
+            .map(|(pos, points)| standing(*pos, *points))
+            .chain(still_playing.into_iter().map(|pos| standing(pos, 0)))
+            .collect()
+    }
+
+    fn reset_match(&mut self) {
+        self.table.clear();
+        self.round.clear();
+        self.finished.clear();
+        self.leader_turn = true;
+        self.deal_requests.clear();
+        self.turn.new_match();
+    }
+}
+
+/// Whether `cards` can go on top of `last_played`, `None` when the player
+/// leads the round and only needs a combination
+pub fn check_play(last_played: Option<&Hand>, cards: &Hand) -> Result<(), GameError> {
+    let expected =
+        last_played.map(|last_played| (last_played.get_combination(), last_played.len()));
+
+    if cards.is_empty() || !cards.check_combination() {
+        return Err(GameError::WrongCombination { expected });
+    }
+
+    if let Some(last_played) = last_played {
+        if expected != Some((cards.get_combination(), cards.len())) {
+            return Err(GameError::WrongCombination { expected });
+        }
+
+        if last_played.cmp(cards) == Ordering::Greater {
+            return Err(GameError::InvalidCards {
+                to_beat: last_played.clone(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use naia_bevy_demo_shared::components::cards::ThirteenCombination;
+
+    use super::*;
+
+    /// Two ready players with the first match dealt
+    fn playing_engine() -> GameEngine {
+        let mut engine = GameEngine::default();
+
+        for name in ["alice", "bob"] {
+            engine
+                .apply(Action::Join {
+                    name: name.to_string(),
+                })
+                .unwrap();
+        }
+
+        for pos in 0..2 {
+            engine.apply(Action::Ready { pos }).unwrap();
+        }
+
+        engine.apply(Action::Start).unwrap();
+        engine.apply(Action::RequestDeal { pos: 0 }).unwrap();
+        engine.apply(Action::RequestDeal { pos: 1 }).unwrap();
+
+        engine
+    }
+
+    fn three_player_engine() -> GameEngine {
+        let mut engine = GameEngine::default();
+
+        for name in ["alice", "bob", "carol"] {
+            engine
+                .apply(Action::Join {
+                    name: name.to_string(),
+                })
+                .unwrap();
+        }
+
+        for pos in 0..3 {
+            engine.apply(Action::Ready { pos }).unwrap();
+        }
+
+        engine.apply(Action::Start).unwrap();
+        for pos in 0..3 {
+            engine.apply(Action::RequestDeal { pos }).unwrap();
+        }
+
+        engine
+    }
+
+    fn give(engine: &mut GameEngine, pos: usize, cards: &str) {
+        engine.seats[pos].as_mut().unwrap().hand = Hand::from_str(cards);
+    }
+
+    #[test]
+    fn test_join_until_full() {
+        let mut engine = GameEngine::default();
+
+        for pos in 0..4 {
+            let events = engine
+                .apply(Action::Join {
+                    name: "a very long name".to_string(),
+                })
+                .unwrap();
+
+            assert_eq!(
+                events,
+                vec![Event::PlayerJoined {
+                    pos,
+                    name: "a very lon".to_string()
+                }]
+            );
+        }
+
+        let full = engine.apply(Action::Join {
+            name: "late".to_string(),
+        });
+
+        assert_eq!(full.err(), Some(GameError::TableFull));
+    }
+
+    #[test]
+    fn test_start_needs_ready_players() {
+        let mut engine = GameEngine::default();
+
+        engine
+            .apply(Action::Join {
+                name: "alice".to_string(),
+            })
+            .unwrap();
+
+        assert_eq!(
+            engine.apply(Action::Start).err(),
+            Some(GameError::NotEnoughPlayers { needed: 2 })
+        );
+
+        engine
+            .apply(Action::Join {
+                name: "bob".to_string(),
+            })
+            .unwrap();
+        engine.apply(Action::Ready { pos: 0 }).unwrap();
+
+        assert_eq!(
+            engine.apply(Action::Start).err(),
+            Some(GameError::PlayersNotReady)
+        );
+
+        engine.apply(Action::Ready { pos: 1 }).unwrap();
+
+        assert_eq!(engine.apply(Action::Start), Ok(vec![Event::TableSetUp]));
+        assert_eq!(engine.phase(), Phase::Dealing);
+    }
+
+    #[test]
+    fn test_take_seat_and_leave_in_lobby() {
+        let mut engine = GameEngine::default();
+
+        for name in ["alice", "bob"] {
+            engine
+                .apply(Action::Join {
+                    name: name.to_string(),
+                })
+                .unwrap();
+        }
+
+        assert_eq!(
+            engine.apply(Action::TakeSeat { pos: 1, to: 0 }).err(),
+            Some(GameError::SeatTaken)
+        );
+        assert_eq!(
+            engine.apply(Action::TakeSeat { pos: 1, to: 3 }),
+            Ok(vec![Event::SeatChanged { from: 1, to: 3 }])
+        );
+        assert_eq!(engine.seat(3).map(|seat| seat.name.as_str()), Some("bob"));
+
+        assert_eq!(
+            engine.apply(Action::Leave { pos: 0 }),
+            Ok(vec![Event::PlayerLeft { pos: 0 }])
+        );
+
+        // The seat left behind is the first one a newcomer gets
+        let joined = engine.apply(Action::Join {
+            name: "carol".to_string(),
+        });
+        assert_eq!(
+            joined,
+            Ok(vec![Event::PlayerJoined {
+                pos: 0,
+                name: "carol".to_string()
+            }])
+        );
+
+        engine.apply(Action::Ready { pos: 0 }).unwrap();
+        engine.apply(Action::Ready { pos: 3 }).unwrap();
+        engine.apply(Action::Start).unwrap();
+
+        assert_eq!(
+            engine.apply(Action::TakeSeat { pos: 3, to: 1 }).err(),
+            Some(GameError::WrongPhase {
+                phase: Phase::Dealing
+            })
+        );
+    }
+
+    #[test]
+    fn test_turns_skip_empty_seats() {
+        let mut engine = GameEngine::default();
+
+        for name in ["alice", "bob", "carol"] {
+            engine
+                .apply(Action::Join {
+                    name: name.to_string(),
+                })
+                .unwrap();
+        }
+        engine.apply(Action::TakeSeat { pos: 1, to: 3 }).unwrap();
+
+        for pos in [0, 2, 3] {
+            engine.apply(Action::Ready { pos }).unwrap();
+        }
+        engine.apply(Action::Start).unwrap();
+        for pos in [0, 2, 3] {
+            engine.apply(Action::RequestDeal { pos }).unwrap();
+        }
+
+        assert_eq!(engine.phase(), Phase::Playing);
+        assert_eq!(engine.active_player(), Some(0));
+
+        give(&mut engine, 0, "3S,4S");
+        give(&mut engine, 2, "5S,6S");
+        give(&mut engine, 3, "7S,8S");
+
+        for (pos, card, next_player) in [(0, "3S", 2), (2, "5S", 3), (3, "7S", 0)] {
+            let events = engine
+                .apply(Action::Play {
+                    pos,
+                    cards: Hand::from_str(card),
+                })
+                .unwrap();
+
+            assert_eq!(events.last(), Some(&Event::TurnChanged(next_player)));
+        }
+    }
+
+    #[test]
+    fn test_deal_once_everyone_requested() {
+        let mut engine = GameEngine::default();
+
+        for name in ["alice", "bob"] {
+            engine
+                .apply(Action::Join {
+                    name: name.to_string(),
+                })
+                .unwrap();
+        }
+        engine.apply(Action::Ready { pos: 0 }).unwrap();
+        engine.apply(Action::Ready { pos: 1 }).unwrap();
+        engine.apply(Action::Start).unwrap();
+
+        assert_eq!(engine.apply(Action::RequestDeal { pos: 0 }), Ok(vec![]));
+        // Asking again does not stand in for the other players
+        assert_eq!(engine.apply(Action::RequestDeal { pos: 0 }), Ok(vec![]));
+        assert_eq!(engine.phase(), Phase::Dealing);
+
+        let events = engine.apply(Action::RequestDeal { pos: 1 }).unwrap();
+
+        let [Event::MatchStarted {
+            hands,
+            active_player: 0,
+        }] = events.as_slice()
+        else {
+            panic!("expected the match to start");
+        };
+
+        // One hand per chair, the empty ones get nothing
+        assert_eq!(hands.len(), 4);
+        assert!(hands[..2].iter().all(|hand| hand.len() == CARDS_PER_PLAYER));
+        assert!(hands[2..].iter().all(|hand| hand.is_empty()));
+        assert_eq!(engine.phase(), Phase::Playing);
+        assert_eq!(engine.active_player(), Some(0));
+    }
+
+    #[test]
+    fn test_leader_plays_anything_but_can_not_skip() {
+        let mut engine = playing_engine();
+        give(&mut engine, 0, "3S,4S,5S");
+
+        assert_eq!(
+            engine.apply(Action::Skip { pos: 0 }).err(),
+            Some(GameError::CanNotSkipTurn)
+        );
+
+        let not_a_combination = engine.apply(Action::Play {
+            pos: 0,
+            cards: Hand::from_str("3S,5S"),
+        });
+        assert_eq!(
+            not_a_combination.err(),
+            Some(GameError::WrongCombination { expected: None })
+        );
+
+        let events = engine
+            .apply(Action::Play {
+                pos: 0,
+                cards: Hand::from_str("3S,4S,5S"),
+            })
+            .unwrap();
+
+        assert_eq!(
+            events[1..],
+            [
+                Event::PlayerFinished {
+                    pos: 0,
+                    points: 1,
+                    score: 1
+                },
+                Event::MatchEnded {
+                    standings: vec![
+                        Standing {
+                            pos: 0,
+                            points: 1,
+                            score: 1,
+                            hand_left: Hand::default(),
+                        },
+                        Standing {
+                            pos: 1,
+                            points: 0,
+                            score: 0,
+                            hand_left: engine.seat(1).unwrap().hand.clone(),
+                        },
+                    ]
+                }
+            ]
+        );
+        assert_eq!(engine.phase(), Phase::Dealing);
+    }
+
+    #[test]
+    fn test_follow_must_match_and_beat() {
+        let mut engine = playing_engine();
+        give(&mut engine, 0, "9S,KH,AH");
+        give(&mut engine, 1, "4D,5D,JC,JD");
+
+        assert_eq!(
+            engine
+                .apply(Action::Play {
+                    pos: 1,
+                    cards: Hand::from_str("4D"),
+                })
+                .err(),
+            Some(GameError::WrongTurn { active_player: 0 })
+        );
+
+        engine
+            .apply(Action::Play {
+                pos: 0,
+                cards: Hand::from_str("9S"),
+            })
+            .unwrap();
+
+        let weaker = engine.apply(Action::Play {
+            pos: 1,
+            cards: Hand::from_str("4D"),
+        });
+        assert_eq!(
+            weaker.err(),
+            Some(GameError::InvalidCards {
+                to_beat: Hand::from_str("9S")
+            })
+        );
+
+        let not_held = engine.apply(Action::Play {
+            pos: 1,
+            cards: Hand::from_str("2S,JC"),
+        });
+        assert_eq!(
+            not_held.err(),
+            Some(GameError::MissingCards {
+                cards: Hand::from_str("2S")
+            })
+        );
+
+        let single = Some((ThirteenCombination::Single, 1));
+
+        let pair = engine.apply(Action::Play {
+            pos: 1,
+            cards: Hand::from_str("JC,JD"),
+        });
+        assert_eq!(
+            pair.err(),
+            Some(GameError::WrongCombination { expected: single })
+        );
+
+        let not_a_combination = engine.apply(Action::Play {
+            pos: 1,
+            cards: Hand::from_str("4D,JC"),
+        });
+        assert_eq!(
+            not_a_combination.err(),
+            Some(GameError::WrongCombination { expected: single })
+        );
+
+        let events = engine
+            .apply(Action::Play {
+                pos: 1,
+                cards: Hand::from_str("JC"),
+            })
+            .unwrap();
+
+        assert_eq!(
+            events,
+            vec![
+                Event::CardsPlayed {
+                    pos: 1,
+                    cards: Hand::from_str("JC"),
+                    hand_left: Hand::from_str("4D,5D,JD"),
+                    next_player: 0,
+                    run_out: false,
+                },
+                Event::TurnChanged(0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_skip_gives_the_lead_back() {
+        let mut engine = playing_engine();
+        give(&mut engine, 0, "9S,KH,AH");
+
+        engine
+            .apply(Action::Play {
+                pos: 0,
+                cards: Hand::from_str("9S"),
+            })
+            .unwrap();
+
+        assert_eq!(
+            engine.apply(Action::Skip { pos: 1 }),
+            Ok(vec![Event::TurnSkipped {
+                pos: 1,
+                next_player: 0
+            }])
+        );
+        assert!(engine.leader_turn());
+        assert_eq!(engine.active_player(), Some(0));
+    }
+
+    #[test]
+    fn test_lead_passes_on_after_running_out_unbeaten() {
+        let mut engine = three_player_engine();
+
+        give(&mut engine, 0, "3S,AS");
+        give(&mut engine, 1, "5S,6H");
+        give(&mut engine, 2, "4S,7H");
+
+        for (pos, card) in [(0, "3S"), (1, "5S")] {
+            engine
+                .apply(Action::Play {
+                    pos,
+                    cards: Hand::from_str(card),
+                })
+                .unwrap();
+        }
+        engine.apply(Action::Skip { pos: 2 }).unwrap();
+        engine
+            .apply(Action::Play {
+                pos: 0,
+                cards: Hand::from_str("AS"),
+            })
+            .unwrap();
+
+        // Nobody is left to beat the ace, bob is next after alice
+        assert_eq!(
+            engine.apply(Action::Skip { pos: 1 }),
+            Ok(vec![Event::TurnSkipped {
+                pos: 1,
+                next_player: 1
+            }])
+        );
+        assert!(engine.leader_turn());
+        assert_eq!(engine.active_player(), Some(1));
+    }
+
+    /// Alice plays 3S, bob 5S, carol passes and alice runs out with 9S:
+    /// only bob is left in the round
+    fn bob_alone_in_round(bob: &str) -> GameEngine {
+        let mut engine = three_player_engine();
+
+        give(&mut engine, 0, "3S,9S");
+        give(&mut engine, 1, bob);
+        give(&mut engine, 2, "4S,7H,8H");
+
+        for (pos, card) in [(0, "3S"), (1, "5S")] {
+            engine
+                .apply(Action::Play {
+                    pos,
+                    cards: Hand::from_str(card),
+                })
+                .unwrap();
+        }
+        engine.apply(Action::Skip { pos: 2 }).unwrap();
+        engine
+            .apply(Action::Play {
+                pos: 0,
+                cards: Hand::from_str("9S"),
+            })
+            .unwrap();
+
+        engine
+    }
+
+    #[test]
+    fn test_beating_the_last_one_in_round_leads() {
+        let mut engine = bob_alone_in_round("5S,AH,KD,QD");
+
+        let events = engine
+            .apply(Action::Play {
+                pos: 1,
+                cards: Hand::from_str("AH"),
+            })
+            .unwrap();
+
+        assert_eq!(events[1], Event::TurnChanged(1));
+        assert!(engine.leader_turn());
+        assert_eq!(engine.active_player(), Some(1));
+
+        // Carol is back in the next round
+        engine
+            .apply(Action::Play {
+                pos: 1,
+                cards: Hand::from_str("KD"),
+            })
+            .unwrap();
+        assert_eq!(engine.active_player(), Some(2));
+    }
+
+    #[test]
+    fn test_running_out_last_in_round_ends_match() {
+        let mut engine = bob_alone_in_round("5S,AH");
+
+        let events = engine
+            .apply(Action::Play {
+                pos: 1,
+                cards: Hand::from_str("AH"),
+            })
+            .unwrap();
+
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, Event::MatchEnded { .. })));
+    }
+
+    #[test]
+    fn test_leaving_while_dealing_deals_to_the_others() {
+        let mut engine = GameEngine::default();
+
+        for name in ["alice", "bob", "carol"] {
+            engine
+                .apply(Action::Join {
+                    name: name.to_string(),
+                })
+                .unwrap();
+        }
+        for pos in 0..3 {
+            engine.apply(Action::Ready { pos }).unwrap();
+        }
+        engine.apply(Action::Start).unwrap();
+        for pos in 0..2 {
+            engine.apply(Action::RequestDeal { pos }).unwrap();
+        }
+
+        let events = engine.apply(Action::Leave { pos: 2 }).unwrap();
+
+        assert_eq!(events[0], Event::PlayerLeft { pos: 2 });
+        assert!(matches!(events[1], Event::MatchStarted { .. }));
+        assert_eq!(engine.phase(), Phase::Playing);
+        assert!(engine.seat(2).is_none());
+    }
+
+    #[test]
+    fn test_seat_left_mid_match_is_freed_after_it() {
+        let mut engine = three_player_engine();
+
+        assert_eq!(engine.apply(Action::Leave { pos: 2 }), Ok(vec![]));
+        assert!(engine.seat(2).is_some());
+
+        let events = engine.apply(Action::EndMatch).unwrap();
+        assert_eq!(events.last(), Some(&Event::PlayerLeft { pos: 2 }));
+        assert!(engine.seat(2).is_none());
+
+        // The next deal does not wait for the seat left behind
+        engine.apply(Action::RequestDeal { pos: 0 }).unwrap();
+        let events = engine.apply(Action::RequestDeal { pos: 1 }).unwrap();
+        assert!(matches!(events[0], Event::MatchStarted { .. }));
+
+        give(&mut engine, 0, "3S,4S");
+        give(&mut engine, 1, "5S,6S");
+        engine
+            .apply(Action::Play {
+                pos: 0,
+                cards: Hand::from_str("3S"),
+            })
+            .unwrap();
+        assert_eq!(engine.active_player(), Some(1));
+    }
+
+    #[test]
+    fn test_back_to_lobby_when_too_few_are_left() {
+        let mut engine = playing_engine();
+
+        engine.apply(Action::Leave { pos: 1 }).unwrap();
+        let events = engine.apply(Action::EndMatch).unwrap();
+
+        assert_eq!(
+            events[1..],
+            [Event::PlayerLeft { pos: 1 }, Event::BackToLobby]
+        );
+        assert_eq!(engine.phase(), Phase::Lobby);
+        assert!(engine
+            .apply(Action::Join {
+                name: "carol".to_string(),
+            })
+            .is_ok());
+    }
+
+    #[test]
+    fn test_timeout_plays_smallest_card_for_the_leader() {
+        let mut engine = playing_engine();
+        give(&mut engine, 0, "KH,4C,AH");
+
+        let events = engine.apply(Action::Timeout).unwrap();
+
+        let Event::CardsPlayed { cards, .. } = &events[0] else {
+            panic!("expected a play");
+        };
+
+        assert_eq!(*cards, Hand::from_str("4C"));
+
+        // Not the leader anymore, so the next timeout passes
+        let events = engine.apply(Action::Timeout).unwrap();
+        assert!(matches!(events[0], Event::TurnSkipped { pos: 1, .. }));
+    }
+
+    #[test]
+    fn test_round_keeps_plays_and_passes_until_the_next_lead() {
+        let mut engine = playing_engine();
+        give(&mut engine, 0, "9S,KH,AH");
+        give(&mut engine, 1, "4D,5D,JD");
+
+        engine
+            .apply(Action::Play {
+                pos: 0,
+                cards: Hand::from_str("9S"),
+            })
+            .unwrap();
+        engine
+            .apply(Action::Play {
+                pos: 1,
+                cards: Hand::from_str("JD"),
+            })
+            .unwrap();
+        engine.apply(Action::Skip { pos: 0 }).unwrap();
+
+        assert_eq!(
+            engine.round(),
+            [
+                Trick {
+                    pos: 0,
+                    cards: Some(Hand::from_str("9S"))
+                },
+                Trick {
+                    pos: 1,
+                    cards: Some(Hand::from_str("JD"))
+                },
+                Trick {
+                    pos: 0,
+                    cards: None
+                },
+            ]
+        );
+
+        // Bob leads again, the old round is gone
+        engine
+            .apply(Action::Play {
+                pos: 1,
+                cards: Hand::from_str("4D"),
+            })
+            .unwrap();
+
+        assert_eq!(
+            engine.round(),
+            [Trick {
+                pos: 1,
+                cards: Some(Hand::from_str("4D"))
+            }]
+        );
+    }
+
+    #[test]
+    fn test_end_match_ranks_the_rest_by_cards_left() {
+        let mut engine = playing_engine();
+        give(&mut engine, 0, "3S,4S,5S");
+        give(&mut engine, 1, "9S");
+
+        let events = engine.apply(Action::EndMatch).unwrap();
+
+        let [Event::MatchEnded { standings }] = events.as_slice() else {
+            panic!("expected the match to end");
+        };
+
+        assert_eq!(
+            standings
+                .iter()
+                .map(|standing| (standing.pos, standing.points, standing.hand_left.len()))
+                .collect::<Vec<_>>(),
+            vec![(1, 0, 1), (0, 0, 3)]
+        );
+    }
+}
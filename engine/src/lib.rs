@@ -0,0 +1,10 @@
+//! Tien Len rules without any app around them.
+//!
+//! The [`GameEngine`] owns the seats, the turn pool and the cards on the table.
+//! Callers feed it [`Action`]s and get back the [`Event`]s that happened, the
+//! server turns those into naia messages and the tests just look at them.
+
+mod engine;
+
+pub use engine::{check_play, Action, Event, GameEngine, Rules, Seat, Standing};
+pub use naia_bevy_demo_shared::{components::table::Phase, messages::GameError};
@@ -0,0 +1,387 @@
+use std::{
+    io::Read,
+    net::SocketAddr,
+    sync::{
+        mpsc::{channel, Receiver, Sender},
+        Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use bevy_app::{App, AppExit, Plugin, Update};
+use bevy_ecs::{
+    event::{Event, EventWriter},
+    system::{Commands, Query, Res, ResMut, Resource},
+};
+use bevy_log::{info, warn};
+use bevy_time::{Time, Timer, TimerMode};
+use naia_bevy_demo_shared::{
+    channels::GameSystemChannel,
+    components::{hand::Hand, Host, Player, Table},
+    messages::SystemMessage,
+};
+use naia_bevy_server::Server;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Request, Response};
+
+use tienlen_engine::Phase;
+
+use crate::resources::Global;
+
+/// How long the HTTP thread waits for the game loop to answer
+const REPLY_TIMEOUT: Duration = Duration::from_secs(2);
+/// Time between announcing a shutdown and disconnecting everyone
+const SHUTDOWN_DELAY: Duration = Duration::from_secs(3);
+/// Time left for the disconnect packets to go out before the app exits
+const DISCONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Optional HTTP API on localhost for operating the server.
+///
+/// `GET /tables`, `GET /users`, `POST /kick`, `POST /end-match`, `POST /broadcast`
+/// and `POST /shutdown`. Requests are handed to the game loop, so every answer
+/// reflects the same `Global` state the game systems use.
+pub struct AdminPlugin {
+    address: Option<String>,
+}
+
+impl AdminPlugin {
+    pub fn new(address: Option<String>) -> Self {
+        Self { address }
+    }
+}
+
+impl Plugin for AdminPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ForceEndMatch>();
+
+        let Some(address) = &self.address else {
+            info!("Admin API: disabled");
+            return;
+        };
+
+        let Some(receiver) = spawn_listener(address) else {
+            return;
+        };
+
+        app.insert_resource(AdminChannel(Mutex::new(receiver)))
+            .add_systems(Update, (handle_admin_requests, shutdown_countdown));
+    }
+}
+
+/// Ends the current match as if only one player had cards left
+#[derive(Event, Default)]
+pub struct ForceEndMatch;
+
+#[derive(Resource)]
+struct AdminChannel(Mutex<Receiver<AdminRequest>>);
+
+#[derive(Resource)]
+struct ShutdownTimer {
+    timer: Timer,
+    users_disconnected: bool,
+}
+
+enum AdminCommand {
+    Tables,
+    Users,
+    Kick(String),
+    EndMatch,
+    Broadcast(String),
+    Shutdown,
+}
+
+struct AdminRequest {
+    command: AdminCommand,
+    reply: Sender<AdminReply>,
+}
+
+struct AdminReply {
+    status: u16,
+    body: Value,
+}
+
+impl AdminReply {
+    fn ok(body: Value) -> Self {
+        Self { status: 200, body }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            body: json!({ "error": message }),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct KickBody {
+    address: String,
+}
+
+#[derive(Deserialize)]
+struct BroadcastBody {
+    message: String,
+}
+
+#[derive(Serialize)]
+struct TableInfo {
+    id: usize,
+    phase: Phase,
+    cards: String,
+    players: Vec<PlayerInfo>,
+}
+
+#[derive(Serialize)]
+struct PlayerInfo {
+    pos: usize,
+    name: String,
+    score: u32,
+    cards_left: usize,
+    active: bool,
+    ready: bool,
+    host: bool,
+}
+
+impl PlayerInfo {
+    fn new(player: &Player, host: Option<&Host>) -> Self {
+        Self {
+            pos: *player.pos,
+            name: player.name(),
+            score: *player.score,
+            cards_left: Hand::from(player.cards()).len(),
+            active: *player.active,
+            ready: *player.ready,
+            host: host.is_some(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct UserInfo {
+    address: String,
+    player: Option<PlayerInfo>,
+}
+
+fn spawn_listener(address: &str) -> Option<Receiver<AdminRequest>> {
+    let address: SocketAddr = match address.parse() {
+        Ok(address) => address,
+        Err(err) => {
+            warn!("Admin API: invalid address `{}`: {}", address, err);
+            return None;
+        }
+    };
+
+    if !address.ip().is_loopback() {
+        warn!(
+            "Admin API: {} is not a localhost address -> Disabled",
+            address
+        );
+        return None;
+    }
+
+    let http = match tiny_http::Server::http(address) {
+        Ok(http) => http,
+        Err(err) => {
+            warn!("Admin API: can not listen on {}: {}", address, err);
+            return None;
+        }
+    };
+
+    let (sender, receiver) = channel();
+
+    thread::spawn(move || {
+        for request in http.incoming_requests() {
+            serve(request, &sender);
+        }
+    });
+
+    info!("Admin API: listening on http://{}", address);
+
+    Some(receiver)
+}
+
+fn serve(mut request: Request, sender: &Sender<AdminRequest>) {
+    let mut body = String::new();
+    let _ = request.as_reader().read_to_string(&mut body);
+
+    let command = match (request.method(), request.url()) {
+        (Method::Get, "/tables") => Ok(AdminCommand::Tables),
+        (Method::Get, "/users") => Ok(AdminCommand::Users),
+        (Method::Post, "/kick") => {
+            serde_json::from_str::<KickBody>(&body).map(|kick| AdminCommand::Kick(kick.address))
+        }
+        (Method::Post, "/end-match") => Ok(AdminCommand::EndMatch),
+        (Method::Post, "/broadcast") => serde_json::from_str::<BroadcastBody>(&body)
+            .map(|broadcast| AdminCommand::Broadcast(broadcast.message)),
+        (Method::Post, "/shutdown") => Ok(AdminCommand::Shutdown),
+        _ => return respond(request, AdminReply::error(404, "not found")),
+    };
+
+    let command = match command {
+        Ok(command) => command,
+        Err(err) => return respond(request, AdminReply::error(400, &err.to_string())),
+    };
+
+    let (reply, response) = channel();
+
+    if sender.send(AdminRequest { command, reply }).is_err() {
+        return respond(request, AdminReply::error(503, "game loop is gone"));
+    }
+
+    match response.recv_timeout(REPLY_TIMEOUT) {
+        Ok(reply) => respond(request, reply),
+        Err(_) => respond(request, AdminReply::error(503, "game loop did not answer")),
+    }
+}
+
+fn respond(request: Request, reply: AdminReply) {
+    let content_type = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap();
+
+    let response = Response::from_string(reply.body.to_string())
+        .with_status_code(reply.status)
+        .with_header(content_type);
+
+    if let Err(err) = request.respond(response) {
+        warn!("Admin API: failed to respond: {}", err);
+    }
+}
+
+fn broadcast(server: &mut Server, global: &Global, text: &str) {
+    let message = SystemMessage(text.to_string());
+
+    for user_key in global.users_map.keys() {
+        server.send_message::<GameSystemChannel, SystemMessage>(user_key, &message);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn handle_admin_requests(
+    mut commands: Commands,
+    mut server: Server,
+    global: Res<Global>,
+    admin_channel: Res<AdminChannel>,
+    player_q: Query<(&Player, Option<&Host>)>,
+    table_q: Query<&Table>,
+    mut force_end_ev: EventWriter<ForceEndMatch>,
+) {
+    let receiver = admin_channel.0.lock().unwrap();
+
+    for AdminRequest { command, reply } in receiver.try_iter() {
+        let answer = match command {
+            AdminCommand::Tables => {
+                let mut players: Vec<PlayerInfo> = global
+                    .users_map
+                    .values()
+                    .filter_map(|entity| player_q.get(*entity).ok())
+                    .map(|(player, host)| PlayerInfo::new(player, host))
+                    .collect();
+
+                players.sort_by_key(|p| p.pos);
+
+                let table = TableInfo {
+                    // There is only the main room for now
+                    id: 0,
+                    phase: global.engine.phase(),
+                    cards: table_q
+                        .get_single()
+                        .map(|table| table.cards.to_string())
+                        .unwrap_or_default(),
+                    players,
+                };
+
+                AdminReply::ok(json!([table]))
+            }
+            AdminCommand::Users => {
+                let users: Vec<UserInfo> = server
+                    .user_keys()
+                    .into_iter()
+                    .map(|user_key| UserInfo {
+                        address: server.user(&user_key).address().to_string(),
+                        player: global
+                            .users_map
+                            .get(&user_key)
+                            .and_then(|entity| player_q.get(*entity).ok())
+                            .map(|(player, host)| PlayerInfo::new(player, host)),
+                    })
+                    .collect();
+
+                AdminReply::ok(json!(users))
+            }
+            AdminCommand::Kick(address) => {
+                let user_key = server
+                    .user_keys()
+                    .into_iter()
+                    .find(|user_key| server.user(user_key).address().to_string() == address);
+
+                match user_key {
+                    Some(user_key) => {
+                        info!("Admin: kick {}", address);
+                        server.user_mut(&user_key).disconnect();
+                        AdminReply::ok(json!({ "kicked": address }))
+                    }
+                    None => AdminReply::error(404, "no user connected from this address"),
+                }
+            }
+            AdminCommand::EndMatch => {
+                if global.engine.phase() == Phase::Playing {
+                    info!("Admin: end match");
+                    force_end_ev.send_default();
+                    AdminReply::ok(json!({ "ended": true }))
+                } else {
+                    AdminReply::error(409, "no match in progress")
+                }
+            }
+            AdminCommand::Broadcast(text) => {
+                info!("Admin: broadcast `{}`", text);
+                broadcast(&mut server, &global, &text);
+                AdminReply::ok(json!({ "sent_to": global.users_map.len() }))
+            }
+            AdminCommand::Shutdown => {
+                info!("Admin: shutdown requested");
+                broadcast(&mut server, &global, "Server is shutting down");
+
+                commands.insert_resource(ShutdownTimer {
+                    timer: Timer::new(SHUTDOWN_DELAY, TimerMode::Once),
+                    users_disconnected: false,
+                });
+
+                AdminReply {
+                    status: 202,
+                    body: json!({ "shutdown_in_secs": SHUTDOWN_DELAY.as_secs() }),
+                }
+            }
+        };
+
+        let _ = reply.send(answer);
+    }
+}
+
+fn shutdown_countdown(
+    time: Res<Time>,
+    mut server: Server,
+    shutdown: Option<ResMut<ShutdownTimer>>,
+    mut exit_ev: EventWriter<AppExit>,
+) {
+    let Some(mut shutdown) = shutdown else {
+        return;
+    };
+
+    if !shutdown.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    if shutdown.users_disconnected {
+        info!("Admin: bye");
+        exit_ev.send(AppExit);
+        return;
+    }
+
+    for user_key in server.user_keys() {
+        server.user_mut(&user_key).disconnect();
+    }
+
+    shutdown.users_disconnected = true;
+    shutdown.timer = Timer::new(DISCONNECT_DELAY, TimerMode::Once);
+}
@@ -0,0 +1,98 @@
+use bevy_app::{App, Startup, Update};
+use bevy_core::{FrameCountPlugin, TaskPoolPlugin, TypeRegistrationPlugin};
+use bevy_ecs::schedule::IntoSystemConfigs;
+use bevy_time::TimePlugin;
+use naia_shared::ConnectionConfig;
+use std::time::Duration;
+
+use naia_bevy_demo_shared::protocol_with_tick_interval;
+use naia_bevy_server::{Plugin as ServerPlugin, ReceiveEvents, ServerConfig};
+
+mod admin;
+mod metrics;
+mod rate_limit;
+pub mod resources;
+pub mod settings;
+mod systems;
+mod transport;
+
+pub use admin::ForceEndMatch;
+
+use admin::AdminPlugin;
+use metrics::MetricsPlugin;
+use rate_limit::RateLimiter;
+use settings::Settings;
+use systems::{common, events, game, init};
+
+/// Builds the whole game server without logging and without a runner, so the
+/// binary and the integration tests drive the same app
+pub fn build_app(settings: Settings) -> App {
+    let connection_config = ConnectionConfig {
+        disconnection_timeout_duration: Duration::from_secs(
+            settings.network.disconnect_timeout_secs,
+        ),
+        ..Default::default()
+    };
+
+    let server_config = ServerConfig {
+        // Always on, the auth message carries the protocol version. The
+        // credentials are only checked when the settings ask for it
+        require_auth: true,
+        connection: connection_config,
+        ..Default::default()
+    };
+
+    let mut app = App::default();
+
+    app
+        // Plugins
+        .add_plugins((
+            TaskPoolPlugin::default(),
+            TimePlugin::default(),
+            TypeRegistrationPlugin::default(),
+            FrameCountPlugin::default(),
+            ServerPlugin::new(
+                server_config,
+                protocol_with_tick_interval(settings.tick_interval()),
+            ),
+        ))
+        .add_plugins(AdminPlugin::new(settings.network.admin_address.clone()))
+        .add_plugins(MetricsPlugin::new(settings.network.metrics_address.clone()))
+        .insert_resource(RateLimiter::new(settings.rate_limit.clone()))
+        .insert_resource(settings)
+        .add_event::<game::EngineEvent>()
+        // Startup System
+        .add_systems(Startup, (init, common::set_up_counter))
+        // Test
+        .add_systems(
+            Update,
+            (
+                common::countdown,
+                common::run_out_countdown,
+                events::end_match,
+            ),
+        )
+        // Receive Server Events
+        .add_systems(
+            Update,
+            (
+                events::auth_events,
+                events::connect_events,
+                events::disconnect_events,
+                events::error_events,
+                events::accept_start_game,
+                events::message_events,
+                events::tick_events,
+                events::spawn_entity_events,
+                events::despawn_entity_events,
+                events::insert_component_events,
+                events::update_component_events,
+                events::remove_component_events,
+                game::engine_events,
+            )
+                .chain()
+                .in_set(ReceiveEvents),
+        );
+
+    app
+}
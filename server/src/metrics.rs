@@ -0,0 +1,274 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::Instant,
+};
+
+use bevy_app::{App, First, Last, Plugin, Update};
+use bevy_ecs::system::{Query, Res, ResMut, Resource};
+use bevy_log::{info, warn};
+use naia_bevy_demo_shared::{components::Table, messages::GameError};
+use naia_bevy_server::Server;
+use tiny_http::{Header, Response};
+
+/// Counters and gauges about load and health, served in Prometheus text format.
+///
+/// The `Metrics` resource is always available so systems can record into it,
+/// the HTTP endpoint is only started when an address is configured.
+pub struct MetricsPlugin {
+    address: Option<String>,
+}
+
+impl MetricsPlugin {
+    pub fn new(address: Option<String>) -> Self {
+        Self { address }
+    }
+}
+
+impl Plugin for MetricsPlugin {
+    fn build(&self, app: &mut App) {
+        let metrics = Metrics::default();
+
+        app.insert_resource(metrics.clone())
+            .init_resource::<TickStart>()
+            .add_systems(First, start_tick)
+            .add_systems(Update, update_gauges)
+            .add_systems(Last, end_tick);
+
+        let Some(address) = &self.address else {
+            info!("Metrics: disabled");
+            return;
+        };
+
+        spawn_listener(address, metrics);
+    }
+}
+
+#[derive(Default)]
+struct Counters {
+    connected_users: AtomicU64,
+    active_tables: AtomicU64,
+    matches_started: AtomicU64,
+    matches_finished: AtomicU64,
+    plays_accepted: AtomicU64,
+    plays_rejected: Mutex<BTreeMap<&'static str, u64>>,
+    timeouts: AtomicU64,
+    disconnects_mid_match: AtomicU64,
+    rate_limited: AtomicU64,
+    rate_limit_kicks: AtomicU64,
+    tick_micros_sum: AtomicU64,
+    tick_count: AtomicU64,
+}
+
+/// Cheap to clone handle, the HTTP thread reads the same counters
+#[derive(Resource, Clone, Default)]
+pub struct Metrics(Arc<Counters>);
+
+impl Metrics {
+    pub fn match_started(&self) {
+        self.0.matches_started.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn match_finished(&self) {
+        self.0.matches_finished.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn play_accepted(&self) {
+        self.0.plays_accepted.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn play_rejected(&self, error: &GameError) {
+        *self
+            .0
+            .plays_rejected
+            .lock()
+            .unwrap()
+            .entry(error.kind())
+            .or_default() += 1;
+    }
+
+    pub fn timeout(&self) {
+        self.0.timeouts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn disconnect_mid_match(&self) {
+        self.0.disconnects_mid_match.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn rate_limited(&self) {
+        self.0.rate_limited.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn rate_limit_kick(&self) {
+        self.0.rate_limit_kicks.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn render(&self) -> String {
+        let counters = &self.0;
+        let mut out = String::new();
+
+        let mut single = |name: &str, kind: &str, help: &str, value: u64| {
+            let _ = writeln!(out, "# HELP tienlen_{} {}", name, help);
+            let _ = writeln!(out, "# TYPE tienlen_{} {}", name, kind);
+            let _ = writeln!(out, "tienlen_{} {}", name, value);
+        };
+
+        single(
+            "connected_users",
+            "gauge",
+            "Users currently connected",
+            counters.connected_users.load(Ordering::Relaxed),
+        );
+        single(
+            "active_tables",
+            "gauge",
+            "Tables with a game set up",
+            counters.active_tables.load(Ordering::Relaxed),
+        );
+        single(
+            "matches_started_total",
+            "counter",
+            "Matches dealt",
+            counters.matches_started.load(Ordering::Relaxed),
+        );
+        single(
+            "matches_finished_total",
+            "counter",
+            "Matches ended",
+            counters.matches_finished.load(Ordering::Relaxed),
+        );
+        single(
+            "plays_accepted_total",
+            "counter",
+            "Plays that passed validation",
+            counters.plays_accepted.load(Ordering::Relaxed),
+        );
+        single(
+            "turn_timeouts_total",
+            "counter",
+            "Turns that ran out of time",
+            counters.timeouts.load(Ordering::Relaxed),
+        );
+        single(
+            "disconnects_mid_match_total",
+            "counter",
+            "Players who left while a match was running",
+            counters.disconnects_mid_match.load(Ordering::Relaxed),
+        );
+        single(
+            "rate_limited_total",
+            "counter",
+            "Player actions dropped by the rate limiter",
+            counters.rate_limited.load(Ordering::Relaxed),
+        );
+        single(
+            "rate_limit_kicks_total",
+            "counter",
+            "Users kicked for flooding",
+            counters.rate_limit_kicks.load(Ordering::Relaxed),
+        );
+
+        let _ = writeln!(
+            out,
+            "# HELP tienlen_plays_rejected_total Actions rejected, by game error"
+        );
+        let _ = writeln!(out, "# TYPE tienlen_plays_rejected_total counter");
+        for (kind, value) in counters.plays_rejected.lock().unwrap().iter() {
+            let _ = writeln!(
+                out,
+                "tienlen_plays_rejected_total{{kind=\"{}\"}} {}",
+                kind, value
+            );
+        }
+
+        let tick_secs_sum = counters.tick_micros_sum.load(Ordering::Relaxed) as f64 / 1_000_000.;
+        let _ = writeln!(
+            out,
+            "# HELP tienlen_tick_processing_seconds Time spent running the game systems per tick"
+        );
+        let _ = writeln!(out, "# TYPE tienlen_tick_processing_seconds summary");
+        let _ = writeln!(out, "tienlen_tick_processing_seconds_sum {}", tick_secs_sum);
+        let _ = writeln!(
+            out,
+            "tienlen_tick_processing_seconds_count {}",
+            counters.tick_count.load(Ordering::Relaxed)
+        );
+
+        out
+    }
+}
+
+#[derive(Resource, Default)]
+struct TickStart(Option<Instant>);
+
+fn start_tick(mut tick_start: ResMut<TickStart>) {
+    tick_start.0 = Some(Instant::now());
+}
+
+fn end_tick(tick_start: Res<TickStart>, metrics: Res<Metrics>) {
+    let Some(start) = tick_start.0 else {
+        return;
+    };
+
+    let elapsed = start.elapsed().as_micros() as u64;
+
+    metrics
+        .0
+        .tick_micros_sum
+        .fetch_add(elapsed, Ordering::Relaxed);
+    metrics.0.tick_count.fetch_add(1, Ordering::Relaxed);
+}
+
+fn update_gauges(server: Server, metrics: Res<Metrics>, table_q: Query<&Table>) {
+    metrics
+        .0
+        .connected_users
+        .store(server.users_count() as u64, Ordering::Relaxed);
+    metrics
+        .0
+        .active_tables
+        .store(table_q.iter().count() as u64, Ordering::Relaxed);
+}
+
+fn spawn_listener(address: &str, metrics: Metrics) {
+    let address: SocketAddr = match address.parse() {
+        Ok(address) => address,
+        Err(err) => {
+            warn!("Metrics: invalid address `{}`: {}", address, err);
+            return;
+        }
+    };
+
+    let http = match tiny_http::Server::http(address) {
+        Ok(http) => http,
+        Err(err) => {
+            warn!("Metrics: can not listen on {}: {}", address, err);
+            return;
+        }
+    };
+
+    thread::spawn(move || {
+        let content_type =
+            Header::from_bytes(&b"Content-Type"[..], &b"text/plain; version=0.0.4"[..]).unwrap();
+
+        for request in http.incoming_requests() {
+            let response = if request.url() == "/metrics" {
+                Response::from_string(metrics.render()).with_header(content_type.clone())
+            } else {
+                Response::from_string("not found").with_status_code(404)
+            };
+
+            if let Err(err) = request.respond(response) {
+                warn!("Metrics: failed to respond: {}", err);
+            }
+        }
+    });
+
+    info!("Metrics: listening on http://{}/metrics", address);
+}
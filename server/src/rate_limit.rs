@@ -0,0 +1,277 @@
+use std::{collections::HashMap, hash::Hash, time::Duration};
+
+use bevy_ecs::system::Resource;
+use bevy_log::warn;
+use naia_bevy_demo_shared::{channels::GameSystemChannel, messages::SystemMessage};
+use naia_bevy_server::{Server, UserKey};
+
+use crate::{
+    metrics::Metrics,
+    settings::{Budget, RateLimitSettings},
+};
+
+/// Player actions with their own budget
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LimitedAction {
+    Join,
+    Ready,
+    Start,
+    RequestStart,
+    PlayCard,
+    SkipTurn,
+    /// Taking a seat, kicking, handing the host role over and table options
+    Seats,
+}
+
+impl LimitedAction {
+    fn budget(self, settings: &RateLimitSettings) -> Budget {
+        match self {
+            Self::Join => settings.join,
+            Self::Ready => settings.ready,
+            Self::Start => settings.start,
+            Self::RequestStart => settings.request_start,
+            Self::PlayCard => settings.play,
+            Self::SkipTurn => settings.skip,
+            Self::Seats => settings.seats,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Allow,
+    /// Over budget, or still muted
+    Drop,
+    /// Over budget one time too many, everything is dropped for a while
+    Mute,
+    Kick,
+}
+
+struct Bucket {
+    tokens: f32,
+    refilled_at: Duration,
+}
+
+#[derive(Default)]
+struct Offender {
+    strikes: u32,
+    struck_at: Duration,
+    mutes: u32,
+    /// Muted up to then, the mutes wear off a while after
+    muted_until: Duration,
+}
+
+/// Keeps the budgets of every connected user, `now` is the app uptime
+#[derive(Resource)]
+pub struct RateLimiter<K = UserKey> {
+    settings: RateLimitSettings,
+    buckets: HashMap<(K, LimitedAction), Bucket>,
+    offenders: HashMap<K, Offender>,
+}
+
+impl<K: Copy + Eq + Hash> RateLimiter<K> {
+    pub fn new(settings: RateLimitSettings) -> Self {
+        Self {
+            settings,
+            buckets: HashMap::new(),
+            offenders: HashMap::new(),
+        }
+    }
+
+    pub fn check(&mut self, user: K, action: LimitedAction, now: Duration) -> Verdict {
+        if !self.settings.enabled {
+            return Verdict::Allow;
+        }
+
+        let offender = self.offenders.entry(user).or_default();
+
+        if now < offender.muted_until {
+            return Verdict::Drop;
+        }
+
+        let budget = action.budget(&self.settings);
+        let bucket = self.buckets.entry((user, action)).or_insert(Bucket {
+            tokens: budget.burst as f32,
+            refilled_at: now,
+        });
+
+        let elapsed = now.saturating_sub(bucket.refilled_at).as_secs_f32();
+        bucket.tokens = (bucket.tokens + elapsed * budget.per_sec).min(budget.burst as f32);
+        bucket.refilled_at = now;
+
+        if bucket.tokens >= 1. {
+            bucket.tokens -= 1.;
+            return Verdict::Allow;
+        }
+
+        // The odd burst in a long session, like Play pressed again on lag,
+        // wears off instead of adding up to a kick
+        if now.saturating_sub(offender.struck_at)
+            >= Duration::from_secs(self.settings.strike_reset_secs)
+        {
+            offender.strikes = 0;
+        }
+        if now.saturating_sub(offender.muted_until)
+            >= Duration::from_secs(self.settings.mute_reset_secs)
+        {
+            offender.mutes = 0;
+        }
+
+        offender.struck_at = now;
+        offender.strikes += 1;
+        if offender.strikes < self.settings.strikes_before_mute {
+            return Verdict::Drop;
+        }
+
+        offender.strikes = 0;
+        offender.mutes += 1;
+        // Also covers a kick, the rest of the messages in flight are dropped
+        offender.muted_until = now + Duration::from_secs(self.settings.mute_secs);
+
+        if offender.mutes > self.settings.mutes_before_kick {
+            Verdict::Kick
+        } else {
+            Verdict::Mute
+        }
+    }
+
+    /// Drops everything about a user that left
+    pub fn forget(&mut self, user: &K) {
+        self.offenders.remove(user);
+        self.buckets.retain(|(key, _), _| key != user);
+    }
+}
+
+impl RateLimiter {
+    /// Checks the budget and deals with offenders, `true` when the message may
+    /// be handled
+    pub fn allow(
+        &mut self,
+        server: &mut Server,
+        metrics: &Metrics,
+        user_key: &UserKey,
+        action: LimitedAction,
+        now: Duration,
+    ) -> bool {
+        let verdict = self.check(*user_key, action, now);
+
+        if verdict != Verdict::Allow {
+            metrics.rate_limited();
+        }
+
+        match verdict {
+            Verdict::Allow => return true,
+            Verdict::Drop => return false,
+            Verdict::Mute => {
+                warn!(
+                    "Rate limit: {:?} floods {:?} -> Mute for {}s",
+                    user_key, action, self.settings.mute_secs
+                );
+
+                let message = SystemMessage(format!(
+                    "Slow down! Your actions are ignored for {} seconds",
+                    self.settings.mute_secs
+                ));
+                server.send_message::<GameSystemChannel, SystemMessage>(user_key, &message);
+            }
+            Verdict::Kick => {
+                warn!("Rate limit: {:?} floods {:?} -> Kick", user_key, action);
+
+                metrics.rate_limit_kick();
+
+                let message = SystemMessage("Kicked for flooding the server".to_string());
+                server.send_message::<GameSystemChannel, SystemMessage>(user_key, &message);
+                server.user_mut(user_key).disconnect();
+            }
+        }
+
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LimitedAction::*, *};
+
+    fn limiter() -> RateLimiter<u32> {
+        RateLimiter::new(RateLimitSettings {
+            strikes_before_mute: 2,
+            mute_secs: 10,
+            mutes_before_kick: 1,
+            strike_reset_secs: 5,
+            mute_reset_secs: 30,
+            play: Budget::new(2, 1.),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_burst_then_refill() {
+        let mut limiter = limiter();
+        let mut check = |user, action, at| limiter.check(user, action, Duration::from_secs(at));
+
+        assert_eq!(check(1, PlayCard, 0), Verdict::Allow);
+        assert_eq!(check(1, PlayCard, 0), Verdict::Allow);
+        assert_eq!(check(1, PlayCard, 0), Verdict::Drop);
+
+        // Budgets are per user and per action
+        assert_eq!(check(2, PlayCard, 0), Verdict::Allow);
+        assert_eq!(check(1, SkipTurn, 0), Verdict::Allow);
+
+        assert_eq!(check(1, PlayCard, 1), Verdict::Allow);
+    }
+
+    #[test]
+    fn test_strikes_mute_then_kick() {
+        let mut limiter = limiter();
+        let mut check = |user, action, at| limiter.check(user, action, Duration::from_secs(at));
+
+        check(1, PlayCard, 0);
+        check(1, PlayCard, 0);
+
+        assert_eq!(check(1, PlayCard, 0), Verdict::Drop);
+        assert_eq!(check(1, PlayCard, 0), Verdict::Mute);
+
+        // Muted for every action, even with budget left
+        assert_eq!(check(1, SkipTurn, 5), Verdict::Drop);
+
+        assert_eq!(check(1, PlayCard, 10), Verdict::Allow);
+        assert_eq!(check(1, PlayCard, 10), Verdict::Allow);
+        assert_eq!(check(1, PlayCard, 10), Verdict::Drop);
+        assert_eq!(check(1, PlayCard, 10), Verdict::Kick);
+    }
+
+    #[test]
+    fn test_strikes_and_mutes_wear_off() {
+        let mut limiter = limiter();
+        let mut check = |user, action, at| limiter.check(user, action, Duration::from_secs(at));
+
+        check(1, PlayCard, 0);
+        check(1, PlayCard, 0);
+        assert_eq!(check(1, PlayCard, 0), Verdict::Drop);
+
+        // Quiet for a while, the strike is forgiven
+        check(1, PlayCard, 6);
+        check(1, PlayCard, 6);
+        assert_eq!(check(1, PlayCard, 6), Verdict::Drop);
+        assert_eq!(check(1, PlayCard, 6), Verdict::Mute);
+
+        // Clean for a while after the mute, it is forgiven too
+        check(1, PlayCard, 50);
+        check(1, PlayCard, 50);
+        assert_eq!(check(1, PlayCard, 50), Verdict::Drop);
+        assert_eq!(check(1, PlayCard, 50), Verdict::Mute);
+    }
+
+    #[test]
+    fn test_disabled_allows_everything() {
+        let mut limiter = RateLimiter::new(RateLimitSettings {
+            enabled: false,
+            ..Default::default()
+        });
+
+        for _ in 0..100 {
+            assert_eq!(limiter.check(1, Start, Duration::ZERO), Verdict::Allow);
+        }
+    }
+}
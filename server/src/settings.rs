@@ -0,0 +1,519 @@
+use std::{
+    fmt::{self, Display},
+    fs,
+    net::SocketAddr,
+    path::PathBuf,
+    time::Duration,
+};
+
+use bevy_ecs::system::Resource;
+use clap::Parser;
+use dotenv::dotenv;
+use serde::{Deserialize, Deserializer};
+use tienlen_engine::Rules;
+
+/// Config file used when `--config` is not given, it is fine if it does not exist
+const DEFAULT_CONFIG_PATH: &str = "tienlen.toml";
+
+/// Command line flags, every flag also reads the env var next to it and wins
+/// over the value from the config file
+#[derive(Parser, Debug, Default)]
+#[command(name = "tienlen-server", version, about = "Tien Len game server")]
+pub struct Cli {
+    /// Path to the TOML config file
+    #[arg(short, long, env = "SERVER_CONFIG")]
+    pub config: Option<PathBuf>,
+    /// Address for the WebRTC signaling HTTP server
+    #[arg(long, env = "SERVER_SIGNALING_ADDRESS")]
+    pub signaling_address: Option<String>,
+    /// Address to listen on for WebRTC data channels
+    #[arg(long, env = "SERVER_WEBRTC_ADDRESS")]
+    pub webrtc_address: Option<String>,
+    /// Public WebRTC URL advertised to the clients
+    #[arg(long, env = "SERVER_ADDRESS")]
+    pub public_address: Option<String>,
+    /// Address for the UDP transport auth HTTP server, empty to disable UDP
+    #[arg(long, env = "SERVER_UDP_AUTH_ADDRESS")]
+    pub udp_auth_address: Option<String>,
+    /// Address to listen on for UDP packets
+    #[arg(long, env = "SERVER_UDP_ADDRESS")]
+    pub udp_address: Option<String>,
+    /// Public UDP URL advertised to the native clients
+    #[arg(long, env = "SERVER_UDP_PUBLIC_ADDRESS")]
+    pub udp_public_address: Option<String>,
+    /// Localhost address for the admin HTTP API, empty to disable it
+    #[arg(long, env = "SERVER_ADMIN_ADDRESS")]
+    pub admin_address: Option<String>,
+    /// Address for the Prometheus metrics endpoint, empty to disable it
+    #[arg(long, env = "SERVER_METRICS_ADDRESS")]
+    pub metrics_address: Option<String>,
+    /// Server tick interval in milliseconds
+    #[arg(long)]
+    pub tick_interval_ms: Option<u64>,
+    /// Seconds a player has to play before the turn is taken for them
+    #[arg(long)]
+    pub turn_timeout_secs: Option<u32>,
+    /// Maximum number of tables running at the same time
+    #[arg(long)]
+    pub max_tables: Option<usize>,
+    /// Require clients to send the right user name and password
+    #[arg(long)]
+    pub require_auth: Option<bool>,
+    #[arg(long, env = "AUTH_USER_NAME")]
+    pub auth_user_name: Option<String>,
+    #[arg(long, env = "AUTH_USER_PASS", hide_env_values = true)]
+    pub auth_user_pass: Option<String>,
+}
+
+#[derive(Resource, Deserialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    pub network: NetworkSettings,
+    pub game: GameSettings,
+    pub rules: RuleSettings,
+    pub rate_limit: RateLimitSettings,
+    pub auth: AuthSettings,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct NetworkSettings {
+    pub signaling_address: String,
+    pub webrtc_address: String,
+    pub public_address: String,
+    /// Auth address of the UDP transport for native clients, `""` in the file
+    /// disables UDP
+    #[serde(deserialize_with = "optional_address")]
+    pub udp_auth_address: Option<String>,
+    pub udp_address: String,
+    pub udp_public_address: String,
+    /// Localhost address for the admin HTTP API, `""` in the file disables it
+    #[serde(deserialize_with = "optional_address")]
+    pub admin_address: Option<String>,
+    /// Address for the Prometheus metrics endpoint, `""` in the file disables it
+    #[serde(deserialize_with = "optional_address")]
+    pub metrics_address: Option<String>,
+    pub disconnect_timeout_secs: u64,
+}
+
+/// An empty address turns the listener off, the same as on the command line.
+/// A missing one keeps the default
+fn optional_address<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error> {
+    let address = Option::<String>::deserialize(deserializer)?;
+    Ok(address.filter(|address| !address.is_empty()))
+}
+
+impl Default for NetworkSettings {
+    fn default() -> Self {
+        Self {
+            signaling_address: "0.0.0.0:14191".to_string(),
+            webrtc_address: "0.0.0.0:14192".to_string(),
+            public_address: "http://127.0.0.1:14192".to_string(),
+            udp_auth_address: Some("0.0.0.0:14195".to_string()),
+            udp_address: "0.0.0.0:14196".to_string(),
+            udp_public_address: "http://127.0.0.1:14196".to_string(),
+            admin_address: Some("127.0.0.1:14193".to_string()),
+            metrics_address: Some("127.0.0.1:14194".to_string()),
+            disconnect_timeout_secs: 5,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct GameSettings {
+    pub tick_interval_ms: u64,
+    pub turn_timeout_secs: u32,
+    /// Seconds between everyone pressing start and the first deal
+    pub start_countdown_secs: usize,
+    /// Seconds between the end of a match and the next deal
+    pub next_match_countdown_secs: usize,
+    pub max_tables: usize,
+}
+
+impl Default for GameSettings {
+    fn default() -> Self {
+        Self {
+            tick_interval_ms: 40,
+            turn_timeout_secs: 20,
+            start_countdown_secs: 3,
+            next_match_countdown_secs: 5,
+            max_tables: 1,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct RuleSettings {
+    pub min_players: usize,
+    pub max_players: usize,
+    /// Ranked tables keep aids like the played cards tracker off
+    pub ranked: bool,
+}
+
+impl Default for RuleSettings {
+    fn default() -> Self {
+        Self {
+            min_players: 2,
+            max_players: 4,
+            ranked: false,
+        }
+    }
+}
+
+impl From<&RuleSettings> for Rules {
+    fn from(rules: &RuleSettings) -> Self {
+        Self {
+            min_players: rules.min_players,
+            max_players: rules.max_players,
+        }
+    }
+}
+
+/// Token buckets per user and message type, going over the budget earns a
+/// strike, enough strikes mute the user and too many mutes kick them
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct RateLimitSettings {
+    pub enabled: bool,
+    pub strikes_before_mute: u32,
+    pub mute_secs: u64,
+    /// Mutes a user can get, the next one is a kick
+    pub mutes_before_kick: u32,
+    /// Seconds without a strike that wipe the strikes
+    pub strike_reset_secs: u64,
+    /// Seconds after a mute ends without another one that wipe the mutes
+    pub mute_reset_secs: u64,
+    pub join: Budget,
+    pub ready: Budget,
+    pub start: Budget,
+    pub request_start: Budget,
+    pub play: Budget,
+    pub skip: Budget,
+    pub seats: Budget,
+}
+
+impl Default for RateLimitSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            strikes_before_mute: 5,
+            mute_secs: 10,
+            mutes_before_kick: 2,
+            strike_reset_secs: 30,
+            mute_reset_secs: 300,
+            join: Budget::new(2, 0.2),
+            ready: Budget::new(3, 1.),
+            start: Budget::new(3, 0.5),
+            request_start: Budget::new(2, 0.5),
+            play: Budget::new(5, 2.),
+            skip: Budget::new(5, 2.),
+            seats: Budget::new(3, 0.5),
+        }
+    }
+}
+
+/// `burst` messages at once, then `per_sec` on average
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(deny_unknown_fields)]
+pub struct Budget {
+    pub burst: u32,
+    pub per_sec: f32,
+}
+
+impl Budget {
+    pub fn new(burst: u32, per_sec: f32) -> Self {
+        Self { burst, per_sec }
+    }
+}
+
+#[derive(Deserialize, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuthSettings {
+    pub require_auth: bool,
+    pub user_name: String,
+    pub password: String,
+}
+
+// Keep the password out of the startup logs
+impl fmt::Debug for AuthSettings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuthSettings")
+            .field("require_auth", &self.require_auth)
+            .field("user_name", &self.user_name)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug)]
+pub enum SettingsError {
+    Read(PathBuf, std::io::Error),
+    Parse(PathBuf, toml::de::Error),
+    Invalid(Vec<String>),
+}
+
+impl Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Read(path, err) => write!(f, "can not read {}: {}", path.display(), err),
+            Self::Parse(path, err) => write!(f, "invalid config {}: {}", path.display(), err),
+            Self::Invalid(problems) => {
+                writeln!(f, "invalid settings:")?;
+                for problem in problems {
+                    writeln!(f, "  - {}", problem)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for SettingsError {}
+
+impl Settings {
+    /// Reads the config file, applies the command line and env overrides, then validates
+    pub fn load() -> Result<Self, SettingsError> {
+        dotenv().ok();
+
+        Self::from_cli(Cli::parse())
+    }
+
+    pub fn from_cli(cli: Cli) -> Result<Self, SettingsError> {
+        let mut settings = match &cli.config {
+            Some(path) => Self::from_file(path)?,
+            None => {
+                let path = PathBuf::from(DEFAULT_CONFIG_PATH);
+                if path.exists() {
+                    Self::from_file(&path)?
+                } else {
+                    Self::default()
+                }
+            }
+        };
+
+        settings.apply(cli);
+        settings.validate()?;
+
+        Ok(settings)
+    }
+
+    fn from_file(path: &PathBuf) -> Result<Self, SettingsError> {
+        let content =
+            fs::read_to_string(path).map_err(|err| SettingsError::Read(path.clone(), err))?;
+
+        toml::from_str(&content).map_err(|err| SettingsError::Parse(path.clone(), err))
+    }
+
+    fn apply(&mut self, cli: Cli) {
+        let network = &mut self.network;
+        let game = &mut self.game;
+        let auth = &mut self.auth;
+
+        if let Some(address) = cli.signaling_address {
+            network.signaling_address = address;
+        }
+        if let Some(address) = cli.webrtc_address {
+            network.webrtc_address = address;
+        }
+        if let Some(address) = cli.public_address {
+            network.public_address = address;
+        }
+        if let Some(address) = cli.udp_auth_address {
+            network.udp_auth_address = Some(address).filter(|a| !a.is_empty());
+        }
+        if let Some(address) = cli.udp_address {
+            network.udp_address = address;
+        }
+        if let Some(address) = cli.udp_public_address {
+            network.udp_public_address = address;
+        }
+        if let Some(address) = cli.admin_address {
+            network.admin_address = Some(address).filter(|a| !a.is_empty());
+        }
+        if let Some(address) = cli.metrics_address {
+            network.metrics_address = Some(address).filter(|a| !a.is_empty());
+        }
+        if let Some(tick_interval_ms) = cli.tick_interval_ms {
+            game.tick_interval_ms = tick_interval_ms;
+        }
+        if let Some(turn_timeout_secs) = cli.turn_timeout_secs {
+            game.turn_timeout_secs = turn_timeout_secs;
+        }
+        if let Some(max_tables) = cli.max_tables {
+            game.max_tables = max_tables;
+        }
+        if let Some(require_auth) = cli.require_auth {
+            auth.require_auth = require_auth;
+        }
+        if let Some(user_name) = cli.auth_user_name {
+            auth.user_name = user_name;
+        }
+        if let Some(password) = cli.auth_user_pass {
+            auth.password = password;
+        }
+    }
+
+    /// Collects every problem at once so a bad deploy is fixed in one go
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        let mut problems = vec![];
+
+        for (name, address) in [
+            ("signaling_address", Some(&self.network.signaling_address)),
+            ("webrtc_address", Some(&self.network.webrtc_address)),
+            ("udp_auth_address", self.network.udp_auth_address.as_ref()),
+            (
+                "udp_address",
+                self.network
+                    .udp_auth_address
+                    .as_ref()
+                    .map(|_| &self.network.udp_address),
+            ),
+            ("admin_address", self.network.admin_address.as_ref()),
+            ("metrics_address", self.network.metrics_address.as_ref()),
+        ] {
+            let Some(address) = address else {
+                continue;
+            };
+
+            match address.parse::<SocketAddr>() {
+                Ok(address) if name == "admin_address" && !address.ip().is_loopback() => {
+                    problems.push(format!(
+                        "network.admin_address `{}` must be a localhost address",
+                        address
+                    ));
+                }
+                Ok(_) => {}
+                Err(_) => problems.push(format!(
+                    "network.{} `{}` is not an `ip:port` address",
+                    name, address
+                )),
+            }
+        }
+
+        let mut public_addresses = vec![("public_address", &self.network.public_address)];
+        if self.network.udp_auth_address.is_some() {
+            public_addresses.push(("udp_public_address", &self.network.udp_public_address));
+        }
+
+        for (name, address) in public_addresses {
+            if !address.starts_with("http://") && !address.starts_with("https://") {
+                problems.push(format!(
+                    "network.{} `{}` must start with http:// or https://",
+                    name, address
+                ));
+            }
+        }
+
+        if self.network.disconnect_timeout_secs == 0 {
+            problems.push("network.disconnect_timeout_secs must be at least 1".to_string());
+        }
+
+        if !(1..=1000).contains(&self.game.tick_interval_ms) {
+            problems.push(format!(
+                "game.tick_interval_ms must be between 1 and 1000, got {}",
+                self.game.tick_interval_ms
+            ));
+        }
+
+        if self.game.turn_timeout_secs == 0 {
+            problems.push("game.turn_timeout_secs must be at least 1".to_string());
+        }
+
+        if self.game.max_tables == 0 {
+            problems.push("game.max_tables must be at least 1".to_string());
+        }
+
+        let rules = &self.rules;
+        if rules.min_players < 2 || rules.max_players > 4 || rules.min_players > rules.max_players {
+            problems.push(format!(
+                "rules need 2 <= min_players <= max_players <= 4, got {} and {}",
+                rules.min_players, rules.max_players
+            ));
+        }
+
+        let rate_limit = &self.rate_limit;
+        for (name, budget) in [
+            ("join", rate_limit.join),
+            ("ready", rate_limit.ready),
+            ("start", rate_limit.start),
+            ("request_start", rate_limit.request_start),
+            ("play", rate_limit.play),
+            ("skip", rate_limit.skip),
+            ("seats", rate_limit.seats),
+        ] {
+            if budget.burst == 0 || budget.per_sec.is_nan() || budget.per_sec <= 0. {
+                problems.push(format!(
+                    "rate_limit.{} needs burst >= 1 and per_sec > 0, got {} and {}",
+                    name, budget.burst, budget.per_sec
+                ));
+            }
+        }
+
+        if rate_limit.strikes_before_mute == 0 {
+            problems.push("rate_limit.strikes_before_mute must be at least 1".to_string());
+        }
+
+        if self.auth.require_auth
+            && (self.auth.user_name.is_empty() || self.auth.password.is_empty())
+        {
+            problems.push(
+                "auth.user_name and auth.password must be set when auth.require_auth is on"
+                    .to_string(),
+            );
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(SettingsError::Invalid(problems))
+        }
+    }
+
+    pub fn tick_interval(&self) -> Duration {
+        Duration::from_millis(self.game.tick_interval_ms)
+    }
+
+    pub fn turn_timeout(&self) -> f32 {
+        self.game.turn_timeout_secs as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_addresses_keep_the_defaults() {
+        let settings: Settings = toml::from_str("[network]\ndisconnect_timeout_secs = 3").unwrap();
+
+        assert_eq!(
+            settings.network.udp_auth_address.as_deref(),
+            Some("0.0.0.0:14195")
+        );
+        assert_eq!(
+            settings.network.admin_address.as_deref(),
+            Some("127.0.0.1:14193")
+        );
+        assert_eq!(
+            settings.network.metrics_address.as_deref(),
+            Some("127.0.0.1:14194")
+        );
+        assert!(settings.validate().is_ok());
+    }
+
+    #[test]
+    fn test_empty_addresses_turn_listeners_off() {
+        let settings: Settings = toml::from_str(
+            "[network]\nudp_auth_address = \"\"\nadmin_address = \"\"\nmetrics_address = \"\"",
+        )
+        .unwrap();
+
+        assert_eq!(settings.network.udp_auth_address, None);
+        assert_eq!(settings.network.admin_address, None);
+        assert_eq!(settings.network.metrics_address, None);
+        assert!(settings.validate().is_ok());
+    }
+}
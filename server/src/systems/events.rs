@@ -1,10 +1,10 @@
-use std::cmp::Ordering;
-
 use bevy_ecs::{
-    event::EventReader,
-    system::{Commands, Query, ResMut},
+    entity::Entity,
+    event::{EventReader, EventWriter},
+    system::{Commands, Query, Res, ResMut},
 };
 use bevy_log::info;
+use bevy_time::Time;
 
 use naia_bevy_server::{
     events::{
@@ -12,37 +12,110 @@ use naia_bevy_server::{
         InsertComponentEvents, MessageEvents, RemoveComponentEvents, SpawnEntityEvent, TickEvent,
         UpdateComponentEvents,
     },
-    CommandsExt, Server,
+    CommandsExt, Server, UserKey,
 };
 
 use naia_bevy_demo_shared::{
     channels::{EntityAssignmentChannel, GameSystemChannel, PlayerActionChannel},
     components::{
-        deck::Deck,
         hand::Hand,
-        player::{Host, Player},
-        table::Table,
-        turn::Turn,
-        Color, ColorValue, Counter, Position, Shape, ShapeValue,
+        player::{Host, Player, AVATAR_COUNT},
+        table::{Table, TableOptions},
+        Color, ColorValue, Position, Shape, ShapeValue,
     },
+    is_compatible_version,
     messages::{
-        error::GameError, AcceptPlayCard, AcceptPlayerReady, AcceptStartGame, Auth, EndMatch,
-        EntityAssignment, ErrorCode, NewPlayer, PlayCard, PlayerMessage, PlayerReady, RequestStart,
-        SkipTurn, StartGame, UpdateTurn, WaitForStart,
+        error::GameError, Auth, EntityAssignment, ErrorCode, IncompatibleVersion, KickPlayer,
+        NewPlayer, PlayCard, PlayerReady, RequestStart, SetCardTracker, SkipTurn, StartGame,
+        SystemMessage, TakeSeat, TransferHost,
     },
+    PROTOCOL_VERSION,
 };
+use tienlen_engine::{Action, Event, Phase};
 
 use crate::{
-    resources::{Global, PlayerData},
-    systems::common::PlayerIteratorMut,
+    admin::ForceEndMatch,
+    metrics::Metrics,
+    rate_limit::{LimitedAction, RateLimiter},
+    resources::Global,
+    settings::Settings,
+    transport::UdpAddresses,
 };
 
-use super::common::PlayerIterator;
+use super::game::EngineEvent;
+
+fn reject(server: &mut Server, metrics: &Metrics, user_key: &UserKey, error: GameError) {
+    metrics.play_rejected(&error);
+    server.send_message::<GameSystemChannel, ErrorCode>(user_key, &ErrorCode::from(error));
+}
+
+/// Seat position of the user, `None` until they joined the table
+fn user_pos(global: &Global, player_q: &Query<&mut Player>, user_key: &UserKey) -> Option<usize> {
+    global
+        .users_map
+        .get(user_key)
+        .and_then(|entity| player_q.get(*entity).ok())
+        .map(|player| *player.pos)
+}
+
+fn is_host(global: &Global, host_q: &Query<&Host>, user_key: &UserKey) -> bool {
+    global
+        .users_map
+        .get(user_key)
+        .is_some_and(|entity| host_q.contains(*entity))
+}
 
-pub fn auth_events(mut server: Server, mut event_reader: EventReader<AuthEvents>) {
+/// The user and entity of whoever else sits at `pos`, as long as `user_key` is
+/// the host
+fn host_target(
+    global: &Global,
+    player_q: &Query<&mut Player>,
+    host_q: &Query<&Host>,
+    user_key: &UserKey,
+    pos: usize,
+) -> Result<(UserKey, Entity), GameError> {
+    if !is_host(global, host_q, user_key) {
+        return Err(GameError::NotHost);
+    }
+
+    global
+        .users_map
+        .iter()
+        .find(|(other, entity)| {
+            *other != user_key
+                && player_q
+                    .get(**entity)
+                    .is_ok_and(|player| *player.pos == pos)
+        })
+        .map(|(other, entity)| (*other, *entity))
+        .ok_or(GameError::NoSeat)
+}
+
+pub fn auth_events(
+    mut server: Server,
+    mut global: ResMut<Global>,
+    settings: Res<Settings>,
+    mut event_reader: EventReader<AuthEvents>,
+) {
     for events in event_reader.read() {
         for (user_key, auth) in events.read::<Auth>() {
-            if auth.username == "charlie" && auth.password == "12345" {
+            if !is_compatible_version(&auth.protocol_version) {
+                info!(
+                    "Client protocol {} does not match {} -> Drop after telling them",
+                    auth.protocol_version, PROTOCOL_VERSION
+                );
+
+                // A rejection carries no reason, let them in just to say why
+                global.outdated_users.insert(user_key);
+                server.accept_connection(&user_key);
+                continue;
+            }
+
+            let credentials_ok = !settings.auth.require_auth
+                || (auth.username == settings.auth.user_name
+                    && auth.password == settings.auth.password);
+
+            if credentials_ok {
                 // Accept incoming connection
                 server.accept_connection(&user_key);
             } else {
@@ -54,11 +127,22 @@ pub fn auth_events(mut server: Server, mut event_reader: EventReader<AuthEvents>
 }
 
 pub fn connect_events(
-    global: ResMut<Global>,
+    mut global: ResMut<Global>,
     mut server: Server,
     mut event_reader: EventReader<ConnectEvent>,
 ) {
     for ConnectEvent(user_key) in event_reader.read() {
+        if global.outdated_users.remove(user_key) {
+            server.send_message::<GameSystemChannel, IncompatibleVersion>(
+                user_key,
+                &IncompatibleVersion {
+                    server_version: PROTOCOL_VERSION.to_string(),
+                },
+            );
+            server.user_mut(user_key).disconnect();
+            continue;
+        }
+
         let address = server
             .user_mut(user_key)
             // Add User to the main Room
@@ -70,16 +154,52 @@ pub fn connect_events(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn disconnect_events(
     mut commands: Commands,
     mut server: Server,
     mut global: ResMut<Global>,
+    mut rate_limiter: ResMut<RateLimiter>,
+    metrics: Res<Metrics>,
+    udp_addresses: Option<Res<UdpAddresses>>,
+    player_q: Query<(Entity, &Player, Option<&Host>)>,
+    mut engine_ev: EventWriter<EngineEvent>,
     mut event_reader: EventReader<DisconnectEvent>,
 ) {
     for DisconnectEvent(user_key, user) in event_reader.read() {
         info!("Naia Server disconnected from: {:?}", user.address);
 
+        global.outdated_users.remove(user_key);
+        rate_limiter.forget(user_key);
+        if let Some(udp_addresses) = &udp_addresses {
+            udp_addresses.forget(&user.address);
+        }
+
+        if global.engine.phase() == Phase::Playing && global.users_map.contains_key(user_key) {
+            metrics.disconnect_mid_match();
+        }
+
         if let Some(entity) = global.users_map.remove(user_key) {
+            if let Ok((_, player, host)) = player_q.get(entity) {
+                // Mid match the clock plays for the seat until the match is over
+                let pos = *player.pos;
+                if let Ok(events) = global.engine.apply(Action::Leave { pos }) {
+                    engine_ev.send_batch(events.into_iter().map(EngineEvent));
+                }
+
+                // The host role goes to the first seat still taken
+                let next_host = player_q
+                    .iter()
+                    .filter(|(other, ..)| global.users_map.values().any(|user| user == other))
+                    .min_by_key(|(_, other, _)| *other.pos)
+                    .filter(|_| host.is_some());
+
+                if let Some((next_host, next_player, _)) = next_host {
+                    info!("Game State: `{}` is the host now", next_player.name());
+                    commands.entity(next_host).insert(Host);
+                }
+            }
+
             commands.entity(entity).despawn();
             server
                 .room_mut(&global.main_room_key)
@@ -101,19 +221,58 @@ pub fn message_events(
     mut server: Server,
     mut event_reader: EventReader<MessageEvents>,
     mut global: ResMut<Global>,
-    mut table_q: Query<&mut Table>,
-    mut player_q: Query<&mut Player>,
-    mut turn_q: Query<&mut Turn>,
-    mut counter_q: Query<&mut Counter>,
+    player_q: Query<&mut Player>,
+    host_q: Query<&Host>,
+    table_q: Query<&Table>,
+    mut options_q: Query<&mut TableOptions>,
+    mut engine_ev: EventWriter<EngineEvent>,
+    mut rate_limiter: ResMut<RateLimiter>,
+    metrics: Res<Metrics>,
+    settings: Res<Settings>,
+    time: Res<Time>,
 ) {
+    let now = time.elapsed();
+    let mut allow = |server: &mut Server, user_key: &UserKey, action: LimitedAction| {
+        rate_limiter.allow(server, &metrics, user_key, action, now)
+    };
+
+    // The host joining this frame is not in the query yet
+    let mut has_host = !host_q.is_empty();
+
     for events in event_reader.read() {
         for (user_key, new_player_data) in events.read::<PlayerActionChannel, NewPlayer>() {
-            let player_name = new_player_data.0.chars().take(10).collect::<String>();
+            if !allow(&mut server, &user_key, LimitedAction::Join) {
+                continue;
+            }
+
+            let NewPlayer { name, avatar } = new_player_data;
+            let joined = global.engine.apply(Action::Join { name });
+
+            let (pos, player_name) = match joined.as_deref() {
+                Ok([Event::PlayerJoined { pos, name }]) => (*pos, name.clone()),
+                Ok(_) => continue,
+                Err(error) => {
+                    info!("Game State: Can not join ({}) -> Disconnect", error.kind());
+
+                    let reason = match error {
+                        GameError::TableFull => "The table is full",
+                        _ => "The game already started",
+                    };
+
+                    server.send_message::<GameSystemChannel, SystemMessage>(
+                        &user_key,
+                        &SystemMessage(reason.to_string()),
+                    );
+                    server.user_mut(&user_key).disconnect();
+                    continue;
+                }
+            };
 
             info!("Game State: Player `{}` join", player_name);
 
-            let player_num = global.users_map.len();
-            let player = Player::new(player_num, &player_name);
+            // An unknown avatar falls back to the one of the seat
+            let avatar = if avatar < AVATAR_COUNT { avatar } else { pos };
+            let player = Player::new(pos, &player_name, avatar);
 
             let entity = commands
                 .spawn_empty()
@@ -121,39 +280,18 @@ pub fn message_events(
                 .insert(player)
                 .id();
 
-            if player_num == 0 {
+            if !has_host {
                 commands.entity(entity).insert(Host);
+                has_host = true;
             }
 
             global.users_map.insert(user_key, entity);
 
-            let player_data = PlayerData {
-                name: player_name,
-                entity,
-                pos: player_num,
-                active: player_num == 0,
-                cards: String::new(),
-                score: 0,
-                user_key,
-            };
-
-            global.players_map.0.insert(user_key, player_data.clone());
-            global.total_player += 1;
-
             server.room_mut(&global.main_room_key).add_entity(&entity);
 
             let mut assignment_message = EntityAssignment::new(true);
             assignment_message.entity.set(&server, &entity);
 
-            // let new_player=  NewPlayer {
-            //     name: player_name,
-            //     server_pos: player_num
-            // };
-
-            // for (u_key, _) in global.users_map.iter() {
-            //     server.send_message::<GameSystemChannel, NewPlayer>(u_key, &new_player);
-            // }
-
             server.send_message::<EntityAssignmentChannel, EntityAssignment>(
                 &user_key,
                 &assignment_message,
@@ -161,403 +299,232 @@ pub fn message_events(
         }
 
         for (user_key, _) in events.read::<PlayerActionChannel, PlayerReady>() {
-            let player_entity = global.users_map.get(&user_key).unwrap();
-
-            if let Ok(mut player) = player_q.get_mut(*player_entity) {
-                *player.ready = true;
+            if !allow(&mut server, &user_key, LimitedAction::Ready) {
+                continue;
+            }
 
-                let new_player = AcceptPlayerReady {
-                    name: player.name(),
-                    server_pos: *player.pos,
-                };
+            let Some(pos) = user_pos(&global, &player_q, &user_key) else {
+                continue;
+            };
 
-                for (u_key, _) in global.users_map.iter() {
-                    server.send_message::<GameSystemChannel, AcceptPlayerReady>(u_key, &new_player);
-                }
+            if let Ok(events) = global.engine.apply(Action::Ready { pos }) {
+                engine_ev.send_batch(events.into_iter().map(EngineEvent));
             }
         }
 
-        for (_, _) in events.read::<PlayerActionChannel, StartGame>() {
-            let total_player = global.total_player;
-
-            if total_player < 2 {
-                info!("Game State: The game require 2 to 4 players -> Discard Start Game!");
-                return;
+        for (user_key, TakeSeat(to)) in events.read::<PlayerActionChannel, TakeSeat>() {
+            if !allow(&mut server, &user_key, LimitedAction::Seats) {
+                continue;
             }
 
-            if player_q.iter().some_player_not_ready() {
-                info!("Game State: There are players not ready yet -> Discard Start Game!");
-                return;
-            }
+            let Some(pos) = user_pos(&global, &player_q, &user_key) else {
+                continue;
+            };
 
-            for (user_key, _p_entity) in global.users_map.iter() {
-                server.send_message::<GameSystemChannel, WaitForStart>(user_key, &WaitForStart(3));
+            match global.engine.apply(Action::TakeSeat { pos, to }) {
+                Ok(events) => engine_ev.send_batch(events.into_iter().map(EngineEvent)),
+                Err(error) => {
+                    server.send_message::<GameSystemChannel, ErrorCode>(
+                        &user_key,
+                        &ErrorCode::from(error),
+                    );
+                }
             }
-
-            // Add the table component to the room
-            let server_table = Table::new("".to_string());
-            let server_table_entity = commands
-                .spawn_empty()
-                .enable_replication(&mut server)
-                .insert(server_table)
-                .id();
-
-            let server_counter = Counter::default();
-
-            let server_counter_entity = commands
-                .spawn_empty()
-                .enable_replication(&mut server)
-                .insert(server_counter)
-                .id();
-
-            let turn = Turn::new(total_player);
-
-            commands.spawn_empty().insert(turn);
-
-            server
-                .room_mut(&global.main_room_key)
-                .add_entity(&server_table_entity)
-                .add_entity(&server_counter_entity);
         }
 
-        for (user_key, _) in events.read::<PlayerActionChannel, SkipTurn>().into_iter() {
-            let mut turn = turn_q.get_single_mut().unwrap();
-
-            info!("==== BEFORE SKIP: {}", global.leader_turn);
-
-            if global.leader_turn {
-                server.send_message::<GameSystemChannel, ErrorCode>(
-                    &user_key,
-                    &ErrorCode::from(GameError::CanNotSkipTurn),
-                );
-
-                return;
+        for (user_key, KickPlayer(pos)) in events.read::<PlayerActionChannel, KickPlayer>() {
+            if !allow(&mut server, &user_key, LimitedAction::Seats) {
+                continue;
             }
 
-            let current_active_player = turn.current_active_player().unwrap();
-
-            if let (leader_turn, Some(next_player)) = turn.skip_turn() {
-                // If only 1 player left on the pool, they can play any card they wanted to and
-                // they can not skip turn
+            let target = match global.engine.phase() {
+                Phase::Lobby => host_target(&global, &player_q, &host_q, &user_key, pos),
+                phase => Err(GameError::WrongPhase { phase }),
+            };
 
-                global.leader_turn = leader_turn;
+            match target {
+                Ok((kicked, _)) => {
+                    info!("Game State: The host removes seat {}", pos);
 
-                for (u_key, _) in global.users_map.iter() {
-                    server.send_message::<GameSystemChannel, UpdateTurn>(
-                        u_key,
-                        &UpdateTurn(next_player),
+                    server.send_message::<GameSystemChannel, SystemMessage>(
+                        &kicked,
+                        &SystemMessage("The host removed you from the table".to_string()),
                     );
-
-                    server.send_message::<GameSystemChannel, PlayerMessage>(
-                        u_key,
-                        &PlayerMessage(current_active_player, "skip".to_string()),
+                    // The seat is freed when the disconnect comes through
+                    server.user_mut(&kicked).disconnect();
+                }
+                Err(error) => {
+                    server.send_message::<GameSystemChannel, ErrorCode>(
+                        &user_key,
+                        &ErrorCode::from(error),
                     );
                 }
-
-                player_q.iter_mut().set_next_active(next_player);
-            };
-
-            // Reset  counter
-            counter_q.get_single_mut().unwrap().recount();
+            }
         }
 
-        events
-            .read::<PlayerActionChannel, PlayCard>()
-            .into_iter()
-            .for_each(|(user_key, play_card)| {
-                let put_hand = Hand::from_str(&play_card.0);
+        for (user_key, TransferHost(pos)) in events.read::<PlayerActionChannel, TransferHost>() {
+            if !allow(&mut server, &user_key, LimitedAction::Seats) {
+                continue;
+            }
+
+            match host_target(&global, &player_q, &host_q, &user_key, pos) {
+                Ok((_, next_host)) => {
+                    info!("Game State: The host hands over to seat {}", pos);
 
-                // Get player info
-                let cur_player_entity = *global.users_map.get(&user_key).unwrap();
-                let mut cur_player = player_q.get_mut(cur_player_entity).unwrap();
+                    if let Some(host) = global.users_map.get(&user_key) {
+                        commands.entity(*host).remove::<Host>();
+                    }
+                    commands.entity(next_host).insert(Host);
+                }
+                Err(error) => {
+                    server.send_message::<GameSystemChannel, ErrorCode>(
+                        &user_key,
+                        &ErrorCode::from(error),
+                    );
+                }
+            }
+        }
 
-                let player_name = cur_player.name();
+        for (user_key, SetCardTracker(on)) in events.read::<PlayerActionChannel, SetCardTracker>() {
+            if !allow(&mut server, &user_key, LimitedAction::Seats) {
+                continue;
+            }
 
-                info!(
-                    "================= Game State: Player {} Try to play cards ========================",
-                    player_name
-                );
+            let Ok(mut options) = options_q.get_single_mut() else {
+                continue;
+            };
 
-                info!("Cards: {}", put_hand);
+            let result = if !is_host(&global, &host_q, &user_key) {
+                Err(GameError::NotHost)
+            } else if *options.ranked {
+                Err(GameError::RankedTable)
+            } else {
+                Ok(())
+            };
 
-                if !put_hand.check_combination() {
+            match result {
+                Ok(()) => {
+                    info!("Game State: Card tracker {}", if on { "on" } else { "off" });
+                    *options.card_tracker = on;
+                }
+                Err(error) => {
                     server.send_message::<GameSystemChannel, ErrorCode>(
                         &user_key,
-                        &ErrorCode::from(GameError::WrongCombination),
+                        &ErrorCode::from(error),
                     );
-                    return;
                 }
+            }
+        }
 
-                // Check if is their turn?
+        for (user_key, _) in events.read::<PlayerActionChannel, StartGame>() {
+            if !allow(&mut server, &user_key, LimitedAction::Start) {
+                continue;
+            }
+
+            if table_q.iter().count() >= settings.game.max_tables {
+                info!("Game State: Max tables reached -> Discard Start Game!");
+                continue;
+            }
 
-                if !*cur_player.active {
+            match global.engine.apply(Action::Start) {
+                Ok(events) => engine_ev.send_batch(events.into_iter().map(EngineEvent)),
+                Err(error) => {
                     info!(
-                        "Game State: Player is not in turn but able to play card -> Discard action"
+                        "Game State: Can not start ({}) -> Discard Start Game!",
+                        error.kind()
                     );
                     server.send_message::<GameSystemChannel, ErrorCode>(
                         &user_key,
-                        &ErrorCode::from(GameError::WrongTurn),
+                        &ErrorCode::from(error),
                     );
-                    return;
                 }
+            }
+        }
 
-                info!("Game State: Play is in correct turn");
-
-                if let Some(last_played_hand) = global.table.back() {
-                    // FIXME: Find better way for allow free combo. This feel like hacky
-                    // Not check last hand played on the table because of leader turn
-                    if !global.leader_turn {
-                        info!("last_played_hand: {}", last_played_hand);
-                        if last_played_hand.len() != put_hand.len() {
-                            server.send_message::<GameSystemChannel, ErrorCode>(
-                                &user_key,
-                                &ErrorCode::from(GameError::WrongCombination),
-                            );
-
-                            info!("Game State: Wrong Combination!");
-
-                            return;
-                        }
-
-                        if last_played_hand.cmp(&put_hand) == Ordering::Greater {
-                            server.send_message::<GameSystemChannel, ErrorCode>(
-                                &user_key,
-                                &ErrorCode::from(GameError::InvalidCards),
-                            );
-
-                            info!("Game State: Weaker card!");
-                            return;
-                        }
-                    }
-                }
-
-                info!("Game State: Pass card validation");
-
-                global.leader_turn = false;
-
-                let mut turn = turn_q.get_single_mut().unwrap();
-                let current_active_player = turn.current_active_player().unwrap();
-
-                // Update cards on the table
-                let mut table = table_q.get_single_mut().unwrap();
-                *table.cards = put_hand.to_string();
-
-                // Keep track the history of the card being played
-                global.table.push_back(put_hand.clone());
-
-                info!("Game State: Updated Table Cards");
-
-                info!("Game State: Start update card to players");
-                // Update cards of the player
-
-                    let player_cards = cur_player.cards.clone();
-
-                    let mut player_hand = Hand::from(player_cards);
-                    // remove cards
-                    player_hand.remove_cards(put_hand.cards.as_slice());
-
-                    let new_hand_str = player_hand.to_string();
-                    *cur_player.cards =  new_hand_str.clone();
-
-                    info!("Game State: Removed Card from the player");
-
-                    global.players_map.update_cards(&user_key, new_hand_str);
-
-                    // Check if run out of cards / update player score
-                    if player_hand.is_empty() {
-
-                        info!("Game State: The player run out of card -> calculate score or end game now");
-                        let next_score = turn.next_score();
-
-                        global.players_map.update_score(&user_key, next_score);
-
-                        let mut player = player_q
-                            .get_mut(*global.users_map.get(&user_key).unwrap())
-                            .unwrap();
-
-                        *player.score += next_score;
-
-                        info!("Game State: Updated score");
-                        
-
-                        // Update turn pool
-                        let next_player = turn.player_out();
-                        info!("Game State: Removed player out of turn pool");
-
-                        player_q.iter_mut().set_next_active(next_player);
-
-                    let data = AcceptPlayCard {
-                        cur_player: current_active_player,
-                        cards: play_card.0,
-                        next_player,
-                        run_out_card: true,
-                    };
-
-
-                    for (u_key, _) in global.users_map.iter() {
-                        server.send_message::<GameSystemChannel, AcceptPlayCard>(
-                            u_key,
-                            &data,
-                        );
-                    }
-
-                        info!("Game State: Sended new turn to all player");
-
-                        info!("=============== End play card action ====================");
-                        // No need to update turn after then
-                        return;
-                    }
-
-                // Handle Turn:
-                if let Some(next_player) = turn.next_turn() {
-                    info!("Game State: Update player turn");
-
-                    global.players_map.update_active_player(next_player);
-
-                    let data = AcceptPlayCard {
-                        cur_player: current_active_player,
-                        cards: play_card.0,
-                        next_player,
-                        run_out_card: false,
-                    };
-
-                    for (u_key, _) in global.users_map.iter() {
-                        server.send_message::<GameSystemChannel, AcceptPlayCard>(
-                            u_key,
-                            &data,
-                        );
-                    }
+        for (user_key, _) in events.read::<PlayerActionChannel, SkipTurn>() {
+            if !allow(&mut server, &user_key, LimitedAction::SkipTurn) {
+                continue;
+            }
 
-                    info!("Game State: Sended Play Card Message");
+            let Some(pos) = user_pos(&global, &player_q, &user_key) else {
+                continue;
+            };
 
-                    for (u_key, _) in global.users_map.iter() {
-                        server.send_message::<GameSystemChannel, UpdateTurn>(
-                            u_key,
-                            &UpdateTurn(next_player),
-                        );
-                    }
+            match global.engine.apply(Action::Skip { pos }) {
+                Ok(events) => engine_ev.send_batch(events.into_iter().map(EngineEvent)),
+                Err(error) => reject(&mut server, &metrics, &user_key, error),
+            }
+        }
 
-                    info!("Game State: Sended Update Turn Message");
+        for (user_key, play_card) in events.read::<PlayerActionChannel, PlayCard>() {
+            if !allow(&mut server, &user_key, LimitedAction::PlayCard) {
+                continue;
+            }
 
-                    player_q.iter_mut().set_next_active(next_player);
+            let Some(pos) = user_pos(&global, &player_q, &user_key) else {
+                continue;
+            };
 
-                    if let Ok(mut counter) = counter_q.get_single_mut() {
-                        counter.recount();
-                    }
+            let cards = Hand::from_str(&play_card.0);
 
-                    info!("Game State: Reseted Counter");
+            info!("Game State: Player {} try to play {}", pos, cards);
 
-                    info!("=============== End play card action ====================");
+            match global.engine.apply(Action::Play { pos, cards }) {
+                Ok(events) => {
+                    metrics.play_accepted();
+                    engine_ev.send_batch(events.into_iter().map(EngineEvent));
+                }
+                Err(error) => {
+                    info!("Game State: Reject play ({})", error.kind());
+                    reject(&mut server, &metrics, &user_key, error);
                 }
-                // global.players_map.debug();
-                // turn.debug();
-            });
+            }
+        }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn accept_start_game(
-    mut event_reader: EventReader<MessageEvents>,
     mut server: Server,
+    mut event_reader: EventReader<MessageEvents>,
     mut global: ResMut<Global>,
-    mut turn_q: Query<&mut Turn>,
-    mut player_q: Query<&mut Player>,
-    mut table_q: Query<&mut Table>,
-    mut counter_q: Query<&mut Counter>,
+    mut rate_limiter: ResMut<RateLimiter>,
+    player_q: Query<&mut Player>,
+    mut engine_ev: EventWriter<EngineEvent>,
+    metrics: Res<Metrics>,
+    time: Res<Time>,
 ) {
     for events in event_reader.read() {
-        for (_, _) in events.read::<PlayerActionChannel, RequestStart>() {
-            global.total_request_play += 1;
-
-            info!("Global total: {}", global.total_player);
-
-            if global.total_request_play == global.total_player {
-                global.new_match();
-
-                if let Ok(mut turn) = turn_q.get_single_mut() {
-                    turn.new_match();
-                    let next_player = turn.current_active_player().unwrap();
-                    player_q.iter_mut().set_next_active(next_player);
-                }
-
-                if let Ok(mut table) = table_q.get_single_mut() {
-                    table.new_match();
-                }
-
-                if let Ok(mut counter) = counter_q.get_single_mut() {
-                    counter.recount();
-                }
-
-                let mut deck = Deck::new();
-
-                for (user_key, p_entity) in global.users_map.iter() {
-                    let hand = Hand {
-                        cards: deck.deal(13),
-                    };
-
-                    let cards_str = hand.to_string();
-
-                    let mut player = player_q.get_mut(*p_entity).unwrap();
-                    *player.cards = cards_str.clone();
+        for (user_key, _) in events.read::<PlayerActionChannel, RequestStart>() {
+            let action = LimitedAction::RequestStart;
+            if !rate_limiter.allow(&mut server, &metrics, &user_key, action, time.elapsed()) {
+                continue;
+            }
 
-                    let message = AcceptStartGame {
-                        cards: cards_str.clone(),
-                        // TODO: decide who play first
-                        active_player: 0,
-                    };
+            let Some(pos) = user_pos(&global, &player_q, &user_key) else {
+                continue;
+            };
 
-                    server.send_message::<GameSystemChannel, AcceptStartGame>(user_key, &message);
-                }
+            match global.engine.apply(Action::RequestDeal { pos }) {
+                Ok(events) => engine_ev.send_batch(events.into_iter().map(EngineEvent)),
+                Err(error) => info!("Game State: Discard request start ({})", error.kind()),
             }
         }
     }
 }
 
+/// Ends the match when the admin asks for it, normal ends come out of the plays
 pub fn end_match(
     mut global: ResMut<Global>,
-    mut turn_q: Query<&mut Turn>,
-    mut server: Server,
-    mut counter_q: Query<&mut Counter>,
-    mut player_q: Query<&mut Player>,
-    mut table_q: Query<&mut Table>,
+    mut force_end_ev: EventReader<ForceEndMatch>,
+    mut engine_ev: EventWriter<EngineEvent>,
 ) {
-    if let Ok(mut turn) = turn_q.get_single_mut() {
-        // End match here since only 1 player have cards left
-        if turn.only_one_player_left() {
-            // Clear player hand
-            info!("------ Game State: End Match ---------");
-
-            // // FIXME: let client verify & finish animation -> then reset
-            global.new_match();
-            turn.new_match();
-
-            let next_player = turn.current_active_player().unwrap();
-
-            player_q.iter_mut().set_next_active(next_player);
-
-            let mut deck = Deck::new();
-
-            for (user_key, entity) in global.users_map.iter_mut() {
-                let hand = Hand {
-                    cards: deck.deal(13),
-                };
-
-                let mut player = player_q.get_mut(*entity).unwrap();
-                *player.cards = hand.to_string();
-
-                server.send_message::<GameSystemChannel, EndMatch>(user_key, &EndMatch(5));
-            }
-
-            if let Ok(mut table) = table_q.get_single_mut() {
-                table.new_match();
-            }
-
-            if let Ok(mut counter) = counter_q.get_single_mut() {
-                counter.recount();
-            }
+    if force_end_ev.read().count() == 0 {
+        return;
+    }
 
-            info!("------ Game State: Finish End Match ---------");
-            // turn.debug();
-        }
+    if let Ok(events) = global.engine.apply(Action::EndMatch) {
+        engine_ev.send_batch(events.into_iter().map(EngineEvent));
     }
 }
 
@@ -0,0 +1,148 @@
+use bevy_ecs::{
+    entity::Entity,
+    event::{Event as BevyEvent, EventReader},
+    query::{Or, With},
+    system::{Commands, Query, Res},
+    world::Mut,
+};
+use bevy_log::info;
+use naia_bevy_demo_shared::{
+    channels::GameSystemChannel,
+    components::{timer::Counter, Player, Table},
+    messages::AcceptStartGame,
+};
+use naia_bevy_server::{CommandsExt, Server};
+use naia_shared::Message;
+use tienlen_adapter::{mirror, Countdowns, Notice};
+use tienlen_engine::Event;
+
+use crate::{metrics::Metrics, resources::Global, settings::Settings};
+
+/// Something the game engine reported, turned into messages by `engine_events`
+#[derive(BevyEvent)]
+pub struct EngineEvent(pub Event);
+
+fn broadcast<M: Message>(server: &mut Server, global: &Global, message: &M) {
+    for user_key in global.users_map.keys() {
+        server.send_message::<GameSystemChannel, M>(user_key, message);
+    }
+}
+
+fn reset_counter(counter_q: &mut Query<&mut Counter>, settings: &Settings) {
+    if let Ok(mut counter) = counter_q.get_single_mut() {
+        counter.reset(settings.turn_timeout());
+    }
+}
+
+/// Mirrors the engine into the replicated components and tells the clients
+#[allow(clippy::too_many_arguments)]
+pub fn engine_events(
+    mut commands: Commands,
+    mut server: Server,
+    global: Res<Global>,
+    settings: Res<Settings>,
+    metrics: Res<Metrics>,
+    mut engine_ev: EventReader<EngineEvent>,
+    mut player_q: Query<&mut Player>,
+    mut table_q: Query<&mut Table>,
+    mut counter_q: Query<&mut Counter>,
+    table_entity_q: Query<Entity, Or<(With<Table>, With<Counter>)>>,
+) {
+    let countdowns = Countdowns {
+        start: settings.game.start_countdown_secs,
+        next_match: settings.game.next_match_countdown_secs,
+    };
+
+    for EngineEvent(event) in engine_ev.read() {
+        match event {
+            Event::SeatChanged { from, to } => {
+                info!("Game State: Seat {} moves to seat {}", from, to);
+            }
+            Event::PlayerLeft { pos } => {
+                info!("Game State: Seat {} is free", pos);
+            }
+            Event::BackToLobby => {
+                info!("Game State: Too few players left, back to the lobby");
+
+                // A new table is set up at the next start
+                for entity in table_entity_q.iter() {
+                    server
+                        .room_mut(&global.main_room_key)
+                        .remove_entity(&entity);
+                    commands.entity(entity).despawn();
+                }
+            }
+            Event::TableSetUp => {
+                info!("Game State: Table set up");
+
+                // Add the table component to the room
+                let table_entity = commands
+                    .spawn_empty()
+                    .enable_replication(&mut server)
+                    .insert(Table::new(String::new()))
+                    .id();
+
+                let counter_entity = commands
+                    .spawn_empty()
+                    .enable_replication(&mut server)
+                    .insert(Counter::new(settings.turn_timeout()))
+                    .id();
+
+                server
+                    .room_mut(&global.main_room_key)
+                    .add_entity(&table_entity)
+                    .add_entity(&counter_entity);
+            }
+            Event::MatchStarted { .. } => {
+                info!("Game State: Match started");
+
+                metrics.match_started();
+                reset_counter(&mut counter_q, &settings);
+            }
+            Event::CardsPlayed { .. } | Event::TurnSkipped { .. } => {
+                reset_counter(&mut counter_q, &settings);
+            }
+            Event::PlayerFinished { pos, .. } => {
+                info!("Game State: Player {} run out of cards", pos);
+            }
+            Event::MatchEnded { .. } => {
+                info!("------ Game State: End Match ---------");
+
+                metrics.match_finished();
+                reset_counter(&mut counter_q, &settings);
+            }
+            // Handled when the message comes in, that is where the user is known
+            Event::PlayerJoined { .. } | Event::PlayerReady { .. } | Event::TurnChanged(_) => {}
+        }
+
+        let notices = mirror(
+            event,
+            &global.engine,
+            player_q.iter_mut().map(Mut::into_inner),
+            table_q.get_single_mut().ok().map(Mut::into_inner),
+            countdowns,
+        );
+
+        for notice in notices {
+            match notice {
+                Notice::PlayerReady(message) => broadcast(&mut server, &global, &message),
+                Notice::WaitForStart(message) => broadcast(&mut server, &global, &message),
+                Notice::StartGame { pos, message } => {
+                    for (user_key, entity) in global.users_map.iter() {
+                        if player_q.get(*entity).is_ok_and(|player| *player.pos == pos) {
+                            server.send_message::<GameSystemChannel, AcceptStartGame>(
+                                user_key, &message,
+                            );
+                        }
+                    }
+                }
+                Notice::PlayCard(message) => broadcast(&mut server, &global, &message),
+                Notice::UpdateTurn(message) => broadcast(&mut server, &global, &message),
+                Notice::PlayerMessage(message) => broadcast(&mut server, &global, &message),
+                Notice::MatchResults(message) => broadcast(&mut server, &global, &message),
+                Notice::EndMatch(message) => broadcast(&mut server, &global, &message),
+                Notice::BackToLobby(message) => broadcast(&mut server, &global, &message),
+            }
+        }
+    }
+}
@@ -1,52 +1,76 @@
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet};
 
-use bevy_ecs::system::Commands;
+use bevy_ecs::system::{Commands, Res};
 use bevy_log::info;
 
-use naia_bevy_server::{transport::webrtc, Server};
+use naia_bevy_server::{
+    transport::{udp, webrtc},
+    CommandsExt, Server,
+};
 
-use crate::resources::{Global, PlayerMap};
+use crate::{resources::Global, settings::Settings, transport::MultiSocket};
 
-use naia_bevy_demo_shared::{env::Env, messages::Counter};
+use naia_bevy_demo_shared::{components::TableOptions, messages::Counter};
+use tienlen_engine::GameEngine;
 
-pub fn init(mut commands: Commands, mut server: Server) {
+pub fn init(mut commands: Commands, mut server: Server, settings: Res<Settings>) {
     info!("Tienlen server is running");
+    info!("Settings: {:?}", *settings);
 
-    let env = Env::new();
-    info!("ENV: {:?}", env);
+    let network = &settings.network;
 
+    // Addresses are checked when the settings are loaded
     let server_addresses = webrtc::ServerAddrs::new(
-        env.signaling_address.parse().unwrap(),
+        network.signaling_address.parse().unwrap(),
         // IP Address to listen on for UDP WebRTC data channels
-        env.webrtc_address.parse().unwrap(),
+        network.webrtc_address.parse().unwrap(),
         // The public WebRTC IP address to advertise
-        &env.server_public_address,
+        &network.public_address,
     );
-    let socket = webrtc::Socket::new(&server_addresses, server.socket_config());
-    server.listen(socket);
+    let webrtc_socket = webrtc::Socket::new(&server_addresses, server.socket_config());
+
+    if let Some(udp_auth_address) = &network.udp_auth_address {
+        let udp_addresses = udp::ServerAddrs::new(
+            udp_auth_address.parse().unwrap(),
+            // IP Address to listen on for native clients
+            network.udp_address.parse().unwrap(),
+            // The public UDP address to advertise
+            &network.udp_public_address,
+        );
+        let udp_socket = udp::Socket::new(&udp_addresses, server.socket_config());
+
+        info!("Listening on WebRTC and UDP");
+        let socket = MultiSocket::new(webrtc_socket, udp_socket);
+        commands.insert_resource(socket.udp_addresses());
+        server.listen(socket);
+    } else {
+        server.listen(webrtc_socket);
+    }
 
     // Create a new, singular room, which will contain Users and Entities that they
     // can receive updates from
     let main_room_key = server.make_room().key();
 
-    let table = VecDeque::new();
+    // Lives as long as the room, the lobby shows the options already
+    let options_entity = commands
+        .spawn_empty()
+        .enable_replication(&mut server)
+        .insert(TableOptions::new(settings.rules.ranked))
+        .id();
+    server.room_mut(&main_room_key).add_entity(&options_entity);
 
     let counter = Counter::new(0.);
-    let players_map = PlayerMap::new();
+    let engine = GameEngine::new((&settings.rules).into());
 
     // Init Global Resource
     let global = Global {
         counter,
         time: 0.,
-        total_request_play: 0,
         pre_start: true,
-        leader_turn: true,
-        table,
-        players_map,
+        engine,
         main_room_key,
-        total_player: 0,
-        cur_active_pos: 0,
         users_map: HashMap::new(),
+        outdated_users: HashSet::new(),
         user_to_square_map: HashMap::new(),
         user_to_cursor_map: HashMap::new(),
         client_to_server_cursor_map: HashMap::new(),
@@ -1,5 +1,6 @@
 pub mod common;
 pub mod events;
+pub mod game;
 
 mod init;
 
@@ -0,0 +1,202 @@
+use std::{
+    collections::HashSet,
+    net::SocketAddr,
+    sync::{Arc, RwLock},
+};
+
+use bevy_ecs::system::Resource;
+use naia_bevy_server::transport::{
+    AuthReceiver, AuthSender, PacketReceiver, PacketSender, RecvError, SendError, Socket,
+};
+use naia_shared::IdentityToken;
+
+/// Listens on the WebRTC and the UDP transport at the same time.
+///
+/// Naia only takes one socket, so this one hands out senders and receivers
+/// that poll both and remember which transport every client address came
+/// from, to send the answers back the same way.
+pub struct MultiSocket {
+    webrtc: Box<dyn Socket>,
+    udp: Box<dyn Socket>,
+    udp_addresses: UdpAddresses,
+}
+
+impl MultiSocket {
+    pub fn new(webrtc: impl Into<Box<dyn Socket>>, udp: impl Into<Box<dyn Socket>>) -> Self {
+        Self {
+            webrtc: webrtc.into(),
+            udp: udp.into(),
+            udp_addresses: UdpAddresses::default(),
+        }
+    }
+
+    /// The UDP clients of this socket, to forget the ones who left
+    pub fn udp_addresses(&self) -> UdpAddresses {
+        self.udp_addresses.clone()
+    }
+}
+
+impl From<MultiSocket> for Box<dyn Socket> {
+    fn from(socket: MultiSocket) -> Self {
+        Box::new(socket)
+    }
+}
+
+/// Client addresses that talk to us over UDP, everything else is WebRTC
+#[derive(Resource, Clone, Default)]
+pub struct UdpAddresses(Arc<RwLock<HashSet<SocketAddr>>>);
+
+impl UdpAddresses {
+    fn contains(&self, address: &SocketAddr) -> bool {
+        self.0.read().unwrap().contains(address)
+    }
+
+    fn insert(&self, address: SocketAddr) {
+        self.0.write().unwrap().insert(address);
+    }
+
+    /// The client at `address` disconnected, a new one there may be WebRTC
+    pub fn forget(&self, address: &SocketAddr) {
+        self.0.write().unwrap().remove(address);
+    }
+}
+
+impl Socket for MultiSocket {
+    fn listen(
+        self: Box<Self>,
+    ) -> (
+        Box<dyn AuthSender>,
+        Box<dyn AuthReceiver>,
+        Box<dyn PacketSender>,
+        Box<dyn PacketReceiver>,
+    ) {
+        let (webrtc_auth_sender, webrtc_auth_receiver, webrtc_sender, webrtc_receiver) =
+            self.webrtc.listen();
+        let (udp_auth_sender, udp_auth_receiver, udp_sender, udp_receiver) = self.udp.listen();
+
+        let udp_addresses = self.udp_addresses;
+
+        (
+            Box::new(MultiAuthSender {
+                webrtc: webrtc_auth_sender,
+                udp: udp_auth_sender,
+                udp_addresses: udp_addresses.clone(),
+            }),
+            Box::new(MultiAuthReceiver {
+                webrtc: webrtc_auth_receiver,
+                udp: udp_auth_receiver,
+                udp_addresses: udp_addresses.clone(),
+                last: None,
+            }),
+            Box::new(MultiPacketSender {
+                webrtc: webrtc_sender,
+                udp: udp_sender,
+                udp_addresses: udp_addresses.clone(),
+            }),
+            Box::new(MultiPacketReceiver {
+                webrtc: webrtc_receiver,
+                udp: udp_receiver,
+                udp_addresses,
+                last: None,
+            }),
+        )
+    }
+}
+
+struct MultiPacketSender {
+    webrtc: Box<dyn PacketSender>,
+    udp: Box<dyn PacketSender>,
+    udp_addresses: UdpAddresses,
+}
+
+impl PacketSender for MultiPacketSender {
+    fn send(&self, address: &SocketAddr, payload: &[u8]) -> Result<(), SendError> {
+        if self.udp_addresses.contains(address) {
+            self.udp.send(address, payload)
+        } else {
+            self.webrtc.send(address, payload)
+        }
+    }
+}
+
+// The payloads are copied because the borrow checker can not return a borrow of
+// one inner receiver after trying the other one
+#[derive(Clone)]
+struct MultiPacketReceiver {
+    webrtc: Box<dyn PacketReceiver>,
+    udp: Box<dyn PacketReceiver>,
+    udp_addresses: UdpAddresses,
+    last: Option<(SocketAddr, Vec<u8>)>,
+}
+
+impl PacketReceiver for MultiPacketReceiver {
+    fn receive(&mut self) -> Result<Option<(SocketAddr, &[u8])>, RecvError> {
+        self.last = None;
+
+        if let Some((address, payload)) = self.udp.receive()? {
+            self.udp_addresses.insert(address);
+            self.last = Some((address, payload.to_vec()));
+        } else if let Some((address, payload)) = self.webrtc.receive()? {
+            self.last = Some((address, payload.to_vec()));
+        }
+
+        Ok(self
+            .last
+            .as_ref()
+            .map(|(address, payload)| (*address, payload.as_slice())))
+    }
+}
+
+struct MultiAuthSender {
+    webrtc: Box<dyn AuthSender>,
+    udp: Box<dyn AuthSender>,
+    udp_addresses: UdpAddresses,
+}
+
+impl AuthSender for MultiAuthSender {
+    fn accept(
+        &self,
+        address: &SocketAddr,
+        identity_token: &IdentityToken,
+    ) -> Result<(), SendError> {
+        if self.udp_addresses.contains(address) {
+            self.udp.accept(address, identity_token)
+        } else {
+            self.webrtc.accept(address, identity_token)
+        }
+    }
+
+    fn reject(&self, address: &SocketAddr) -> Result<(), SendError> {
+        if self.udp_addresses.contains(address) {
+            self.udp.reject(address)
+        } else {
+            self.webrtc.reject(address)
+        }
+    }
+}
+
+#[derive(Clone)]
+struct MultiAuthReceiver {
+    webrtc: Box<dyn AuthReceiver>,
+    udp: Box<dyn AuthReceiver>,
+    udp_addresses: UdpAddresses,
+    last: Option<(SocketAddr, Vec<u8>)>,
+}
+
+impl AuthReceiver for MultiAuthReceiver {
+    fn receive(&mut self) -> Result<Option<(&SocketAddr, &[u8])>, RecvError> {
+        self.last = None;
+
+        if let Some((address, payload)) = self.udp.receive()? {
+            self.udp_addresses.insert(*address);
+            self.last = Some((*address, payload.to_vec()));
+        } else if let Some((address, payload)) = self.webrtc.receive()? {
+            self.last = Some((*address, payload.to_vec()));
+        }
+
+        Ok(self
+            .last
+            .as_ref()
+            .map(|(address, payload)| (address, payload.as_slice())))
+    }
+}
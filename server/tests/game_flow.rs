@@ -0,0 +1,301 @@
+mod harness;
+
+use std::time::Duration;
+
+use naia_bevy_demo_shared::{
+    channels::GameSystemChannel,
+    components::{hand::Hand, timer::Counter, Player, Table},
+    messages::{
+        AcceptPlayCard, AcceptPlayerReady, AcceptStartGame, BackToLobby, GameError, NewPlayer,
+        UpdateTurn, WaitForStart,
+    },
+};
+use tienlen_engine::Phase;
+
+use harness::{seated, Harness};
+
+/// Seats, readies and presses start, the table waits for the deal requests
+fn waiting_for_deal(names: &[&str]) -> Harness {
+    let mut harness = seated(names);
+
+    for client in 0..names.len() {
+        harness.ready(client);
+    }
+    harness.run_until("everyone sees everyone ready", |harness| {
+        harness.clients.iter().all(|client| {
+            client
+                .received::<GameSystemChannel, AcceptPlayerReady>()
+                .len()
+                == names.len()
+        })
+    });
+
+    harness.start(0);
+    harness.run_until("start countdown", |harness| {
+        harness.clients.iter().all(|client| {
+            !client
+                .received::<GameSystemChannel, WaitForStart>()
+                .is_empty()
+        })
+    });
+
+    harness
+}
+
+/// Seats, readies and deals, returns the hands and the first active player
+fn dealt(names: &[&str]) -> (Harness, Vec<Hand>, usize) {
+    let mut harness = waiting_for_deal(names);
+
+    for client in 0..names.len() {
+        harness.request_deal(client);
+    }
+    harness.run_until("cards dealt", |harness| {
+        harness.clients.iter().all(|client| {
+            !client
+                .received::<GameSystemChannel, AcceptStartGame>()
+                .is_empty()
+        })
+    });
+
+    let mut active_player = 0;
+    let hands = harness
+        .clients
+        .iter()
+        .map(|client| {
+            let start = client
+                .received::<GameSystemChannel, AcceptStartGame>()
+                .remove(0);
+            active_player = start.active_player;
+            Hand::from_str(&start.cards)
+        })
+        .collect();
+
+    for client in harness.clients.iter_mut() {
+        client.clear_received();
+    }
+
+    (harness, hands, active_player)
+}
+
+#[test]
+fn test_players_replicated_to_everyone() {
+    let mut harness = seated(&["alice", "bob", "carol"]);
+
+    harness.run_until("three players replicated", |harness| {
+        harness
+            .clients
+            .iter_mut()
+            .all(|client| client.replicated::<Player, _>(|_| ()).len() == 3)
+    });
+
+    for client in harness.clients.iter_mut() {
+        let mut players = client.replicated::<Player, _>(|player| (*player.pos, player.name()));
+        players.sort();
+
+        assert_eq!(
+            players,
+            vec![
+                (0, "alice".to_string()),
+                (1, "bob".to_string()),
+                (2, "carol".to_string()),
+            ]
+        );
+    }
+}
+
+#[test]
+fn test_avatar_replicated_with_player() {
+    let mut harness = Harness::new();
+
+    // One after the other, so the seats are known
+    for avatar in [4, 99] {
+        let client = harness.add_client();
+        harness.send(
+            client,
+            NewPlayer {
+                name: format!("player {}", client),
+                avatar,
+            },
+        );
+        harness.run_until("player replicated", |harness| {
+            harness.clients[0].replicated::<Player, _>(|_| ()).len() == client + 1
+        });
+    }
+
+    let mut avatars =
+        harness.clients[0].replicated::<Player, _>(|player| (*player.pos, *player.avatar));
+    avatars.sort();
+
+    // An avatar the client does not have falls back to the seat's
+    assert_eq!(avatars, vec![(0, 4), (1, 1)]);
+}
+
+#[test]
+fn test_start_needs_everyone_ready() {
+    let mut harness = seated(&["alice", "bob"]);
+
+    harness.ready(0);
+    harness.start(0);
+
+    harness.run_until("start rejected", |harness| {
+        !harness.clients[0].errors().is_empty()
+    });
+
+    assert_eq!(
+        harness.clients[0].errors(),
+        vec![GameError::PlayersNotReady]
+    );
+    assert_eq!(harness.global().engine.phase(), Phase::Lobby);
+}
+
+#[test]
+fn test_deal_gives_everyone_a_hand() {
+    let (mut harness, hands, active_player) = dealt(&["alice", "bob"]);
+
+    assert_eq!(harness.global().engine.phase(), Phase::Playing);
+    assert!(active_player < 2);
+    for hand in hands.iter() {
+        assert_eq!(hand.len(), 13);
+    }
+
+    harness.run_until("table and counter replicated", |harness| {
+        harness.clients.iter_mut().all(|client| {
+            client.replicated::<Table, _>(|_| ()).len() == 1
+                && client.replicated::<Counter, _>(|_| ()).len() == 1
+        })
+    });
+}
+
+#[test]
+fn test_one_player_can_not_deal_alone() {
+    let mut harness = waiting_for_deal(&["alice", "bob"]);
+
+    for _ in 0..3 {
+        harness.request_deal(0);
+    }
+    harness.run_for(Duration::from_millis(500));
+
+    assert_eq!(harness.global().engine.phase(), Phase::Dealing);
+    assert!(harness.clients[0]
+        .received::<GameSystemChannel, AcceptStartGame>()
+        .is_empty());
+}
+
+#[test]
+fn test_play_and_skip() {
+    let (mut harness, mut hands, leader) = dealt(&["alice", "bob"]);
+    let other = 1 - leader;
+
+    let card = hands[leader].smallest_card().to_str();
+    harness.play(leader, &card);
+
+    harness.run_until("play broadcast", |harness| {
+        harness.clients.iter().all(|client| {
+            !client
+                .received::<GameSystemChannel, AcceptPlayCard>()
+                .is_empty()
+        })
+    });
+
+    for client in harness.clients.iter() {
+        let played = client
+            .received::<GameSystemChannel, AcceptPlayCard>()
+            .remove(0);
+
+        assert_eq!(played.cur_player, leader);
+        assert_eq!(played.cards, card);
+        assert_eq!(played.next_player, other);
+        assert!(!played.run_out_card);
+    }
+
+    harness.run_until("table replicated", |harness| {
+        harness.clients.iter_mut().all(|client| {
+            client.replicated::<Table, _>(|table| (*table.cards).clone()) == [card.clone()]
+        })
+    });
+    harness.run_until("hand replicated", |harness| {
+        harness.clients.iter_mut().all(|client| {
+            client
+                .replicated::<Player, _>(|player| {
+                    let cards_left = player.cards.split(',').filter(|c| !c.is_empty()).count();
+                    (*player.pos, cards_left)
+                })
+                .contains(&(leader, 12))
+        })
+    });
+
+    // Not their turn anymore
+    harness.play(leader, &hands[leader].clone().remove(1).to_str());
+    harness.run_until("play rejected", |harness| {
+        !harness.clients[leader].errors().is_empty()
+    });
+    assert_eq!(
+        harness.clients[leader].errors(),
+        vec![GameError::WrongTurn {
+            active_player: other
+        }]
+    );
+
+    // Everybody else passed, the lead goes back
+    harness.skip(other);
+    harness.run_until("lead back to the leader", |harness| {
+        harness.clients.iter().all(|client| {
+            client
+                .received::<GameSystemChannel, UpdateTurn>()
+                .last()
+                .map(|turn| turn.0)
+                == Some(leader)
+        })
+    });
+
+    assert_eq!(harness.global().engine.active_player(), Some(leader));
+    assert!(harness.global().engine.leader_turn());
+}
+
+#[test]
+fn test_next_deal_after_a_player_left() {
+    let (mut harness, _, _) = dealt(&["alice", "bob", "carol"]);
+
+    harness.force_end_match();
+    harness.run_until("match over", |harness| {
+        harness.global().engine.phase() == Phase::Dealing
+    });
+
+    harness.clients[2].disconnect();
+    harness.run_until("carol gone", |harness| {
+        harness.global().users_map.len() == 2
+    });
+    assert!(harness.global().engine.seat(2).is_none());
+
+    for client in 0..2 {
+        harness.request_deal(client);
+    }
+    harness.run_until("cards dealt without carol", |harness| {
+        harness.clients[..2].iter().all(|client| {
+            !client
+                .received::<GameSystemChannel, AcceptStartGame>()
+                .is_empty()
+        })
+    });
+
+    assert_eq!(harness.global().engine.phase(), Phase::Playing);
+}
+
+#[test]
+fn test_back_to_lobby_when_too_few_left() {
+    let (mut harness, _, _) = dealt(&["alice", "bob"]);
+
+    harness.force_end_match();
+    harness.run_until("match over", |harness| {
+        harness.global().engine.phase() == Phase::Dealing
+    });
+
+    harness.clients[1].disconnect();
+    harness.run_until("alice sent back to the lobby", |harness| {
+        !harness.clients[0]
+            .received::<GameSystemChannel, BackToLobby>()
+            .is_empty()
+    });
+
+    assert_eq!(harness.global().engine.phase(), Phase::Lobby);
+}
@@ -0,0 +1,46 @@
+mod harness;
+
+use naia_bevy_demo_shared::{
+    channels::GameSystemChannel,
+    messages::{Auth, IncompatibleVersion},
+    PROTOCOL_VERSION,
+};
+
+use harness::Harness;
+
+#[test]
+fn test_current_client_is_let_in() {
+    let mut harness = Harness::new();
+
+    let client = harness.add_client();
+    harness.join(client, "alice");
+
+    assert!(harness.clients[client]
+        .received::<GameSystemChannel, IncompatibleVersion>()
+        .is_empty());
+    assert_eq!(harness.global().users_map.len(), 1);
+}
+
+#[test]
+fn test_outdated_client_is_told_and_dropped() {
+    let mut harness = Harness::new();
+
+    let mut auth = Auth::new("", "");
+    auth.protocol_version = "0.0.1".to_string();
+    let client = harness.add_client_with(auth);
+
+    harness.run_until("told to update", |harness| {
+        !harness.clients[client]
+            .received::<GameSystemChannel, IncompatibleVersion>()
+            .is_empty()
+    });
+
+    let reason = harness.clients[client]
+        .received::<GameSystemChannel, IncompatibleVersion>()
+        .remove(0);
+    assert_eq!(reason.server_version, PROTOCOL_VERSION);
+
+    harness.run_until("dropped", |harness| !harness.clients[client].is_connected());
+    assert!(harness.global().users_map.is_empty());
+    assert!(harness.global().outdated_users.is_empty());
+}
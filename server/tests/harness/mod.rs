@@ -0,0 +1,319 @@
+//! Boots the server app and headless naia clients in one process.
+//!
+//! The clients talk to the server over the UDP transport on loopback, every
+//! socket gets its own free port so the tests can run in parallel. Nothing runs
+//! on its own: `update` steps every app once, `run_until` keeps stepping until a
+//! condition holds or the test times out.
+
+#![allow(dead_code)]
+
+use std::{
+    net::TcpListener,
+    thread,
+    time::{Duration, Instant},
+};
+
+use bevy_app::App;
+use bevy_core::{FrameCountPlugin, TaskPoolPlugin, TypeRegistrationPlugin};
+use bevy_ecs::{component::Component, event::Events, system::RunSystemOnce};
+use bevy_time::TimePlugin;
+use naia_bevy_client::{
+    events::MessageEvents, transport::udp, Client, ClientConfig, Plugin as ClientPlugin,
+};
+use naia_bevy_demo_shared::{
+    channels::{EntityAssignmentChannel, GameSystemChannel, PlayerActionChannel},
+    messages::{
+        Auth, EntityAssignment, ErrorCode, GameError, NewPlayer, PlayCard, PlayerReady,
+        RequestStart, SkipTurn, StartGame,
+    },
+    protocol_with_tick_interval,
+};
+use naia_shared::{Channel, Message};
+
+use tienlen_server::{build_app, resources::Global, settings::Settings, ForceEndMatch};
+
+/// Longest a `run_until` waits before failing the test
+const TIMEOUT: Duration = Duration::from_secs(10);
+/// Breather between two steps so the sockets and the clocks move on
+const STEP: Duration = Duration::from_millis(1);
+
+/// `127.0.0.1:<port>` with a port nobody listens on right now
+fn free_address() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.local_addr().unwrap().to_string()
+}
+
+/// Default settings on free loopback ports, without admin API and metrics
+pub fn test_settings() -> Settings {
+    let mut settings = Settings::default();
+
+    let network = &mut settings.network;
+    network.signaling_address = free_address();
+    network.webrtc_address = free_address();
+    network.public_address = format!("http://{}", network.webrtc_address);
+    network.udp_auth_address = Some(free_address());
+    network.udp_address = free_address();
+    network.udp_public_address = format!("http://{}", network.udp_address);
+    network.admin_address = None;
+    network.metrics_address = None;
+
+    settings.game.tick_interval_ms = 20;
+    // Long enough that no turn runs out in the middle of a test
+    settings.game.turn_timeout_secs = 600;
+
+    settings
+}
+
+/// Connects one client per name and seats them in that order
+pub fn seated(names: &[&str]) -> Harness {
+    seated_with(Harness::new(), names)
+}
+
+pub fn seated_with(mut harness: Harness, names: &[&str]) -> Harness {
+    for name in names {
+        let client = harness.add_client();
+        harness.join(client, name);
+    }
+
+    harness
+}
+
+/// A client app without window or rendering, keeps every message it receives
+pub struct TestClient {
+    pub app: App,
+    received: Vec<MessageEvents>,
+}
+
+impl TestClient {
+    fn connect(settings: &Settings, auth: Auth) -> Self {
+        let mut app = App::default();
+
+        app.add_plugins((
+            TaskPoolPlugin::default(),
+            TimePlugin::default(),
+            TypeRegistrationPlugin::default(),
+            FrameCountPlugin::default(),
+            ClientPlugin::new(
+                ClientConfig::default(),
+                protocol_with_tick_interval(settings.tick_interval()),
+            ),
+        ));
+        app.finish();
+        app.cleanup();
+
+        let server_address = format!(
+            "http://{}",
+            settings.network.udp_auth_address.as_ref().unwrap()
+        );
+
+        app.world.run_system_once(move |mut client: Client| {
+            client.auth(Auth {
+                username: auth.username.clone(),
+                password: auth.password.clone(),
+                protocol_version: auth.protocol_version.clone(),
+            });
+            let socket = udp::Socket::new(&server_address, client.socket_config());
+            client.connect(socket);
+        });
+
+        Self {
+            app,
+            received: vec![],
+        }
+    }
+
+    fn update(&mut self) {
+        self.app.update();
+
+        let mut events = self.app.world.resource_mut::<Events<MessageEvents>>();
+        self.received.extend(events.drain());
+    }
+
+    pub fn is_connected(&mut self) -> bool {
+        self.app
+            .world
+            .run_system_once(|client: Client| client.is_connected())
+    }
+
+    pub fn send<M: Message>(&mut self, message: M) {
+        self.app.world.run_system_once(move |mut client: Client| {
+            client.send_message::<PlayerActionChannel, M>(&message);
+        });
+    }
+
+    /// Leaves the way a closed game does
+    pub fn disconnect(&mut self) {
+        self.app
+            .world
+            .run_system_once(|mut client: Client| client.disconnect());
+    }
+
+    /// Every `M` received on channel `C` so far, oldest first
+    pub fn received<C: Channel, M: Message>(&self) -> Vec<M> {
+        self.received
+            .iter()
+            .flat_map(|events| events.read::<C, M>())
+            .collect()
+    }
+
+    /// Every error the server sent back so far, oldest first
+    pub fn errors(&self) -> Vec<GameError> {
+        self.received::<GameSystemChannel, ErrorCode>()
+            .iter()
+            .map(GameError::from)
+            .collect()
+    }
+
+    pub fn clear_received(&mut self) {
+        self.received.clear();
+    }
+
+    /// Maps every replicated `C` this client sees
+    pub fn replicated<C: Component, R>(&mut self, map: impl Fn(&C) -> R) -> Vec<R> {
+        self.app
+            .world
+            .query::<&C>()
+            .iter(&self.app.world)
+            .map(map)
+            .collect()
+    }
+}
+
+/// One server and the clients connected to it
+pub struct Harness {
+    pub server: App,
+    pub clients: Vec<TestClient>,
+    settings: Settings,
+}
+
+impl Default for Harness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Harness {
+    pub fn new() -> Self {
+        Self::with_settings(test_settings())
+    }
+
+    pub fn with_settings(settings: Settings) -> Self {
+        settings.validate().unwrap();
+
+        let mut server = build_app(settings.clone());
+        server.finish();
+        server.cleanup();
+        // Runs the startup systems, the server listens after that
+        server.update();
+
+        Self {
+            server,
+            clients: vec![],
+            settings,
+        }
+    }
+
+    /// Connects one more client and waits for the handshake, returns its index
+    pub fn add_client(&mut self) -> usize {
+        let index = self.add_client_with(Auth::new(
+            &self.settings.auth.user_name,
+            &self.settings.auth.password,
+        ));
+
+        self.run_until("client connected", |harness| {
+            harness.clients[index].is_connected()
+        });
+
+        index
+    }
+
+    /// Starts connecting a client with a hand made auth message, does not wait
+    pub fn add_client_with(&mut self, auth: Auth) -> usize {
+        self.clients.push(TestClient::connect(&self.settings, auth));
+        self.clients.len() - 1
+    }
+
+    /// Steps the server and every client once
+    pub fn update(&mut self) {
+        self.server.update();
+        for client in self.clients.iter_mut() {
+            client.update();
+        }
+        thread::sleep(STEP);
+    }
+
+    /// Steps until `condition` holds, panics with `what` after `TIMEOUT`
+    pub fn run_until(&mut self, what: &str, mut condition: impl FnMut(&mut Self) -> bool) {
+        let started = Instant::now();
+
+        while !condition(self) {
+            if started.elapsed() > TIMEOUT {
+                panic!("timed out waiting for: {}", what);
+            }
+            self.update();
+        }
+    }
+
+    /// Steps for a while, for checking that something does NOT happen
+    pub fn run_for(&mut self, duration: Duration) {
+        let started = Instant::now();
+
+        while started.elapsed() < duration {
+            self.update();
+        }
+    }
+
+    pub fn global(&self) -> &Global {
+        self.server.world.resource::<Global>()
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    pub fn send<M: Message>(&mut self, client: usize, message: M) {
+        self.clients[client].send(message);
+    }
+
+    /// Takes a seat and waits for the server to assign the player entity
+    pub fn join(&mut self, client: usize, name: &str) {
+        self.send(
+            client,
+            NewPlayer {
+                name: name.to_string(),
+                avatar: 0,
+            },
+        );
+        self.run_until("player entity assigned", |harness| {
+            !harness.clients[client]
+                .received::<EntityAssignmentChannel, EntityAssignment>()
+                .is_empty()
+        });
+    }
+
+    pub fn ready(&mut self, client: usize) {
+        self.send(client, PlayerReady);
+    }
+
+    pub fn start(&mut self, client: usize) {
+        self.send(client, StartGame::default());
+    }
+
+    /// What the client sends once the start countdown is over
+    pub fn request_deal(&mut self, client: usize) {
+        self.send(client, RequestStart);
+    }
+
+    pub fn play(&mut self, client: usize, cards: &str) {
+        self.send(client, PlayCard(cards.to_string()));
+    }
+
+    pub fn skip(&mut self, client: usize) {
+        self.send(client, SkipTurn);
+    }
+
+    /// What the admin API does to end a match
+    pub fn force_end_match(&mut self) {
+        self.server.world.send_event(ForceEndMatch);
+    }
+}
@@ -0,0 +1,114 @@
+mod harness;
+
+use naia_bevy_demo_shared::{
+    components::{Host, Player, TableOptions},
+    messages::{GameError, KickPlayer, SetCardTracker, TakeSeat, TransferHost},
+};
+
+use harness::{seated, seated_with, test_settings, Harness};
+
+fn host_pos(harness: &mut Harness) -> Option<usize> {
+    let world = &mut harness.server.world;
+    world
+        .query::<(&Player, &Host)>()
+        .iter(world)
+        .map(|(player, _)| *player.pos)
+        .next()
+}
+
+#[test]
+fn test_take_an_empty_seat() {
+    let mut harness = seated(&["alice", "bob"]);
+
+    harness.send(1, TakeSeat(3));
+    harness.run_until("bob moved", |harness| {
+        harness.clients[0]
+            .replicated::<Player, _>(|player| *player.pos)
+            .contains(&3)
+    });
+
+    harness.send(0, TakeSeat(3));
+    harness.run_until("seat taken", |harness| {
+        harness.clients[0].errors() == vec![GameError::SeatTaken]
+    });
+
+    assert!(harness.global().engine.seat(1).is_none());
+    assert_eq!(host_pos(&mut harness), Some(0));
+}
+
+#[test]
+fn test_only_the_host_kicks_and_hands_over() {
+    let mut harness = seated(&["alice", "bob", "carol"]);
+
+    harness.send(1, KickPlayer(2));
+    harness.run_until("bob is no host", |harness| {
+        harness.clients[1].errors() == vec![GameError::NotHost]
+    });
+
+    harness.send(0, TransferHost(1));
+    harness.run_until("bob is the host", |harness| host_pos(harness) == Some(1));
+
+    harness.send(1, KickPlayer(2));
+    harness.run_until("carol kicked", |harness| {
+        !harness.clients[2].is_connected() && harness.global().users_map.len() == 2
+    });
+
+    assert!(harness.global().engine.seat(2).is_none());
+}
+
+#[test]
+fn test_host_moves_on_when_the_host_leaves() {
+    let mut harness = seated(&["alice", "bob", "carol"]);
+
+    harness.send(1, TakeSeat(3));
+    harness.run_until("bob moved", |harness| {
+        harness.global().engine.seat(3).is_some()
+    });
+
+    harness.clients[0].disconnect();
+    harness.run_until("alice gone", |harness| {
+        harness.global().users_map.len() == 2
+    });
+
+    // Carol sits at 2, before bob
+    harness.run_until("carol is the host", |harness| host_pos(harness) == Some(2));
+    assert!(harness.global().engine.seat(0).is_none());
+}
+
+#[test]
+fn test_host_turns_the_card_tracker_on() {
+    let mut harness = seated(&["alice", "bob"]);
+
+    harness.send(1, SetCardTracker(true));
+    harness.run_until("bob is no host", |harness| {
+        harness.clients[1].errors() == vec![GameError::NotHost]
+    });
+
+    harness.send(0, SetCardTracker(true));
+    harness.run_until("tracker on everywhere", |harness| {
+        harness.clients.iter_mut().all(|client| {
+            client.replicated::<TableOptions, _>(|options| *options.card_tracker) == [true]
+        })
+    });
+}
+
+#[test]
+fn test_no_card_tracker_at_a_ranked_table() {
+    let mut settings = test_settings();
+    settings.rules.ranked = true;
+    let mut harness = seated_with(Harness::with_settings(settings), &["alice"]);
+
+    harness.run_until("options replicated", |harness| {
+        harness.clients[0].replicated::<TableOptions, _>(|options| *options.ranked) == [true]
+    });
+
+    harness.send(0, SetCardTracker(true));
+    harness.run_until("tracker refused", |harness| {
+        harness.clients[0].errors() == vec![GameError::RankedTable]
+    });
+
+    assert_eq!(
+        harness.clients[0].replicated::<TableOptions, _>(|options| *options.card_tracker),
+        [false]
+    );
+}
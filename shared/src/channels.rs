@@ -20,6 +20,7 @@ pub struct ChannelsPlugin;
 
 impl ProtocolPlugin for ChannelsPlugin {
     fn build(&self, protocol: &mut Protocol) {
+        // GameSystemChannel stays first, old clients read IncompatibleVersion on it
         protocol
             .add_channel::<GameSystemChannel>(
                 ChannelDirection::ServerToClient,
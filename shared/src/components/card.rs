@@ -54,6 +54,19 @@ impl Card {
         cmp_rank_result
     }
 
+    pub fn cmp_suit_then_desc_rank(&self, other: &Card) -> Ordering {
+        self.suit
+            .cmp(&other.suit)
+            .then_with(|| other.rank.cmp(&self.rank))
+    }
+
+    pub fn cmp_desc_rank_then_suit(&self, other: &Card) -> Ordering {
+        other
+            .rank
+            .cmp(&self.rank)
+            .then_with(|| self.suit.cmp(&other.suit))
+    }
+
     #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Result<Card, &'static str> {
         if s.len() != 2 {
@@ -78,38 +91,11 @@ impl Card {
         format!("{}{}", self.rank.to_char(), self.suit.to_char())
     }
 
-    pub fn to_path(&self) -> String {
-        let rank = self.rank;
-        let suit = self.suit;
-
-        let mut path = "cards/standard/solitaire/individuals".to_string();
-
-        match suit {
-            Suit::Hearts => path.push_str("/heart"),
-            Suit::Diamonds => path.push_str("/diamond"),
-            Suit::Clubs => path.push_str("/club"),
-            Suit::Spades => path.push_str("/spade"),
-        }
-
-        match rank {
-            Rank::Two => path.push_str("/2"),
-            Rank::Three => path.push_str("/3"),
-            Rank::Four => path.push_str("/4"),
-            Rank::Five => path.push_str("/5"),
-            Rank::Six => path.push_str("/6"),
-            Rank::Seven => path.push_str("/7"),
-            Rank::Eight => path.push_str("/8"),
-            Rank::Nine => path.push_str("/9"),
-            Rank::Ten => path.push_str("/10"),
-            Rank::Jack => path.push_str("/11"),
-            Rank::Queen => path.push_str("/12"),
-            Rank::King => path.push_str("/13"),
-            Rank::Ace => path.push_str("/1"),
-        }
-
-        path.push_str(".png");
-
-        path
+    /// Fills `{suit}` and `{rank}` in the path `pattern` of a card face
+    pub fn asset_path(&self, pattern: &str) -> String {
+        pattern
+            .replace("{suit}", &self.suit.get_asset_path())
+            .replace("{rank}", &self.rank.get_asset_path())
     }
 
     pub fn name(&self) -> String {
@@ -361,3 +347,14 @@ impl Card {
         Card::all_cards().into_iter()
     }
 }
+
+#[test]
+fn test_asset_path() {
+    let card = Card::from_str("TS").unwrap();
+
+    assert_eq!(
+        card.asset_path("faces/{suit}/{rank}.png"),
+        "faces/spade/10.png"
+    );
+    assert_eq!(card.asset_path("faces/all.png"), "faces/all.png");
+}
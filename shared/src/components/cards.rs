@@ -1,3 +1,4 @@
+use naia_bevy_shared::Serde;
 use rand;
 use std::cmp::Ordering;
 
@@ -112,23 +113,107 @@ fn test_is_sequence() {
     assert!(!is_sequences(non_seq_hand.cards()));
 }
 
+#[test]
+fn test_sort_descending() {
+    let mut cards = Hand::from_strings(&["3D", "2S", "AH", "3H", "KS"]).cards;
+
+    sort_descending_rank_suit(&mut cards);
+    assert_eq!(
+        cards,
+        Hand::from_strings(&["2S", "AH", "KS", "3D", "3H"]).cards
+    );
+
+    sort_suit_descending_rank(&mut cards);
+    assert_eq!(
+        cards,
+        Hand::from_strings(&["2S", "KS", "3D", "AH", "3H"]).cards
+    );
+}
+
+#[test]
+fn test_sort_by_combination() {
+    let mut cards =
+        Hand::from_strings(&["9C", "3D", "5H", "2S", "KS", "4C", "9S", "KH", "KD", "JD"]).cards;
+
+    sort_by_combination(&mut cards);
+
+    assert_eq!(
+        cards,
+        Hand::from_strings(&["3D", "4C", "5H", "KS", "KD", "KH", "9S", "9C", "JD", "2S"]).cards
+    );
+}
+
 /// Sorts the slice by suit then rank (low to high)
 fn sort_suit_ascending_rank(cards: &mut [Card]) {
     cards.sort_by(|a, b| a.cmp_rank_suit(b));
 }
 
-/// Sorts the slice by rank(high to low) and then suit
-fn sort_suit_descending_rank(_cards: &mut [Card]) {
-    // Reverse sort (since default is low to high)
-    // cards.sort_by(|a, b| a.cmp_suit_then_desc_rank(b));
-    todo!()
+/// Sorts the slice by suit and then rank (high to low)
+fn sort_suit_descending_rank(cards: &mut [Card]) {
+    cards.sort_by(|a, b| a.cmp_suit_then_desc_rank(b));
 }
 
 /// Sorts the slice by rank(high to low) and then suit
-fn sort_descending_rank_suit(_cards: &mut [Card]) {
-    // Reverse sort (since default is low to high)
-    todo!()
-    // cards.sort_by(|a, b| a.cmp_desc_rank_then_suit(b));
+fn sort_descending_rank_suit(cards: &mut [Card]) {
+    cards.sort_by(|a, b| a.cmp_desc_rank_then_suit(b));
+}
+
+/// Orders the slice so the combinations sit together: runs first, then the
+/// quartets, triples and pairs, then the cards left over. A card only joins a
+/// run when no other card has its rank.
+fn sort_by_combination(cards: &mut [Card]) {
+    let mut sorted = cards.to_vec();
+    sort_suit_ascending_rank(&mut sorted);
+
+    let mut same_rank: Vec<Vec<Card>> = vec![];
+
+    for card in sorted {
+        match same_rank.last_mut() {
+            Some(set) if set[0].rank == card.rank => set.push(card),
+            _ => same_rank.push(vec![card]),
+        }
+    }
+
+    let (mut sets, singles): (Vec<_>, Vec<_>) =
+        same_rank.into_iter().partition(|set| set.len() > 1);
+    // Bigger sets first, stable so they stay low to high
+    sets.sort_by(|a, b| b.len().cmp(&a.len()));
+
+    let mut runs: Vec<Card> = vec![];
+    let mut left: Vec<Card> = vec![];
+    let mut chain: Vec<Card> = vec![];
+
+    for card in singles.into_iter().flatten() {
+        let extends = chain.last().is_some_and(|last| {
+            card.rank != Rank::Two && card.rank.ordinal() == last.rank.ordinal() + 1
+        });
+
+        if !extends {
+            flush_chain(&mut chain, &mut runs, &mut left);
+        }
+        chain.push(card);
+    }
+    flush_chain(&mut chain, &mut runs, &mut left);
+
+    let ordered = runs
+        .into_iter()
+        .chain(sets.into_iter().flatten())
+        .chain(left);
+
+    for (slot, card) in cards.iter_mut().zip(ordered) {
+        *slot = card;
+    }
+}
+
+/// A chain of three or more goes to the runs, a shorter one is left over
+fn flush_chain(chain: &mut Vec<Card>, runs: &mut Vec<Card>, left: &mut Vec<Card>) {
+    let is_run = chain.len() >= 3 && chain.iter().all(|card| card.rank != Rank::Two);
+
+    if is_run {
+        runs.append(chain);
+    } else {
+        left.append(chain);
+    }
 }
 
 /// Returns cards of the specified rank
@@ -148,7 +233,7 @@ pub fn shuffle(cards: &mut [Card]) {
     cards.shuffle(&mut rng)
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Serde, Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum ThirteenCombination {
     Single,
     Pair,
@@ -156,6 +241,7 @@ pub enum ThirteenCombination {
     Quartet,
     Sequence,
     DoubleSequence,
+    #[default]
     NonSense,
 }
 
@@ -324,4 +410,9 @@ pub trait Cards {
     fn sort_descending_rank_suit(&mut self) {
         sort_descending_rank_suit(self.mut_cards());
     }
+
+    /// Puts the runs, quartets, triples and pairs next to each other
+    fn sort_by_combination(&mut self) {
+        sort_by_combination(self.mut_cards());
+    }
 }
@@ -9,7 +9,7 @@ use naia_bevy_shared::Property;
 use super::card::Card;
 use super::cards::Cards;
 
-#[derive(Clone, Component, PartialEq, Eq, Default)]
+#[derive(Clone, Component, PartialEq, Eq, Default, Debug)]
 pub struct Hand {
     pub cards: Vec<Card>,
 }
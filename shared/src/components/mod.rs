@@ -24,7 +24,7 @@ pub use shape::{Shape, ShapeValue};
 
 pub use {
     player::{Active, Host},
-    table::Table,
+    table::{Table, TableOptions},
     timer::Counter,
 };
 
@@ -41,6 +41,7 @@ impl ProtocolPlugin for ComponentsPlugin {
             .add_component::<Active>()
             .add_component::<Table>()
             .add_component::<Counter>()
-            .add_component::<Host>();
+            .add_component::<Host>()
+            .add_component::<TableOptions>();
     }
 }
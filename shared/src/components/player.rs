@@ -4,6 +4,9 @@ use naia_bevy_shared::{Property, Replicate};
 #[derive(Component, Replicate)]
 pub struct Host;
 
+/// Avatars to pick from, `avatars/c0.png` and up in the client assets
+pub const AVATAR_COUNT: usize = 6;
+
 #[derive(Component, Replicate)]
 pub struct Player {
     pub pos: Property<usize>,
@@ -12,16 +15,30 @@ pub struct Player {
     pub name: Property<String>,
     pub ready: Property<bool>,
     pub cards: Property<String>,
+    pub avatar: Property<usize>,
+    /// Matches played and won since joining
+    pub matches: Property<u32>,
+    pub wins: Property<u32>,
 }
 
 impl Player {
-    pub fn new(pos: usize, name: &str) -> Self {
+    pub fn new(pos: usize, name: &str, avatar: usize) -> Self {
         let mut active = false;
         if pos == 0 {
             active = true;
         }
 
-        Self::new_complete(pos, active, 0, name.to_string(), false, String::new())
+        Self::new_complete(
+            pos,
+            active,
+            0,
+            name.to_string(),
+            false,
+            String::new(),
+            avatar,
+            0,
+            0,
+        )
     }
 
     pub fn name(&self) -> String {
@@ -32,6 +49,11 @@ impl Player {
         self.cards.clone().to_string()
     }
 
+    /// Share of the matches won, `None` before the first one ends
+    pub fn win_rate(&self) -> Option<f32> {
+        (*self.matches > 0).then(|| *self.wins as f32 / *self.matches as f32)
+    }
+
     pub fn update_cards(&mut self, cards: &str) {
         *self.cards = cards.to_string();
     }
@@ -1,23 +1,121 @@
 use bevy_ecs::prelude::Component;
-use naia_bevy_shared::{Property, Replicate};
+use naia_bevy_shared::{Property, Replicate, Serde};
+use serde::Serialize;
+
+use super::hand::Hand;
+
+/// Where the table is in its life cycle
+#[derive(Serde, Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    /// Players are joining, nobody pressed start yet
+    #[default]
+    Lobby,
+    /// Waiting for every player to request the next deal
+    Dealing,
+    Playing,
+}
 
 #[derive(Replicate, Component)]
 pub struct Table {
     pub cards: Property<String>,
+    /// Every move since the lead was last free, see `Table::round`
+    pub round: Property<String>,
 }
 
 impl Default for Table {
     fn default() -> Self {
-        Self::new_complete("".to_string())
+        Self::new_complete("".to_string(), "".to_string())
+    }
+}
+
+/// What the host set up for the table, there from the lobby on
+#[derive(Replicate, Component)]
+pub struct TableOptions {
+    /// Set by the server config, no aids at a ranked table
+    pub ranked: Property<bool>,
+    /// Everyone sees which cards were played this match
+    pub card_tracker: Property<bool>,
+}
+
+impl TableOptions {
+    pub fn new(ranked: bool) -> Self {
+        Self::new_complete(ranked, false)
     }
 }
 
+/// One move of a round, `cards` is `None` when the player passed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Trick {
+    pub pos: usize,
+    pub cards: Option<Hand>,
+}
+
 impl Table {
     pub fn new(card_str: String) -> Self {
-        Self::new_complete(card_str)
+        Self::new_complete(card_str, "".to_string())
     }
 
     pub fn new_match(&mut self) {
         *self.cards = "".to_string();
+        *self.round = "".to_string();
+    }
+
+    pub fn round(&self) -> Vec<Trick> {
+        decode_round(&self.round)
+    }
+
+    pub fn set_round(&mut self, round: &[Trick]) {
+        *self.round = encode_round(round);
     }
 }
+
+/// `pos:cards` for each move, `;` in between, a pass has no cards
+pub fn encode_round(round: &[Trick]) -> String {
+    round
+        .iter()
+        .map(|trick| match &trick.cards {
+            Some(cards) => format!("{}:{}", trick.pos, cards),
+            None => format!("{}:", trick.pos),
+        })
+        .collect::<Vec<String>>()
+        .join(";")
+}
+
+pub fn decode_round(round: &str) -> Vec<Trick> {
+    round
+        .split(';')
+        .filter_map(|trick| {
+            let (pos, cards) = trick.split_once(':')?;
+
+            Some(Trick {
+                pos: pos.parse().ok()?,
+                cards: (!cards.is_empty()).then(|| Hand::from_str(cards)),
+            })
+        })
+        .collect()
+}
+
+#[test]
+fn test_round_encoding() {
+    let round = vec![
+        Trick {
+            pos: 0,
+            cards: Some(Hand::from_str("3C,3D")),
+        },
+        Trick {
+            pos: 1,
+            cards: None,
+        },
+        Trick {
+            pos: 2,
+            cards: Some(Hand::from_str("2H,2S")),
+        },
+    ];
+
+    let encoded = encode_round(&round);
+
+    assert_eq!(encoded, "0:3C,3D;1:;2:2H,2S");
+    assert_eq!(decode_round(&encoded), round);
+    assert_eq!(decode_round(""), vec![]);
+}
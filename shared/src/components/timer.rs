@@ -32,6 +32,10 @@ impl Counter {
         *self.counter = TIMEOUT;
     }
 
+    pub fn reset(&mut self, timeout: f32) {
+        *self.counter = timeout;
+    }
+
     pub fn as_string(&self) -> String {
         self.counter.floor().to_string()
     }
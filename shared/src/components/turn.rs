@@ -10,6 +10,8 @@ pub struct Turn {
     players_out: HashSet<usize>,
     total_player: usize,
     leader_turn: usize,
+    /// Positions nobody sits at, they never get a turn
+    empty_seats: HashSet<usize>,
 }
 
 impl Turn {
@@ -26,13 +28,27 @@ impl Turn {
             total_player,
             players_out,
             leader_turn: 0,
+            empty_seats: HashSet::new(),
         }
     }
 
-    pub fn next_score(&self) -> u32 {
-        let player_left = self.total_player - self.players_out.len();
+    /// A table of `total_player` seats, some of them left empty
+    pub fn with_empty_seats(total_player: usize, empty_seats: HashSet<usize>) -> Self {
+        let mut turn = Self {
+            total_player,
+            empty_seats,
+            ..Default::default()
+        };
+        turn.new_match();
+        turn
+    }
+
+    fn players_left(&self) -> usize {
+        self.total_player - self.empty_seats.len() - self.players_out.len()
+    }
 
-        let next_score = match player_left {
+    pub fn next_score(&self) -> u32 {
+        let next_score = match self.players_left() {
             4 => 3,
             3 => 2,
             2 => 1,
@@ -54,12 +70,14 @@ impl Turn {
         self.players_out.clear();
 
         for i in 0..self.total_player {
-            self.pool.push_back(i);
+            if !self.empty_seats.contains(&i) {
+                self.pool.push_back(i);
+            }
         }
     }
 
     pub fn only_one_player_left(&self) -> bool {
-        (self.total_player - self.players_out.len()) == 1
+        self.players_left() == 1
     }
 
     pub fn new_player_join(&mut self) {
@@ -71,7 +89,7 @@ impl Turn {
         self.total_player += 1;
     }
 
-    pub fn current_active_player(&mut self) -> Option<usize> {
+    pub fn current_active_player(&self) -> Option<usize> {
         self.pool.front().copied()
     }
 
@@ -83,13 +101,27 @@ impl Turn {
 
     pub fn next_turn(&mut self) -> Option<usize> {
         self.make_move();
+
+        // Everybody else still holding cards passed already, the one who
+        // played leads the next round
+        if self.pool.len() == 1 && self.players_left() > 1 {
+            self.refill();
+        }
+
         self.current_active_player()
     }
 
     pub fn player_out(&mut self) -> usize {
         let player_pos = self.pool.pop_front().unwrap();
+        self.leader_turn = player_pos;
         self.players_out.insert(player_pos);
 
+        // The last one in the round ran out, the next one still holding
+        // cards takes the turn
+        if self.pool.is_empty() {
+            self.pool.push_back(self.next_in_play(player_pos));
+        }
+
         self.debug();
 
         self.current_active_player().unwrap()
@@ -99,7 +131,7 @@ impl Turn {
         // FIXME: crazy hack here!!!
         let mut leader_turn = false;
 
-        let player_left = self.total_player - self.players_out.len();
+        let player_left = self.players_left();
 
         if player_left == 1 {
             return (leader_turn, self.current_active_player());
@@ -107,20 +139,41 @@ impl Turn {
 
         self.pool.pop_front().unwrap();
 
+        // Nobody beat the cards a player ran out with, the next one still
+        // holding cards leads
+        if self.pool.is_empty() {
+            self.pool.push_back(self.next_in_play(self.leader_turn));
+        }
+
         if self.pool.len() == 1 {
             leader_turn = true;
+            self.refill();
+        }
 
-            let mut last = (*self.pool.back().unwrap() + 1) % self.total_player;
+        (leader_turn, self.current_active_player())
+    }
+
+    /// Puts everybody still holding cards back in the pool, in seat order
+    /// after the one left in it
+    fn refill(&mut self) {
+        let player_left = self.players_left();
+        let mut last = (*self.pool.back().unwrap() + 1) % self.total_player;
 
-            while self.pool.len() != player_left {
-                if !self.players_out.contains(&last) {
-                    self.pool.push_back(last);
-                }
-                last = (last + 1) % self.total_player;
+        while self.pool.len() != player_left {
+            if !self.players_out.contains(&last) && !self.empty_seats.contains(&last) {
+                self.pool.push_back(last);
             }
+            last = (last + 1) % self.total_player;
         }
+    }
 
-        (leader_turn, self.current_active_player())
+    /// First seat after `pos` of a player still holding cards
+    fn next_in_play(&self, pos: usize) -> usize {
+        let mut next = (pos + 1) % self.total_player;
+        while self.players_out.contains(&next) || self.empty_seats.contains(&next) {
+            next = (next + 1) % self.total_player;
+        }
+        next
     }
 
     pub fn calculate_turn(&mut self, first_player_pos: usize) {
@@ -3,7 +3,8 @@ pub mod channels;
 pub mod components;
 pub mod messages;
 
-pub mod env;
 mod protocol;
 
-pub use protocol::protocol;
+pub use protocol::{
+    is_compatible_version, protocol, protocol_with_tick_interval, PROTOCOL_VERSION,
+};
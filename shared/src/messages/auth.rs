@@ -1,9 +1,16 @@
 use naia_bevy_shared::Message;
 
+use crate::PROTOCOL_VERSION;
+
+/// First thing every client sends, the server checks the protocol version
+/// before it looks at the credentials. Clients from before
+/// `protocol_version` (0.1) send an Auth the server can't decode, so they
+/// can't be told to update
 #[derive(Message)]
 pub struct Auth {
     pub username: String,
     pub password: String,
+    pub protocol_version: String,
 }
 
 impl Auth {
@@ -11,6 +18,13 @@ impl Auth {
         Self {
             username: username.to_string(),
             password: password.to_string(),
+            protocol_version: PROTOCOL_VERSION.to_string(),
         }
     }
 }
+
+/// Sent right before the server drops a client speaking another protocol
+#[derive(Message, Debug, Default)]
+pub struct IncompatibleVersion {
+    pub server_version: String,
+}
@@ -1,38 +1,212 @@
-use naia_bevy_shared::Message;
+use naia_bevy_shared::{Message, Serde};
 
+use crate::components::{cards::ThirteenCombination, hand::Hand, table::Phase};
+
+/// A [`GameError`] on the wire, the fields that do not apply to `kind` keep
+/// their default
 #[derive(Message, Debug, Default)]
 pub struct ErrorCode {
-    code: usize,
+    kind: ErrorKind,
+    /// The cards to beat or the cards missing from the hand
+    cards: String,
+    combination: ThirteenCombination,
+    len: usize,
+    active_player: usize,
+    needed: usize,
+    phase: Phase,
 }
 
-pub enum GameError {
+/// Which [`GameError`] an [`ErrorCode`] carries
+#[derive(Serde, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ErrorKind {
+    #[default]
     InvalidCards,
     WrongTurn,
     WrongCombination,
-    UnknownError,
+    MissingCards,
+    CanNotSkipTurn,
+    NotEnoughPlayers,
+    PlayersNotReady,
+    TableFull,
+    WrongPhase,
+    SeatTaken,
+    NotHost,
+    NoSeat,
+    RankedTable,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameError {
+    /// The cards do not beat `to_beat`, the last hand on the table
+    InvalidCards {
+        to_beat: Hand,
+    },
+    /// Only `active_player` can move now
+    WrongTurn {
+        active_player: usize,
+    },
+    /// `expected` is the kind and number of cards to follow with, `None` when
+    /// the player leads and the cards just do not make a combination
+    WrongCombination {
+        expected: Option<(ThirteenCombination, usize)>,
+    },
+    /// The player does not hold these cards
+    MissingCards {
+        cards: Hand,
+    },
     CanNotSkipTurn,
+    /// Starting needs `needed` players at the table
+    NotEnoughPlayers {
+        needed: usize,
+    },
+    PlayersNotReady,
+    TableFull,
+    /// The action does not fit `phase`, the current phase of the table
+    WrongPhase {
+        phase: Phase,
+    },
+    /// Somebody already sits there
+    SeatTaken,
+    /// Only the host can do that
+    NotHost,
+    /// Nobody sits there, or the seat does not exist
+    NoSeat,
+    /// Not allowed at a ranked table
+    RankedTable,
+}
+
+impl GameError {
+    /// Short snake case name, handy for logs and metrics labels
+    pub fn kind(&self) -> &'static str {
+        match self {
+            GameError::InvalidCards { .. } => "invalid_cards",
+            GameError::WrongTurn { .. } => "wrong_turn",
+            GameError::WrongCombination { .. } => "wrong_combination",
+            GameError::MissingCards { .. } => "missing_cards",
+            GameError::CanNotSkipTurn => "can_not_skip_turn",
+            GameError::NotEnoughPlayers { .. } => "not_enough_players",
+            GameError::PlayersNotReady => "players_not_ready",
+            GameError::TableFull => "table_full",
+            GameError::WrongPhase { .. } => "wrong_phase",
+            GameError::SeatTaken => "seat_taken",
+            GameError::NotHost => "not_host",
+            GameError::NoSeat => "no_seat",
+            GameError::RankedTable => "ranked_table",
+        }
+    }
+
+    /// The cards worth pointing at: the ones to beat or the missing ones
+    pub fn cards(&self) -> Option<&Hand> {
+        match self {
+            GameError::InvalidCards { to_beat } => Some(to_beat),
+            GameError::MissingCards { cards } => Some(cards),
+            _ => None,
+        }
+    }
+}
+
+impl From<&GameError> for ErrorKind {
+    fn from(game_error: &GameError) -> Self {
+        match game_error {
+            GameError::InvalidCards { .. } => Self::InvalidCards,
+            GameError::WrongTurn { .. } => Self::WrongTurn,
+            GameError::WrongCombination { .. } => Self::WrongCombination,
+            GameError::MissingCards { .. } => Self::MissingCards,
+            GameError::CanNotSkipTurn => Self::CanNotSkipTurn,
+            GameError::NotEnoughPlayers { .. } => Self::NotEnoughPlayers,
+            GameError::PlayersNotReady => Self::PlayersNotReady,
+            GameError::TableFull => Self::TableFull,
+            GameError::WrongPhase { .. } => Self::WrongPhase,
+            GameError::SeatTaken => Self::SeatTaken,
+            GameError::NotHost => Self::NotHost,
+            GameError::NoSeat => Self::NoSeat,
+            GameError::RankedTable => Self::RankedTable,
+        }
+    }
 }
 
 impl From<GameError> for ErrorCode {
     fn from(game_error: GameError) -> Self {
+        let mut error_code = Self {
+            kind: ErrorKind::from(&game_error),
+            ..Default::default()
+        };
+
         match game_error {
-            GameError::InvalidCards => Self { code: 0 },
-            GameError::WrongCombination => Self { code: 1 },
-            GameError::WrongTurn => Self { code: 2 },
-            GameError::CanNotSkipTurn => Self { code: 3 },
-            GameError::UnknownError => todo!(),
+            GameError::InvalidCards { to_beat: cards } | GameError::MissingCards { cards } => {
+                error_code.cards = format!("{}", cards);
+            }
+            GameError::WrongTurn { active_player } => error_code.active_player = active_player,
+            GameError::WrongCombination {
+                expected: Some((combination, len)),
+            } => {
+                error_code.combination = combination;
+                error_code.len = len;
+            }
+            GameError::NotEnoughPlayers { needed } => error_code.needed = needed,
+            GameError::WrongPhase { phase } => error_code.phase = phase,
+            _ => {}
         }
+
+        error_code
     }
 }
 
-impl From<ErrorCode> for GameError {
-    fn from(error_code: ErrorCode) -> Self {
-        match error_code.code {
-            0 => Self::InvalidCards,
-            1 => Self::WrongCombination,
-            2 => Self::WrongTurn,
-            3 => Self::CanNotSkipTurn,
-            _ => Self::UnknownError,
+impl From<&ErrorCode> for GameError {
+    fn from(error_code: &ErrorCode) -> Self {
+        match error_code.kind {
+            ErrorKind::InvalidCards => Self::InvalidCards {
+                to_beat: Hand::from(error_code.cards.clone()),
+            },
+            ErrorKind::WrongTurn => Self::WrongTurn {
+                active_player: error_code.active_player,
+            },
+            // A zero length never goes out with an expected combination
+            ErrorKind::WrongCombination => Self::WrongCombination {
+                expected: (error_code.len > 0).then_some((error_code.combination, error_code.len)),
+            },
+            ErrorKind::MissingCards => Self::MissingCards {
+                cards: Hand::from(error_code.cards.clone()),
+            },
+            ErrorKind::CanNotSkipTurn => Self::CanNotSkipTurn,
+            ErrorKind::NotEnoughPlayers => Self::NotEnoughPlayers {
+                needed: error_code.needed,
+            },
+            ErrorKind::PlayersNotReady => Self::PlayersNotReady,
+            ErrorKind::TableFull => Self::TableFull,
+            ErrorKind::WrongPhase => Self::WrongPhase {
+                phase: error_code.phase,
+            },
+            ErrorKind::SeatTaken => Self::SeatTaken,
+            ErrorKind::NotHost => Self::NotHost,
+            ErrorKind::NoSeat => Self::NoSeat,
+            ErrorKind::RankedTable => Self::RankedTable,
         }
     }
 }
+
+#[test]
+fn test_error_code_round_trip() {
+    let errors = [
+        GameError::InvalidCards {
+            to_beat: Hand::from_str("9S,9H"),
+        },
+        GameError::WrongTurn { active_player: 2 },
+        GameError::WrongCombination {
+            expected: Some((ThirteenCombination::Sequence, 4)),
+        },
+        GameError::WrongCombination { expected: None },
+        GameError::MissingCards {
+            cards: Hand::from_str("2H"),
+        },
+        GameError::NotEnoughPlayers { needed: 2 },
+        GameError::WrongPhase {
+            phase: Phase::Dealing,
+        },
+        GameError::NoSeat,
+    ];
+
+    for error in errors {
+        assert_eq!(GameError::from(&ErrorCode::from(error.clone())), error);
+    }
+}
@@ -1,4 +1,4 @@
-use naia_bevy_shared::Message;
+use naia_bevy_shared::{Message, Serde};
 
 #[derive(Message, Default, Debug)]
 pub struct PlayerMessage {
@@ -20,6 +20,24 @@ pub struct WaitForStart(pub usize);
 #[derive(Message, Default)]
 pub struct EndMatch(pub usize);
 
+/// A player's line in the results of a match
+#[derive(Serde, PartialEq, Clone, Debug)]
+pub struct Standing {
+    pub pos: usize,
+    /// Earned this match
+    pub points: u32,
+    /// Over the whole session
+    pub score: u32,
+    /// Revealed for the players who did not run out
+    pub cards_left: String,
+}
+
+/// Sent right before `EndMatch`, best player first
+#[derive(Message, Default, Debug)]
+pub struct MatchResults {
+    pub standings: Vec<Standing>,
+}
+
 #[derive(Message, Default)]
 pub struct AcceptStartGame {
     pub cards: String,
@@ -56,16 +74,44 @@ pub struct NewMatch {
 }
 
 #[derive(Message, Debug, Default)]
-pub struct NewPlayer(pub String);
+pub struct NewPlayer {
+    pub name: String,
+    /// Index of the avatar, below `AVATAR_COUNT`
+    pub avatar: usize,
+}
 
 #[derive(Message, Debug, Default)]
 pub struct PlayerReady;
 
+/// Moves to the empty seat at that position, only in the lobby
+#[derive(Message, Debug, Default)]
+pub struct TakeSeat(pub usize);
+
+/// The host removes the player at that position from the table
+#[derive(Message, Debug, Default)]
+pub struct KickPlayer(pub usize);
+
+/// The host hands the host role to the player at that position
+#[derive(Message, Debug, Default)]
+pub struct TransferHost(pub usize);
+
+/// Too few players are left to go on, the table waits in the lobby for more
+#[derive(Message, Debug, Default)]
+pub struct BackToLobby;
+
+/// The host turns the played cards tracker on or off
+#[derive(Message, Debug, Default)]
+pub struct SetCardTracker(pub bool);
+
 #[derive(Message, Debug, Default)]
 pub struct AcceptPlayerReady {
     pub name: String,
     pub server_pos: usize,
+    pub avatar: usize,
 }
 
 #[derive(Message, Debug, Default)]
 pub struct NewPlayerJoin;
+
+#[derive(Message, Debug, Default)]
+pub struct SystemMessage(pub String);
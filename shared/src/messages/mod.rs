@@ -10,13 +10,15 @@ mod player;
 
 pub use player::PlayerMessage;
 
-pub use auth::Auth;
+pub use auth::{Auth, IncompatibleVersion};
 pub use counter::Counter;
 pub use entity_assignment::EntityAssignment;
 pub use error::{ErrorCode, GameError};
 pub use game::{
-    AcceptPlayCard, AcceptPlayerReady, AcceptStartGame, EndMatch, NewMatch, NewPlayer, PlayCard,
-    PlayerReady, RequestStart, SkipTurn, StartGame, UpdateScore, UpdateTurn, WaitForStart,
+    AcceptPlayCard, AcceptPlayerReady, AcceptStartGame, BackToLobby, EndMatch, KickPlayer,
+    MatchResults, NewMatch, NewPlayer, PlayCard, PlayerReady, RequestStart, SetCardTracker,
+    SkipTurn, Standing, StartGame, SystemMessage, TakeSeat, TransferHost, UpdateScore, UpdateTurn,
+    WaitForStart,
 };
 pub use key_command::KeyCommand;
 
@@ -25,8 +27,13 @@ pub struct MessagesPlugin;
 
 impl ProtocolPlugin for MessagesPlugin {
     fn build(&self, protocol: &mut Protocol) {
+        // Auth and IncompatibleVersion stay first and keep their shape from
+        // protocol 0.2 on, so clients from then on can be told to update.
+        // Older clients send an Auth without a version the server can't
+        // decode, they are dropped without the notice
         protocol
             .add_message::<Auth>()
+            .add_message::<IncompatibleVersion>()
             .add_message::<EntityAssignment>()
             .add_message::<KeyCommand>()
             .add_message::<Counter>()
@@ -45,6 +52,13 @@ impl ProtocolPlugin for MessagesPlugin {
             .add_message::<SkipTurn>()
             .add_message::<PlayerMessage>()
             .add_message::<UpdateScore>()
-            .add_message::<ErrorCode>();
+            .add_message::<SystemMessage>()
+            .add_message::<ErrorCode>()
+            .add_message::<MatchResults>()
+            .add_message::<TakeSeat>()
+            .add_message::<KickPlayer>()
+            .add_message::<TransferHost>()
+            .add_message::<BackToLobby>()
+            .add_message::<SetCardTracker>();
     }
 }
@@ -4,11 +4,38 @@ use naia_bevy_shared::{LinkConditionerConfig, Protocol};
 
 use crate::{channels::ChannelsPlugin, components::ComponentsPlugin, messages::MessagesPlugin};
 
+/// Version of the wire protocol, the version of this crate. Bump the minor
+/// version (the major one after 1.0) whenever a message, channel or component
+/// changes, patch bumps stay compatible.
+pub const PROTOCOL_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Whether a peer speaking `version` understands this build
+pub fn is_compatible_version(version: &str) -> bool {
+    // Everything up to the patch number has to match
+    fn breaking(version: &str) -> Option<(&str, &str)> {
+        let mut parts = version.split('.');
+        Some((parts.next()?, parts.next()?))
+    }
+
+    match (breaking(version), breaking(PROTOCOL_VERSION)) {
+        (Some(theirs), Some(ours)) => theirs == ours,
+        _ => false,
+    }
+}
+
+/// Tick interval the client expects, servers started with another interval
+/// should ship clients built with the same value
+pub const DEFAULT_TICK_INTERVAL: Duration = Duration::from_millis(40);
+
 // Protocol Build
 pub fn protocol() -> Protocol {
+    protocol_with_tick_interval(DEFAULT_TICK_INTERVAL)
+}
+
+pub fn protocol_with_tick_interval(tick_interval: Duration) -> Protocol {
     Protocol::builder()
         // Config
-        .tick_interval(Duration::from_millis(40))
+        .tick_interval(tick_interval)
         .link_condition(LinkConditionerConfig::good_condition())
         .enable_client_authoritative_entities()
         // Channels